@@ -10,6 +10,7 @@ use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
 use crate::state::ADMIN_ADDR;
 use util_types::ExecuteMsg as CommonExecuteMsg;
 use util_types::InstantiateMsg as CommonInstantiateMsg;
+use util_types::{VestingParams, VestingSchedule};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:accounts_factory";
@@ -17,6 +18,11 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const USER_ACCOUNT_REPLY_ID: u64 = 0;
 const DEAR_LEADER_ACCOUNT_REPLY_ID: u64 = 1;
+const VESTING_ACCOUNT_REPLY_ID: u64 = 2;
+
+// pagination limits for the vesting accounts of an owner
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -43,6 +49,20 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::CreateUserAccount {} => execute::create_user_account(deps, env, info),
+        ExecuteMsg::CreateVestingAccount {
+            owner_addr,
+            schedule,
+            validator_addr,
+            clawback,
+        } => execute::create_vesting_account(
+            deps,
+            env,
+            info,
+            owner_addr,
+            schedule,
+            validator_addr,
+            clawback,
+        ),
         ExecuteMsg::CreateDearLeaderAccount { dear_leader_addr } => {
             execute::create_dear_leader_account(deps, env, info, dear_leader_addr)
         }
@@ -57,16 +77,25 @@ pub fn execute(
         ExecuteMsg::SetAssemblyAddr { assembly_addr } => {
             execute::set_assembly_addr(deps, env, info, assembly_addr)
         }
+        ExecuteMsg::SetProxyAllowList {
+            type_urls,
+            contracts,
+        } => execute::set_proxy_allow_list(deps, info, type_urls, contracts),
+        ExecuteMsg::UpdateDearLeaderAccountOwner {
+            previous_owner,
+            new_owner,
+        } => execute::update_dear_leader_account_owner(deps, info, previous_owner, new_owner),
     }
 }
 
 pub mod execute {
-    use cosmwasm_std::{SubMsg, WasmMsg};
+    use cosmwasm_std::{Coin, SubMsg, WasmMsg};
 
     use crate::state::{
         ADMIN_ADDR, ASSEMBLY_ADDR, DEAR_LEADER_ACCOUNTS_CODE_ID,
-        DEAR_LEADER_ACCOUNTS_UNDER_MANAGEMENT, STATE_MANAGEMENT_CACHE_WITHIN_TX,
-        USER_ACCOUNTS_CODE_ID, USER_ACCOUNTS_UNDER_MANAGEMENT,
+        DEAR_LEADER_ACCOUNTS_UNDER_MANAGEMENT, PROXY_ALLOWED_CONTRACTS, PROXY_ALLOWED_TYPE_URLS,
+        STATE_MANAGEMENT_CACHE_WITHIN_TX, USER_ACCOUNTS_CODE_ID, USER_ACCOUNTS_UNDER_MANAGEMENT,
+        VESTING_GRANTOR_CACHE_WITHIN_TX,
     };
 
     use super::*;
@@ -89,7 +118,8 @@ pub mod execute {
             return Err(ContractError::DearLeaderAccountAlreadyExists);
         }
 
-        STATE_MANAGEMENT_CACHE_WITHIN_TX.save(deps.storage, &info.sender.to_string())?;
+        // the account is indexed by its owner, the dear leader
+        STATE_MANAGEMENT_CACHE_WITHIN_TX.save(deps.storage, &validated_addr.to_string())?;
 
         // check if dear_leader_accounts_code_id is set
         let dear_leader_account_code_id = DEAR_LEADER_ACCOUNTS_CODE_ID
@@ -110,7 +140,12 @@ pub mod execute {
         let msg = WasmMsg::Instantiate {
             admin: Some(env.contract.address.to_string()),
             code_id: dear_leader_account_code_id,
-            msg: to_binary(&CommonInstantiateMsg::InstatiateDearLeaderAccount {})?,
+            // the account tells the factory about ownership transfers to keep the index
+            msg: to_binary(&CommonInstantiateMsg::InstatiateDearLeaderAccount {
+                owner_addr: validated_addr.to_string(),
+                assembly_addr,
+                accounts_factory_addr: Some(env.contract.address.to_string()),
+            })?,
             funds: vec![],
             label: info.sender.to_string() + "_user_account",
         };
@@ -123,6 +158,40 @@ pub mod execute {
             .add_submessage(submessage))
     }
 
+    pub fn update_dear_leader_account_owner(
+        deps: DepsMut,
+        info: MessageInfo,
+        previous_owner: String,
+        new_owner: String,
+    ) -> Result<Response, ContractError> {
+        // only the dear leader account itself can move its entry
+        let dear_leader_account_addr = DEAR_LEADER_ACCOUNTS_UNDER_MANAGEMENT
+            .may_load(deps.storage, previous_owner.clone())?
+            .ok_or(ContractError::DearLeaderAccountAddrNotFound)?;
+        if info.sender != dear_leader_account_addr {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        // validate new owner addr and check that the new owner don't have an account yet
+        let validated_addr = deps.api.addr_validate(&new_owner)?;
+        if DEAR_LEADER_ACCOUNTS_UNDER_MANAGEMENT.has(deps.storage, validated_addr.to_string()) {
+            return Err(ContractError::DearLeaderAccountAlreadyExists);
+        }
+
+        DEAR_LEADER_ACCOUNTS_UNDER_MANAGEMENT.remove(deps.storage, previous_owner.clone());
+        DEAR_LEADER_ACCOUNTS_UNDER_MANAGEMENT.save(
+            deps.storage,
+            validated_addr.to_string(),
+            &dear_leader_account_addr,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "update_dear_leader_account_owner")
+            .add_attribute("dear_leader_account_addr", dear_leader_account_addr)
+            .add_attribute("previous_owner", previous_owner)
+            .add_attribute("new_owner", validated_addr.to_string()))
+    }
+
     pub fn create_user_account(
         deps: DepsMut,
         env: Env,
@@ -136,23 +205,90 @@ pub mod execute {
         STATE_MANAGEMENT_CACHE_WITHIN_TX.save(deps.storage, &info.sender.to_string())?;
 
         // create user account with instantiate msg with ReplyOn::Success
+        let submessage = instantiate_user_account_msg(
+            deps.as_ref(),
+            &env,
+            info.sender.to_string(),
+            None,
+            vec![],
+            USER_ACCOUNT_REPLY_ID,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "create_user_account")
+            .add_attribute("created_by", info.sender.to_string())
+            .add_submessage(submessage))
+    }
+
+    pub fn create_vesting_account(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        owner_addr: String,
+        schedule: VestingSchedule,
+        validator_addr: String,
+        clawback: bool,
+    ) -> Result<Response, ContractError> {
+        // validate owner addr, the account is registered apart from the owner user account
+        let validated_addr = deps.api.addr_validate(&owner_addr)?;
+
+        STATE_MANAGEMENT_CACHE_WITHIN_TX.save(deps.storage, &validated_addr.to_string())?;
+        VESTING_GRANTOR_CACHE_WITHIN_TX.save(deps.storage, &info.sender.to_string())?;
+
+        // the sender is the grantor, the user account checks the schedule and the funds
+        let vesting = VestingParams {
+            grantor: info.sender.to_string(),
+            schedule,
+            validator_addr,
+            clawback,
+        };
+        let submessage = instantiate_user_account_msg(
+            deps.as_ref(),
+            &env,
+            validated_addr.to_string(),
+            Some(vesting),
+            info.funds,
+            VESTING_ACCOUNT_REPLY_ID,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "create_vesting_account")
+            .add_attribute("grantor", info.sender.to_string())
+            .add_attribute("owner_addr", validated_addr.to_string())
+            .add_submessage(submessage))
+    }
+
+    fn instantiate_user_account_msg(
+        deps: Deps,
+        env: &Env,
+        boss_addr: String,
+        vesting: Option<VestingParams>,
+        funds: Vec<Coin>,
+        reply_id: u64,
+    ) -> Result<SubMsg, ContractError> {
         let user_account_code_id = USER_ACCOUNTS_CODE_ID.load(deps.storage).unwrap_or_default();
         if user_account_code_id == 0 {
             return Err(ContractError::UserAccountCodeIdNotSet);
         }
+
+        // ensure assembly addr is registered
+        let assembly_addr = ASSEMBLY_ADDR.may_load(deps.storage)?.unwrap_or_default();
+        if assembly_addr.is_empty() {
+            return Err(ContractError::AssemblyAddrNotSet);
+        }
+
         let instantiate_msg = WasmMsg::Instantiate {
             admin: Some(env.contract.address.to_string()),
             code_id: user_account_code_id,
-            msg: to_binary(&CommonInstantiateMsg::InstantiateUserAccountMsg {})?,
-            funds: vec![],
-            label: info.sender.to_string() + "_user_account",
+            msg: to_binary(&CommonInstantiateMsg::InstantiateUserAccountMsg {
+                boss_addr: boss_addr.clone(),
+                assembly_addr,
+                vesting,
+            })?,
+            funds,
+            label: boss_addr + "_user_account",
         };
-        let submessage: SubMsg = SubMsg::reply_on_success(instantiate_msg, USER_ACCOUNT_REPLY_ID);
-
-        Ok(Response::new()
-            .add_attribute("action", "create_user_account")
-            .add_attribute("created_by", info.sender.to_string())
-            .add_submessage(submessage))
+        Ok(SubMsg::reply_on_success(instantiate_msg, reply_id))
     }
 
     pub fn set_user_accounts_code_id(
@@ -223,6 +359,34 @@ pub mod execute {
             .add_attribute("set_by", info.sender.to_string())
             .add_attribute("assembly_addr", validated_addr.to_string()))
     }
+
+    pub fn set_proxy_allow_list(
+        deps: DepsMut,
+        info: MessageInfo,
+        type_urls: Vec<String>,
+        contracts: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        // this type of config can only be set by the admin of the contract
+        let admin_addr = ADMIN_ADDR.load(deps.storage)?;
+        if info.sender != admin_addr {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        // validate contract addrs
+        let contracts = contracts
+            .iter()
+            .map(|contract| Ok(deps.api.addr_validate(contract)?.to_string()))
+            .collect::<Result<Vec<String>, ContractError>>()?;
+
+        PROXY_ALLOWED_TYPE_URLS.save(deps.storage, &type_urls)?;
+        PROXY_ALLOWED_CONTRACTS.save(deps.storage, &contracts)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_proxy_allow_list")
+            .add_attribute("set_by", info.sender.to_string())
+            .add_attribute("type_urls", type_urls.join(","))
+            .add_attribute("contracts", contracts.join(",")))
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -238,6 +402,12 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         }
         QueryMsg::GetAdminAddr {} => query::get_admin_addr(deps),
         QueryMsg::GetConfig {} => query::get_config(deps),
+        QueryMsg::GetVestingAccounts {
+            owner_addr,
+            start_after,
+            limit,
+        } => query::get_vesting_accounts(deps, owner_addr, start_after, limit),
+        QueryMsg::GetProxyAllowList {} => query::get_proxy_allow_list(deps),
     }
 }
 
@@ -246,6 +416,7 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
     match msg.id {
         USER_ACCOUNT_REPLY_ID => reply::instantiate_user_account_reply(deps, msg),
         DEAR_LEADER_ACCOUNT_REPLY_ID => reply::instantiate_dear_leader_account_reply(deps, msg),
+        VESTING_ACCOUNT_REPLY_ID => reply::instantiate_vesting_account_reply(deps, msg),
         _ => Err(ContractError::UnknownReplyIdCommon {}),
     }
 }
@@ -253,7 +424,7 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 pub mod reply {
     use crate::state::{
         ASSEMBLY_ADDR, DEAR_LEADER_ACCOUNTS_UNDER_MANAGEMENT, STATE_MANAGEMENT_CACHE_WITHIN_TX,
-        USER_ACCOUNTS_UNDER_MANAGEMENT,
+        USER_ACCOUNTS_UNDER_MANAGEMENT, VESTING_ACCOUNTS, VESTING_GRANTOR_CACHE_WITHIN_TX,
     };
 
     use super::*;
@@ -313,17 +484,45 @@ pub mod reply {
         Ok(Response::default()
             .add_attribute("instantiated_user_account", user_account_addr.to_string()))
     }
+
+    pub fn instantiate_vesting_account_reply(
+        deps: DepsMut,
+        msg: Reply,
+    ) -> Result<Response, ContractError> {
+        let res = parse_reply_instantiate_data(msg)?;
+        let user_account_addr = deps.api.addr_validate(&res.contract_address)?;
+
+        // add vesting account to the owner vesting accounts
+        let owner_addr = STATE_MANAGEMENT_CACHE_WITHIN_TX.load(deps.storage)?;
+        let grantor = VESTING_GRANTOR_CACHE_WITHIN_TX.load(deps.storage)?;
+        VESTING_ACCOUNTS.save(
+            deps.storage,
+            (owner_addr, user_account_addr.to_string()),
+            &grantor,
+        )?;
+
+        Ok(Response::default().add_attribute(
+            "instantiated_vesting_account",
+            user_account_addr.to_string(),
+        ))
+    }
 }
 
 pub mod query {
 
     use cw_paginate::{paginate_map_keys, paginate_map_values};
+    use cw_storage_plus::Bound;
+    use util_types::GetProxyAllowListResponse;
 
     use crate::{
-        msg::{GetConfigResponse, GetOwnsersListResponse, GetUserAccountListResponse},
+        msg::{
+            GetConfigResponse, GetOwnsersListResponse, GetUserAccountListResponse,
+            GetVestingAccountsResponse,
+        },
         state::{
             ASSEMBLY_ADDR, DEAR_LEADER_ACCOUNTS_CODE_ID, DEAR_LEADER_ACCOUNTS_UNDER_MANAGEMENT,
-            USER_ACCOUNTS_CODE_ID, USER_ACCOUNTS_UNDER_MANAGEMENT,
+            PROXY_ALLOWED_CONTRACTS, PROXY_ALLOWED_TYPE_URLS, USER_ACCOUNTS_CODE_ID,
+            USER_ACCOUNTS_UNDER_MANAGEMENT, VESTING_ACCOUNTS,
         },
     };
 
@@ -369,6 +568,28 @@ pub mod query {
         to_binary(&dear_leader_account_addr.unwrap().unwrap())
     }
 
+    // anyone can create vesting accounts for an owner, so they are always paged
+    pub fn get_vesting_accounts(
+        deps: Deps,
+        owner_addr: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let vesting_accounts = VESTING_ACCOUNTS
+            .prefix(owner_addr)
+            .keys(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                cosmwasm_std::Order::Ascending,
+            )
+            .take(limit)
+            .collect::<StdResult<Vec<String>>>()?;
+
+        to_binary(&GetVestingAccountsResponse { vesting_accounts })
+    }
+
     pub fn get_admin_addr(deps: Deps) -> StdResult<Binary> {
         let admin_addr = ADMIN_ADDR.load(deps.storage)?;
 
@@ -392,4 +613,18 @@ pub mod query {
 
         to_binary(&resp)
     }
+
+    pub fn get_proxy_allow_list(deps: Deps) -> StdResult<Binary> {
+        let type_urls = PROXY_ALLOWED_TYPE_URLS
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        let contracts = PROXY_ALLOWED_CONTRACTS
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+
+        to_binary(&GetProxyAllowListResponse {
+            type_urls,
+            contracts,
+        })
+    }
 }
@@ -2,12 +2,24 @@
 mod tests {
     use std::borrow::BorrowMut;
 
-    use cosmwasm_std::{coins, Addr, Coin, Empty, Uint128};
+    use cosmwasm_std::{
+        coins, Addr, Binary, Coin, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+        Timestamp, Uint128,
+    };
     use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+    use dear_leader_account::msg::{
+        ExecuteMsg as DearLeaderExecuteMsg, GetConfigResponse as GetDearLeaderConfigResponse,
+        QueryMsg as DearLeaderQueryMsg,
+    };
+    use util_types::{
+        ExecuteMsg as CommonExecuteMsg, InstantiateMsg as CommonInstantiateMsg, VestingSchedule,
+    };
 
     use crate::{
-        contract::{execute, instantiate, query},
-        msg::{GetConfigResponse, InstantiateMsg},
+        contract::{execute, instantiate, query, reply},
+        msg::{
+            ExecuteMsg, GetConfigResponse, GetVestingAccountsResponse, InstantiateMsg, QueryMsg,
+        },
     };
 
     const ADMIN: &str = "admin";
@@ -17,7 +29,44 @@ mod tests {
     const USED_DENOM: &str = "Juno";
 
     fn accounts_factory_contract() -> Box<dyn Contract<Empty>> {
-        let contract = ContractWrapper::new(execute, instantiate, query);
+        let contract = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+        Box::new(contract)
+    }
+
+    // the factory only needs the user account to instantiate
+    fn stub_user_account_contract() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+                Ok(Response::default())
+            },
+            |_: DepsMut, _: Env, _: MessageInfo, _: CommonInstantiateMsg| -> StdResult<Response> {
+                Ok(Response::default())
+            },
+            |_: Deps, _: Env, _: Empty| -> StdResult<Binary> { Ok(Binary::default()) },
+        );
+        Box::new(contract)
+    }
+
+    fn dear_leader_account_contract() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            dear_leader_account::contract::execute,
+            dear_leader_account::contract::instantiate,
+            dear_leader_account::contract::query,
+        );
+        Box::new(contract)
+    }
+
+    // takes the dear leader registration without checking it
+    fn stub_assembly_contract() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            |_: DepsMut, _: Env, _: MessageInfo, _: CommonExecuteMsg| -> StdResult<Response> {
+                Ok(Response::default())
+            },
+            |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+                Ok(Response::default())
+            },
+            |_: Deps, _: Env, _: Empty| -> StdResult<Binary> { Ok(Binary::default()) },
+        );
         Box::new(contract)
     }
 
@@ -114,4 +163,208 @@ mod tests {
         assert_eq!(config.dear_leader_accounts_code_id, 70);
         assert_eq!(config.assembly_addr, ASSEMBLY_ADDR);
     }
+
+    #[test]
+    fn vesting_accounts_dont_take_the_owner_slot() {
+        let mut app = App::default();
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(
+                    storage,
+                    &Addr::unchecked("grantor"),
+                    coins(2000, USED_DENOM),
+                )
+                .unwrap()
+        });
+
+        let user_account_code_id = app.store_code(stub_user_account_contract());
+        let accounts_factory_code_id = app.store_code(accounts_factory_contract());
+        let accounts_factory = app
+            .instantiate_contract(
+                accounts_factory_code_id,
+                Addr::unchecked(ADMIN),
+                &InstantiateMsg {},
+                &[],
+                "accounts_factory",
+                None,
+            )
+            .unwrap();
+        for msg in [
+            ExecuteMsg::SetUserAccountsCodeId {
+                user_accounts_code_id: user_account_code_id,
+            },
+            ExecuteMsg::SetAssemblyAddr {
+                assembly_addr: ASSEMBLY_ADDR.to_string(),
+            },
+        ] {
+            app.execute_contract(Addr::unchecked(ADMIN), accounts_factory.clone(), &msg, &[])
+                .unwrap();
+        }
+
+        // any grantor can fund vesting accounts for the owner
+        for _ in 0..2 {
+            app.execute_contract(
+                Addr::unchecked("grantor"),
+                accounts_factory.clone(),
+                &ExecuteMsg::CreateVestingAccount {
+                    owner_addr: "owner".to_string(),
+                    schedule: VestingSchedule::CliffLinear {
+                        total: Uint128::new(1000),
+                        start: Timestamp::from_seconds(0),
+                        cliff: Timestamp::from_seconds(100),
+                        end: Timestamp::from_seconds(1000),
+                    },
+                    validator_addr: "validator".to_string(),
+                    clawback: false,
+                },
+                &coins(1000, USED_DENOM),
+            )
+            .unwrap();
+        }
+        let vesting_accounts = |app: &App, start_after: Option<String>| {
+            app.wrap()
+                .query_wasm_smart::<GetVestingAccountsResponse>(
+                    accounts_factory.clone(),
+                    &QueryMsg::GetVestingAccounts {
+                        owner_addr: "owner".to_string(),
+                        start_after,
+                        limit: Some(1),
+                    },
+                )
+                .unwrap()
+                .vesting_accounts
+        };
+        let first_page = vesting_accounts(&app, None);
+        assert_eq!(first_page.len(), 1);
+        let second_page = vesting_accounts(&app, first_page.last().cloned());
+        assert_eq!(second_page.len(), 1);
+        assert_ne!(first_page, second_page);
+        assert!(vesting_accounts(&app, second_page.last().cloned()).is_empty());
+
+        // and the owner can still create their own account
+        app.execute_contract(
+            Addr::unchecked("owner"),
+            accounts_factory,
+            &ExecuteMsg::CreateUserAccount {},
+            &[],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn dear_leader_account_transfer_moves_the_owner_index() {
+        let mut app = App::default();
+
+        let dear_leader_account_code_id = app.store_code(dear_leader_account_contract());
+        let assembly_code_id = app.store_code(stub_assembly_contract());
+        let assembly = app
+            .instantiate_contract(
+                assembly_code_id,
+                Addr::unchecked(ADMIN),
+                &Empty {},
+                &[],
+                "assembly",
+                None,
+            )
+            .unwrap();
+        let accounts_factory_code_id = app.store_code(accounts_factory_contract());
+        let accounts_factory = app
+            .instantiate_contract(
+                accounts_factory_code_id,
+                Addr::unchecked(ADMIN),
+                &InstantiateMsg {},
+                &[],
+                "accounts_factory",
+                None,
+            )
+            .unwrap();
+        for msg in [
+            ExecuteMsg::SetDearLeaderAccountsCodeId {
+                dear_leader_accounts_code_id: dear_leader_account_code_id,
+            },
+            ExecuteMsg::SetAssemblyAddr {
+                assembly_addr: assembly.to_string(),
+            },
+            ExecuteMsg::CreateDearLeaderAccount {
+                dear_leader_addr: "leader".to_string(),
+            },
+        ] {
+            app.execute_contract(Addr::unchecked(ADMIN), accounts_factory.clone(), &msg, &[])
+                .unwrap();
+        }
+
+        let get_account = |app: &App, owner_addr: &str| -> StdResult<String> {
+            app.wrap().query_wasm_smart(
+                accounts_factory.clone(),
+                &QueryMsg::GetDearLeaderAccountAddr {
+                    owner_addr: owner_addr.to_string(),
+                },
+            )
+        };
+        let dear_leader_account = Addr::unchecked(get_account(&app, "leader").unwrap());
+
+        // the account is owned by the dear leader and knows the factory
+        let config: GetDearLeaderConfigResponse = app
+            .wrap()
+            .query_wasm_smart(
+                dear_leader_account.clone(),
+                &DearLeaderQueryMsg::GetConfig {},
+            )
+            .unwrap();
+        assert_eq!(config.owner, "leader");
+        assert_eq!(
+            config.accounts_factory_addr,
+            Some(accounts_factory.to_string())
+        );
+
+        // only the dear leader account can move its entry
+        app.execute_contract(
+            Addr::unchecked("leader"),
+            accounts_factory.clone(),
+            &ExecuteMsg::UpdateDearLeaderAccountOwner {
+                previous_owner: "leader".to_string(),
+                new_owner: "successor".to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        // accepting the transfer moves the factory entry to the successor
+        app.execute_contract(
+            Addr::unchecked("leader"),
+            dear_leader_account.clone(),
+            &DearLeaderExecuteMsg::TransferOwnership {
+                new_owner: "successor".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked("successor"),
+            dear_leader_account.clone(),
+            &DearLeaderExecuteMsg::AcceptOwnership {},
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            get_account(&app, "successor").unwrap(),
+            dear_leader_account.to_string()
+        );
+
+        // the previous owner can get a new account
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            accounts_factory.clone(),
+            &ExecuteMsg::CreateDearLeaderAccount {
+                dear_leader_addr: "leader".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+        assert_ne!(
+            get_account(&app, "leader").unwrap(),
+            dear_leader_account.to_string()
+        );
+    }
 }
@@ -1,15 +1,34 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use util_types::VestingSchedule;
 
 #[cw_serde]
 pub struct InstantiateMsg {}
 
 #[cw_serde]
+#[rustfmt::skip]
 pub enum ExecuteMsg {
     CreateUserAccount {},
+    // creates a vesting user account for owner_addr holding the sent tokens, staked and vesting.
+    // It doesn't count as the owner user account, the owner can still create one
+    CreateVestingAccount {
+        owner_addr: String,
+        schedule: VestingSchedule,
+        validator_addr: String,
+        clawback: bool,
+    },
     CreateDearLeaderAccount { dear_leader_addr: String },
     SetUserAccountsCodeId { user_accounts_code_id: u64 },
     SetDearLeaderAccountsCodeId { dear_leader_accounts_code_id: u64 },
     SetAssemblyAddr { assembly_addr: String },
+    SetProxyAllowList {
+        type_urls: Vec<String>,
+        contracts: Vec<String>,
+    },
+    // sent by a dear leader account once its new owner accepted the transfer
+    UpdateDearLeaderAccountOwner {
+        previous_owner: String,
+        new_owner: String,
+    },
 }
 
 #[cw_serde]
@@ -38,6 +57,18 @@ pub enum QueryMsg {
     // return user_accounts_code_id, dear_leader_accounts_code_id and assembly_addr
     #[returns(GetConfigResponse)]
     GetConfig {},
+
+    // given the owner address, returns a page of the vesting user accounts grantors created for it
+    #[returns(GetVestingAccountsResponse)]
+    GetVestingAccounts {
+        owner_addr: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    // returns the stargate type urls and contracts user accounts can proxy messages to
+    #[returns(util_types::GetProxyAllowListResponse)]
+    GetProxyAllowList {},
 }
 
 #[cw_serde]
@@ -55,6 +86,11 @@ pub struct GetUserAccountAddrResponse {
     pub user_account_addr: String,
 }
 
+#[cw_serde]
+pub struct GetVestingAccountsResponse {
+    pub vesting_accounts: Vec<String>,
+}
+
 #[cw_serde]
 pub struct GetDearLeaderAccountResponse {
     pub dear_leader_account_addr: String,
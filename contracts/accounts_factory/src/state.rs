@@ -13,8 +13,19 @@ pub const DEAR_LEADER_ACCOUNTS_UNDER_MANAGEMENT: Map<String, String> =
 pub const STATE_MANAGEMENT_CACHE_WITHIN_TX: Item<String> =
     Item::new("state_management_cache_within_tx");
 
+// (owner, vesting user_account)/grantor. Anyone can fund a vesting account for an owner,
+// so they don't take the owner slot in USER_ACCOUNTS_UNDER_MANAGEMENT.
+pub const VESTING_ACCOUNTS: Map<(String, String), String> = Map::new("vesting_accounts");
+
+pub const VESTING_GRANTOR_CACHE_WITHIN_TX: Item<String> =
+    Item::new("vesting_grantor_cache_within_tx");
+
 pub const USER_ACCOUNTS_CODE_ID: Item<u64> = Item::new("user_accounts_code_id");
 
 pub const DEAR_LEADER_ACCOUNTS_CODE_ID: Item<u64> = Item::new("dear_leader_accounts_code_id");
 
 pub const ASSEMBLY_ADDR: Item<String> = Item::new("assembly_addr");
+
+// stargate type urls and contracts user accounts can send through their generic Execute.
+pub const PROXY_ALLOWED_TYPE_URLS: Item<Vec<String>> = Item::new("proxy_allowed_type_urls");
+pub const PROXY_ALLOWED_CONTRACTS: Item<Vec<String>> = Item::new("proxy_allowed_contracts");
@@ -1,4 +1,4 @@
-use assembly::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use assembly::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 use cosmwasm_schema::write_api;
 
 // use multi_contract_boilerplate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
@@ -8,5 +8,6 @@ fn main() {
         instantiate: InstantiateMsg,
         execute: ExecuteMsg,
         query: QueryMsg,
+        migrate: MigrateMsg,
     }
 }
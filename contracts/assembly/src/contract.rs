@@ -1,17 +1,47 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
-use cw2::set_contract_version;
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult, Storage,
+    Timestamp, Uint128,
+};
+use cw2::{get_contract_version, set_contract_version};
 use util_types::ExecuteMsg as CommonExecuteMsg;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{ADMIN_ADDR, DEAR_LEADER_BOARD, PROPOSAL_VOTE_HISTORY};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use crate::state::{
+    RegisteredProposal, ADMIN_ADDR, ANNOUNCED_VOTES, DEAR_LEADERS, DEAR_LEADER_DELEGATORS,
+    DEAR_LEADER_POWER, DEAR_LEADER_VOTES, LEADER_DELEGATIONS, LEADER_FOLLOWERS,
+    PROPOSAL_VOTE_HISTORY, REVIEW_WINDOW,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:assembly";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// review window used until the admin sets one, one day
+const DEFAULT_REVIEW_WINDOW: u64 = 86_400;
+
+// ApplyFallbacks can be used this long before the voting end, six hours
+const FALLBACK_WINDOW: u64 = 21_600;
+
+// vote option user accounts cast as abstain
+const ABSTAIN_VOTE_OPTION: u64 = 3;
+
+// hops a vote can travel through dear leaders following each other, longer chains are cut
+const MAX_DELEGATION_DEPTH: usize = 3;
+
+// length of the periods delegator churn is grouped by, one week
+const CHURN_PERIOD: u64 = 604_800;
+
+// pagination limits for queries and cranks
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+// delegators and votes moved to composite keys per migration page
+const DEFAULT_MIGRATION_LIMIT: u32 = 100;
+const MAX_MIGRATION_LIMIT: u32 = 500;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -29,6 +59,27 @@ pub fn instantiate(
         .add_attribute("done_by", info.sender.to_string()))
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    if get_contract_version(deps.storage)?.contract != CONTRACT_NAME {
+        return Err(ContractError::CannotMigrate {});
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // the first page moves with the upgrade, MigrateCompositeKeys moves the rest
+    let limit = msg
+        .limit
+        .unwrap_or(DEFAULT_MIGRATION_LIMIT)
+        .min(MAX_MIGRATION_LIMIT) as usize;
+    let (migrated, done) = migration::migrate_to_composite_keys(deps, env.block.height, limit)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("version", CONTRACT_VERSION)
+        .add_attribute("migrated_entries", migrated.to_string())
+        .add_attribute("migration_done", done.to_string()))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -40,11 +91,42 @@ pub fn execute(
         ExecuteMsg::UserAccountVote {
             proposal_id,
             vote_option,
-        } => execute::user_account_vote(deps, info, proposal_id, vote_option),
+        } => execute::user_account_vote(deps, env, info, proposal_id, vote_option),
         ExecuteMsg::DearLeaderVote {
             proposal_id,
             vote_option,
-        } => execute::dear_leader_vote(deps, info, proposal_id, vote_option),
+            rationale,
+        } => execute::dear_leader_vote(deps, env, info, proposal_id, vote_option, rationale),
+        ExecuteMsg::AnnounceDearLeaderVote {
+            proposal_id,
+            vote_option,
+            rationale,
+        } => {
+            execute::announce_dear_leader_vote(deps, env, info, proposal_id, vote_option, rationale)
+        }
+        ExecuteMsg::ExecuteAnnouncedVote {
+            dear_leader_addr,
+            proposal_id,
+        } => execute::execute_announced_vote(deps, env, dear_leader_addr, proposal_id),
+        ExecuteMsg::DeliverDearLeaderVote {
+            dear_leader_addr,
+            proposal_id,
+            start_after,
+            limit,
+        } => execute::deliver_dear_leader_vote(
+            deps,
+            env,
+            dear_leader_addr,
+            proposal_id,
+            start_after,
+            limit,
+        ),
+        ExecuteMsg::OptOutOfLeaderVote { proposal_id } => {
+            execute::opt_out_of_leader_vote(deps, info, proposal_id)
+        }
+        ExecuteMsg::SetReviewWindow { review_window } => {
+            execute::set_review_window(deps, info, review_window)
+        }
         ExecuteMsg::TransferVotePower { dear_leader_addr } => {
             execute::transfer_vote_power(deps, env, info, dear_leader_addr)
         }
@@ -52,37 +134,223 @@ pub fn execute(
         ExecuteMsg::RegisterDearLeader {
             new_dear_leader_addr,
         } => execute::register_new_dear_leader(deps, info, new_dear_leader_addr),
-        ExecuteMsg::RegisterUserAccount {} => execute::register_user_account(deps, info),
-        ExecuteMsg::UnregisterUserAccount {} => execute::unregister_user_account(deps, info),
+        ExecuteMsg::RegisterUserAccount {} => execute::register_user_account(deps, env, info),
+        ExecuteMsg::UnregisterUserAccount {} => execute::unregister_user_account(deps, env, info),
         ExecuteMsg::SetAccountFactoryAddr {
             account_factory_addr,
         } => execute::set_account_factory_addr(deps, env, info, account_factory_addr),
+        ExecuteMsg::SetMaxValidatorCommission { max_commission } => {
+            execute::set_max_validator_commission(deps, info, max_commission)
+        }
+        ExecuteMsg::UpdateValidatorAllowList { to_add, to_remove } => {
+            execute::update_validator_allow_list(deps, info, to_add, to_remove)
+        }
+        ExecuteMsg::SyncAccountStake {} => execute::sync_account_stake(deps, env, info),
+        ExecuteMsg::SyncAccounts { start_after, limit } => {
+            execute::sync_accounts(deps, env, start_after, limit)
+        }
+        ExecuteMsg::SetDearLeaderProfile { profile } => {
+            execute::set_dear_leader_profile(deps, info, profile)
+        }
+        ExecuteMsg::RetireDearLeader {} => execute::retire_dear_leader(deps, env, info),
+        ExecuteMsg::ReleaseRetiredDelegators {
+            dear_leader_addr,
+            limit,
+        } => execute::release_retired_delegators(deps, env, dear_leader_addr, limit),
+        ExecuteMsg::SyncProposal { proposal_id } => execute::sync_proposal(deps, env, proposal_id),
+        ExecuteMsg::MigrateCompositeKeys { limit } => {
+            execute::migrate_composite_keys(deps, env, limit)
+        }
+        ExecuteMsg::SetVoteFallback { fallback } => {
+            execute::set_vote_fallback(deps, info, fallback)
+        }
+        ExecuteMsg::ApplyFallbacks {
+            proposal_id,
+            start_after,
+            limit,
+        } => execute::apply_fallbacks(deps, env, proposal_id, start_after, limit),
+        ExecuteMsg::FollowDearLeader {
+            dear_leader_addr,
+            categories,
+        } => execute::follow_dear_leader(deps, env, info, dear_leader_addr, categories),
+        ExecuteMsg::UnfollowDearLeader { dear_leader_addr } => {
+            execute::unfollow_dear_leader(deps, env, info, dear_leader_addr)
+        }
+    }
+}
+
+// dear leader the follower defers the category to, the one it follows on every proposal otherwise
+fn followed_leader(
+    storage: &dyn Storage,
+    follower: &str,
+    category: &str,
+) -> StdResult<Option<String>> {
+    let delegations = LEADER_DELEGATIONS
+        .may_load(storage, follower.to_string())?
+        .unwrap_or_default();
+    let for_category = delegations.iter().find(|delegation| {
+        delegation
+            .categories
+            .as_ref()
+            .map_or(false, |categories| categories.iter().any(|c| c == category))
+    });
+    let for_all = delegations
+        .iter()
+        .find(|delegation| delegation.categories.is_none());
+
+    Ok(for_category
+        .or(for_all)
+        .map(|delegation| delegation.dear_leader.clone()))
+}
+
+// dear leaders whose chain on the category ends with this one, up to MAX_DELEGATION_DEPTH hops.
+// Without a category only the dear leaders following on every proposal count. Dear leaders that
+// already voted on the proposal keep their own vote, and so do the ones following them.
+fn transitive_followers(
+    storage: &dyn Storage,
+    dear_leader: &str,
+    category: Option<&str>,
+    voted_on: Option<u64>,
+) -> StdResult<Vec<String>> {
+    let mut followers = vec![];
+    // dear leaders already reached are skipped, so cycles end there
+    let mut visited = vec![dear_leader.to_string()];
+    let mut current = vec![dear_leader.to_string()];
+    for _ in 0..MAX_DELEGATION_DEPTH {
+        let mut next = vec![];
+        for followed in current.iter() {
+            let candidates = LEADER_FOLLOWERS
+                .prefix(followed.to_string())
+                .keys(storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<String>>>()?;
+            for follower in candidates {
+                if visited.contains(&follower) {
+                    continue;
+                }
+                let follows = match category {
+                    Some(category) => {
+                        followed_leader(storage, &follower, category)?.as_ref() == Some(followed)
+                    }
+                    None => LEADER_DELEGATIONS
+                        .may_load(storage, follower.to_string())?
+                        .unwrap_or_default()
+                        .iter()
+                        .any(|delegation| {
+                            &delegation.dear_leader == followed && delegation.categories.is_none()
+                        }),
+                };
+                let voted = voted_on.map_or(false, |proposal_id| {
+                    DEAR_LEADER_VOTES.has(storage, (follower.to_string(), proposal_id))
+                });
+                if follows && !voted {
+                    visited.push(follower.to_string());
+                    next.push(follower);
+                }
+            }
+        }
+        followers.extend(next.iter().cloned());
+        current = next;
+    }
+
+    Ok(followers)
+}
+
+// power of the dear leaders following this one on every proposal
+fn delegated_power(storage: &dyn Storage, dear_leader: &str) -> StdResult<Uint128> {
+    transitive_followers(storage, dear_leader, None, None)?
+        .iter()
+        .map(|follower| {
+            DEAR_LEADER_POWER
+                .may_load(storage, follower.to_string())
+                .map(Option::unwrap_or_default)
+        })
+        .sum()
+}
+
+// the dear leader, or one it follows on the category, voted on the proposal or announced a vote
+// that can't be cast yet and will be castable before the voting ends
+fn leader_chain_votes(
+    storage: &dyn Storage,
+    dear_leader: &str,
+    proposal: &RegisteredProposal,
+    now: Timestamp,
+) -> StdResult<bool> {
+    let proposal_id = proposal.proposal_id;
+    let mut leader = dear_leader.to_string();
+    let mut visited = vec![];
+    for _ in 0..=MAX_DELEGATION_DEPTH {
+        let pending_announcement = ANNOUNCED_VOTES
+            .may_load(storage, (leader.clone(), proposal_id))?
+            .map_or(false, |announcement| {
+                now < announcement.execute_at && announcement.execute_at < proposal.voting_end_time
+            });
+        if pending_announcement || DEAR_LEADER_VOTES.has(storage, (leader.clone(), proposal_id)) {
+            return Ok(true);
+        }
+        visited.push(leader.clone());
+        match followed_leader(storage, &leader, &proposal.content_type)? {
+            Some(next) if !visited.contains(&next) => leader = next,
+            _ => return Ok(false),
+        }
     }
+
+    Ok(false)
 }
 
 pub mod execute {
 
-    use cosmwasm_std::WasmMsg;
+    use cosmwasm_std::{Addr, Decimal, Event, Order, Storage, Uint128, WasmMsg};
+    use cw_storage_plus::Bound;
+    use util_types::proto::query_gov_proposal;
+    use util_types::{
+        stake_delta, DearLeaderProfile, DearLeaderVote, ProposalStatus, VoteFallback, VoteRationale,
+    };
 
     use crate::state::{
-        BOSS_VOTE_POWER, DEAR_LEADER_ACCOUNT_FACTORY, DEAR_LEADER_BOARD, PROPOSAL_VOTE_HISTORY,
+        ChurnPeriod, LeaderDelegation, LeaderStats, VoteAnnouncement, ACCOUNT_STAKE,
+        ANNOUNCED_VOTES, BOSS_VOTE_POWER, DEAR_LEADER_ACCOUNT_FACTORY, DEAR_LEADER_DELEGATED_POWER,
+        DEAR_LEADER_POWER, DEAR_LEADER_PROFILES, FALLBACK_VOTES, LEADERBOARD_KEYS,
+        LEADERS_BY_DELEGATORS, LEADERS_BY_POWER, LEADER_CHURN, LEADER_STATS, LEADER_VOTE_OPT_OUTS,
+        MAX_VALIDATOR_COMMISSION, PROPOSALS, PROPOSAL_COUNT, RETIRING_DEAR_LEADERS,
+        VALIDATOR_ALLOW_LIST, VOTE_FALLBACKS,
     };
 
     use super::*;
 
     pub fn unregister_user_account(
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
     ) -> Result<Response, ContractError> {
         // check if user accout is registered, and if so, unregister it
-        BOSS_VOTE_POWER.update(deps.storage, info.sender.to_string(), |vote_power| {
-            if vote_power.is_some() {
-                // unregister account
-                Ok(None)
-            } else {
-                Err(ContractError::AccountNotRegistered {})
-            }
-        })?;
+        let dear_leader = BOSS_VOTE_POWER
+            .may_load(deps.storage, info.sender.to_string())?
+            .ok_or(ContractError::AccountNotRegistered {})?;
+
+        // the account stake stops counting for its dear leader
+        let stake = ACCOUNT_STAKE
+            .may_load(deps.storage, info.sender.to_string())?
+            .unwrap_or_default();
+        if let Some(dear_leader) = dear_leader {
+            remove_supporter(
+                deps.storage,
+                env.block.time,
+                env.block.height,
+                &dear_leader,
+                &info.sender,
+            )?;
+            update_leader_power(
+                deps.storage,
+                env.block.height,
+                &dear_leader,
+                stake,
+                Uint128::zero(),
+            )?;
+        }
+
+        ACCOUNT_STAKE.remove(deps.storage, info.sender.to_string(), env.block.height)?;
+        BOSS_VOTE_POWER.remove(deps.storage, info.sender.to_string(), env.block.height)?;
+        VOTE_FALLBACKS.remove(deps.storage, info.sender.to_string());
 
         Ok(Response::default()
             .add_attribute("action", "unregister_user_account")
@@ -91,22 +359,32 @@ pub mod execute {
 
     pub fn register_user_account(
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
     ) -> Result<Response, ContractError> {
         // make sure the user account is NOT already registered
         // register new user account
-        BOSS_VOTE_POWER.update(deps.storage, info.sender.to_string(), |vote_power| {
-            if vote_power.is_none() {
-                // register account
-                Ok(None)
-            } else {
-                Err(ContractError::UserAccountAlreadyRegister {})
-            }
-        })?;
+        BOSS_VOTE_POWER.update(
+            deps.storage,
+            info.sender.to_string(),
+            env.block.height,
+            |vote_power| {
+                if vote_power.is_none() {
+                    // register account
+                    Ok(None)
+                } else {
+                    Err(ContractError::UserAccountAlreadyRegister {})
+                }
+            },
+        )?;
+
+        // start tracking the stake the account already has
+        let (_, stake) = sync_stake(deps, env.block.height, &info.sender)?;
 
         Ok(Response::default()
             .add_attribute("action", "register_user_account")
-            .add_attribute("user_account", info.sender.to_string()))
+            .add_attribute("user_account", info.sender.to_string())
+            .add_attribute("stake", stake.to_string()))
     }
 
     // When Instantiating the new dear_leader_account contract, it should generate a message to register itself in this contract as a dear_leader
@@ -123,14 +401,11 @@ pub mod execute {
         }
 
         // check if dear leader is already registered, if not, register him, if yes return error
-        DEAR_LEADER_BOARD.update(deps.storage, new_dear_leader_addr.to_string(), |addrs| {
-            if addrs.is_none() {
-                // register account
-                Ok(None)
-            } else {
-                Err(ContractError::AlreadyIsADearLeader {})
-            }
-        })?;
+        if DEAR_LEADERS.has(deps.storage, new_dear_leader_addr.to_string()) {
+            return Err(ContractError::AlreadyIsADearLeader {});
+        }
+        DEAR_LEADERS.save(deps.storage, new_dear_leader_addr.to_string(), &0)?;
+        index_leader(deps.storage, &new_dear_leader_addr)?;
 
         Ok(Response::new()
             .add_attribute("action", "register_dear_leader_addr")
@@ -139,7 +414,7 @@ pub mod execute {
 
     pub fn transfer_vote_power(
         deps: DepsMut,
-        _env: Env,
+        env: Env,
         info: MessageInfo,
         dear_leader_addr: String,
     ) -> Result<Response, ContractError> {
@@ -149,10 +424,7 @@ pub mod execute {
             .addr_validate(&dear_leader_addr)
             .map_err(|_| ContractError::InvalidDearLeader {})?;
 
-        /*  let dear_leader_delegators = DEAR_LEADER_BOARD
-        .load(deps.storage, dear_leader_addr.to_string())
-        .map_err(|_| ContractError::DearLeaderNotRegistered {})?; */
-        if !DEAR_LEADER_BOARD.has(deps.storage, dear_leader_addr.to_string()) {
+        if !DEAR_LEADERS.has(deps.storage, dear_leader_addr.to_string()) {
             return Err(ContractError::DearLeaderNotRegistered {});
         }
 
@@ -161,38 +433,59 @@ pub mod execute {
             .load(deps.storage, info.sender.to_string())
             .map_err(|_| ContractError::AccountNotRegistered {})?;
 
-        // check if user_account has a current dear_leader
-
-        match current_dear_leader {
-            Some(current_dear_leader) => {
-                // remove the user_account from the current dear_leader list of supporters
-                // and update the new dear_lear list of supporters
-                DEAR_LEADER_BOARD.update(deps.storage, current_dear_leader, |addrs_opt| {
-                    if let Some(Some(mut list)) = addrs_opt {
-                        /* if let Some(mut list) = addrs { */
-                        list.retain(|addr| addr != &info.sender);
-                        if list.is_empty() {
-                            return Ok(None);
-                        }
-                        Ok(Some(list))
-                        /* }  *//* else {
-                            Err(ContractError::InternalErrorInLogic {})
-                        } */
-                    } else {
-                        Err(ContractError::InternalErrorInLogic {})
-                    }
-                })?;
-            }
-            None => {
-                // this means the user_account is not currently delegating his vote powers
-                BOSS_VOTE_POWER.save(
-                    deps.storage,
-                    info.sender.to_string(),
-                    &Some(dear_leader_addr.to_string()),
-                )?;
-            }
+        if current_dear_leader.as_deref() == Some(dear_leader_addr.as_str()) {
+            return Err(ContractError::AlreadyYourDearLeader {});
         }
 
+        let stake = ACCOUNT_STAKE
+            .may_load(deps.storage, info.sender.to_string())?
+            .unwrap_or_default();
+
+        // if user_account has a current dear_leader, remove it and its stake from its supporters
+        if let Some(current_dear_leader) = current_dear_leader {
+            remove_supporter(
+                deps.storage,
+                env.block.time,
+                env.block.height,
+                &current_dear_leader,
+                &info.sender,
+            )?;
+            update_leader_power(
+                deps.storage,
+                env.block.height,
+                &current_dear_leader,
+                stake,
+                Uint128::zero(),
+            )?;
+        }
+
+        // and add them to the new dear_leader
+        DEAR_LEADER_DELEGATORS.save(
+            deps.storage,
+            (dear_leader_addr.to_string(), info.sender.to_string()),
+            &true,
+            env.block.height,
+        )?;
+        DEAR_LEADERS.update(
+            deps.storage,
+            dear_leader_addr.to_string(),
+            |delegators| -> StdResult<_> { Ok(delegators.unwrap_or_default() + 1) },
+        )?;
+        record_delegator_joined(deps.storage, env.block.time, dear_leader_addr.as_str())?;
+        update_leader_power(
+            deps.storage,
+            env.block.height,
+            dear_leader_addr.as_str(),
+            Uint128::zero(),
+            stake,
+        )?;
+        BOSS_VOTE_POWER.save(
+            deps.storage,
+            info.sender.to_string(),
+            &Some(dear_leader_addr.to_string()),
+            env.block.height,
+        )?;
+
         Ok(Response::new()
             .add_attribute("action", "transfer_vote_power")
             .add_attribute("dear_leader_addr", dear_leader_addr))
@@ -200,7 +493,7 @@ pub mod execute {
 
     pub fn reclaim_vote_power(
         deps: DepsMut,
-        _env: Env,
+        env: Env,
         info: MessageInfo,
     ) -> Result<Response, ContractError> {
         // validate that the user_account is registered in the assembly
@@ -208,18 +501,45 @@ pub mod execute {
         .load(deps.storage, info.sender.to_string())
         .map_err(|_| ContractError::AccountNotRegistered {})?; */
 
-        if !BOSS_VOTE_POWER.has(deps.storage, info.sender.to_string()) {
-            return Err(ContractError::AccountNotRegistered {});
+        let current_dear_leader = BOSS_VOTE_POWER
+            .may_load(deps.storage, info.sender.to_string())?
+            .ok_or(ContractError::AccountNotRegistered {})?;
+
+        // the stake stops counting for the dear leader
+        if let Some(current_dear_leader) = current_dear_leader {
+            let stake = ACCOUNT_STAKE
+                .may_load(deps.storage, info.sender.to_string())?
+                .unwrap_or_default();
+            remove_supporter(
+                deps.storage,
+                env.block.time,
+                env.block.height,
+                &current_dear_leader,
+                &info.sender,
+            )?;
+            update_leader_power(
+                deps.storage,
+                env.block.height,
+                &current_dear_leader,
+                stake,
+                Uint128::zero(),
+            )?;
         }
 
         // reset the option to None, so that the user_account don't delegate his vote power
-        BOSS_VOTE_POWER.save(deps.storage, info.sender.to_string(), &None)?;
+        BOSS_VOTE_POWER.save(
+            deps.storage,
+            info.sender.to_string(),
+            &None,
+            env.block.height,
+        )?;
 
         Ok(Response::new().add_attribute("action", "reclaim_vote_power"))
     }
 
     pub fn user_account_vote(
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
         proposal_id: u64,
         vote: u64,
@@ -228,25 +548,20 @@ pub mod execute {
         if !(1..=4).contains(&vote) {
             return Err(ContractError::InvalidVote {});
         }
+        validate_open_proposal(deps.as_ref(), &env, proposal_id)?;
 
         //validate that user_account is registered in the assembly
-        if !BOSS_VOTE_POWER.has(deps.storage, info.sender.to_string()) {
+        if BOSS_VOTE_POWER
+            .may_load(deps.storage, info.sender.to_string())?
+            .is_none()
+        {
             return Err(ContractError::AccountNotRegistered {});
         }
 
+        record_override(deps.storage, &info.sender, proposal_id)?;
+
         // update PROPOSAL_VOTE_HISTORY
-        PROPOSAL_VOTE_HISTORY.update(
-            deps.storage,
-            proposal_id,
-            |votes| -> StdResult<Vec<String>> {
-                if let Some(mut votes) = votes {
-                    votes.push(info.sender.to_string());
-                    Ok(votes)
-                } else {
-                    Ok(vec![info.sender.to_string()])
-                }
-            },
-        )?;
+        PROPOSAL_VOTE_HISTORY.save(deps.storage, (proposal_id, info.sender.to_string()), &true)?;
 
         let msg = WasmMsg::Execute {
             contract_addr: info.sender.to_string(),
@@ -266,60 +581,104 @@ pub mod execute {
 
     pub fn dear_leader_vote(
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
         proposal_id: u64,
         vote: u64,
+        rationale: Option<VoteRationale>,
     ) -> Result<Response, ContractError> {
-        // check if vote is valid
-        if !(1..=4).contains(&vote) {
-            return Err(ContractError::InvalidVote {});
-        }
+        let leader_vote = validate_leader_vote(proposal_id, vote, rationale)?;
 
-        //validate that dear_leader_account is registered in the assembly and has at least one delegator
-        let valid_dear_leader_list_of_delegators = DEAR_LEADER_BOARD
-            .load(deps.storage, info.sender.to_string())
-            .map_err(|_| ContractError::DearLeaderNotRegistered {})?;
+        cast_dear_leader_vote(deps, &env, info.sender.as_str(), leader_vote)
+    }
 
-        if valid_dear_leader_list_of_delegators.is_none() {
-            return Err(ContractError::NoVotePower {});
+    // the vote is cast once delegators had the time to opt out or vote directly
+    pub fn announce_dear_leader_vote(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        proposal_id: u64,
+        vote: u64,
+        rationale: Option<VoteRationale>,
+    ) -> Result<Response, ContractError> {
+        let leader_vote = validate_leader_vote(proposal_id, vote, rationale)?;
+        let proposal = validate_open_proposal(deps.as_ref(), &env, proposal_id)?;
+        load_voting_followers(deps.as_ref(), info.sender.as_str(), &proposal)?;
+
+        // the window ends with the voting period at the latest
+        let voting_end = proposal.voting_end_time;
+        let review_window = REVIEW_WINDOW
+            .may_load(deps.storage)?
+            .unwrap_or(DEFAULT_REVIEW_WINDOW);
+        let execute_at = env.block.time.plus_seconds(review_window).min(voting_end);
+
+        // announcing again replaces the previous announcement and restarts the window
+        let announcement = VoteAnnouncement {
+            dear_leader: info.sender.to_string(),
+            vote: leader_vote,
+            execute_at,
+        };
+        ANNOUNCED_VOTES.save(
+            deps.storage,
+            (info.sender.to_string(), proposal_id),
+            &announcement,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "announce_dear_leader_vote")
+            .add_attribute("execute_at", execute_at.to_string())
+            .add_event(dear_leader_vote_event(
+                "dear_leader_vote_announced",
+                info.sender.as_str(),
+                &announcement.vote,
+            )))
+    }
+
+    // permissionless, anyone can cast an announced vote once its review window is over
+    pub fn execute_announced_vote(
+        deps: DepsMut,
+        env: Env,
+        dear_leader_addr: String,
+        proposal_id: u64,
+    ) -> Result<Response, ContractError> {
+        let announcement = ANNOUNCED_VOTES
+            .may_load(deps.storage, (dear_leader_addr.clone(), proposal_id))?
+            .ok_or(ContractError::AnnouncementNotFound {})?;
+        if env.block.time < announcement.execute_at {
+            return Err(ContractError::ReviewWindowNotOver {});
         }
 
-        // validate that at least one delegator exists
-        // if not, return error
-        // if yes, iterate over the list of delegators (also validating he has not voted yet) and create messages
-        // prepare list to allow validate that user_account have not voted yet
-        let delegators_that_already_voted = PROPOSAL_VOTE_HISTORY
-            .may_load(deps.storage, proposal_id)?
-            .ok_or(ContractError::ProposalNotRegistered {})?;
+        ANNOUNCED_VOTES.remove(deps.storage, (dear_leader_addr.clone(), proposal_id));
 
-        // Note: It is not possible to have a list of 0 elements
-        let msgs = valid_dear_leader_list_of_delegators
-            .unwrap()
-            .iter()
-            .filter(|addr| !delegators_that_already_voted.contains(addr))
-            .map(|addr| WasmMsg::Execute {
-                contract_addr: addr.to_string(),
-                msg: to_binary(&CommonExecuteMsg::AssemblyVote {
-                    proposal_id,
-                    vote_option: vote,
-                })
-                .unwrap(),
-                funds: vec![],
-            })
-            .take(10) // TEST - HOW MANY MESSAGES CAN BE GENERATED IN ONE TRANSACTION?
-            .collect::<Vec<WasmMsg>>();
+        cast_dear_leader_vote(deps, &env, &dear_leader_addr, announcement.vote)
+    }
+
+    pub fn opt_out_of_leader_vote(
+        deps: DepsMut,
+        info: MessageInfo,
+        proposal_id: u64,
+    ) -> Result<Response, ContractError> {
+        //validate that user_account is registered in the assembly
+        if BOSS_VOTE_POWER
+            .may_load(deps.storage, info.sender.to_string())?
+            .is_none()
+        {
+            return Err(ContractError::AccountNotRegistered {});
+        }
+
+        record_override(deps.storage, &info.sender, proposal_id)?;
+        LEADER_VOTE_OPT_OUTS.save(deps.storage, (proposal_id, info.sender.to_string()), &true)?;
 
         Ok(Response::new()
-            .add_attribute("action", "vote")
-            .add_attribute("n_of_votes", msgs.len().to_string())
-            .add_messages(msgs))
+            .add_attribute("action", "opt_out_of_leader_vote")
+            .add_attribute("user_account", info.sender.to_string())
+            .add_attribute("proposal_id", proposal_id.to_string()))
     }
 
-    pub fn set_account_factory_addr(
+    pub fn set_review_window(
         deps: DepsMut,
-        _env: Env,
         info: MessageInfo,
-        account_factory_addr: String,
+        review_window: u64,
     ) -> Result<Response, ContractError> {
         // validate that the caller is the admin
         let admin = ADMIN_ADDR.load(deps.storage)?;
@@ -327,93 +686,1751 @@ pub mod execute {
             return Err(ContractError::Unauthorized {});
         }
 
-        // validate that the account_factory_addr is a valid address
-        let _ = deps.api.addr_validate(&account_factory_addr)?;
-
-        // update the account_factory_addr
-        DEAR_LEADER_ACCOUNT_FACTORY.save(deps.storage, &account_factory_addr)?;
+        REVIEW_WINDOW.save(deps.storage, &review_window)?;
 
-        Ok(Response::new().add_attribute("action", "set_account_factory_addr"))
+        Ok(Response::new()
+            .add_attribute("action", "set_review_window")
+            .add_attribute("review_window", review_window.to_string()))
     }
-}
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::GetDearLeaderDelegatores { dear_leader_addr } => {
-            query::get_dear_leader_delegators(deps, dear_leader_addr)
+    fn validate_leader_vote(
+        proposal_id: u64,
+        vote: u64,
+        rationale: Option<VoteRationale>,
+    ) -> Result<DearLeaderVote, ContractError> {
+        // check if vote is valid
+        if !(1..=4).contains(&vote) {
+            return Err(ContractError::InvalidVote {});
         }
-        QueryMsg::GetUserAccountLeader { user_account_addr } => {
-            query::get_user_account_leader(deps, user_account_addr)
+        if !rationale.as_ref().map_or(true, VoteRationale::is_valid) {
+            return Err(ContractError::InvalidRationale {});
         }
-        QueryMsg::GetVoteProposalByUserAndProp {
-            user_account_addr,
+
+        Ok(DearLeaderVote {
             proposal_id,
-        } => query::get_vote_proposal_by_user_and_prop(deps, user_account_addr, proposal_id),
-        QueryMsg::GetConfig {} => query::get_config(deps),
+            vote_option: vote,
+            rationale,
+        })
     }
-}
 
-pub mod query {
+    //validate that dear_leader_account is registered in the assembly and has at least one delegator,
+    // its own or one of the dear leaders following it on the proposal category.
+    // Returns the followers.
+    fn load_voting_followers(
+        deps: Deps,
+        dear_leader: &str,
+        proposal: &RegisteredProposal,
+    ) -> Result<Vec<String>, ContractError> {
+        let delegators = DEAR_LEADERS
+            .may_load(deps.storage, dear_leader.to_string())?
+            .ok_or(ContractError::DearLeaderNotRegistered {})?;
+        let followers = transitive_followers(
+            deps.storage,
+            dear_leader,
+            Some(&proposal.content_type),
+            Some(proposal.proposal_id),
+        )?;
+        if delegators == 0 && followers.is_empty() {
+            return Err(ContractError::NoVotePower {});
+        }
 
-    use cosmwasm_std::StdError;
+        Ok(followers)
+    }
 
-    use crate::{
-        msg::{
-            GetConfigResponse, GetDearLeaderDelegatoresResponse,
-            GetVoteProposalByUserAndPropResponse,
-        },
-        state::{BOSS_VOTE_POWER, DEAR_LEADER_ACCOUNT_FACTORY},
-    };
+    fn cast_dear_leader_vote(
+        deps: DepsMut,
+        env: &Env,
+        dear_leader: &str,
+        leader_vote: DearLeaderVote,
+    ) -> Result<Response, ContractError> {
+        let proposal_id = leader_vote.proposal_id;
+        let proposal = validate_open_proposal(deps.as_ref(), env, proposal_id)?;
+        // dear leaders following this one on the category vote the same for their delegators
+        let followers = load_voting_followers(deps.as_ref(), dear_leader, &proposal)?;
+        let follower_events = followers.iter().map(|follower| {
+            Event::new("dear_leader_vote_delegated")
+                .add_attribute("dear_leader", follower)
+                .add_attribute("voted_by", dear_leader)
+                .add_attribute("proposal_id", proposal_id.to_string())
+        });
 
-    use super::*;
+        // voting again on a proposal doesn't count twice
+        if !DEAR_LEADER_VOTES.has(deps.storage, (dear_leader.to_string(), proposal_id)) {
+            update_leader_stats(deps.storage, dear_leader, |stats| stats.votes_cast += 1)?;
+        }
+        DEAR_LEADER_VOTES.save(
+            deps.storage,
+            (dear_leader.to_string(), proposal_id),
+            &leader_vote,
+        )?;
+
+        // the first page goes out with the vote, DeliverDearLeaderVote sends the rest
+        let leaders: Vec<String> = std::iter::once(dear_leader.to_string())
+            .chain(followers.iter().cloned())
+            .collect();
+        let (msgs, last_delegator) = delegator_vote_msgs(
+            deps.storage,
+            &proposal,
+            &leaders,
+            &leader_vote,
+            None,
+            DEFAULT_LIMIT as usize,
+        )?;
 
-    pub fn get_dear_leader_delegators(deps: Deps, dear_leader_addr: String) -> StdResult<Binary> {
-        let delegatores_list = DEAR_LEADER_BOARD
-            .load(deps.storage, dear_leader_addr)
-            .map_err(|_| -> StdError { StdError::generic_err("Dear Leader not registered") })?;
+        Ok(Response::new()
+            .add_attribute("action", "vote")
+            .add_attribute("n_of_votes", msgs.len().to_string())
+            .add_attributes(last_delegator_attributes(last_delegator))
+            .add_event(dear_leader_vote_event(
+                "dear_leader_vote",
+                dear_leader,
+                &leader_vote,
+            ))
+            .add_events(follower_events)
+            .add_messages(msgs))
+    }
 
-        let response = GetDearLeaderDelegatoresResponse { delegatores_list };
+    pub fn deliver_dear_leader_vote(
+        deps: DepsMut,
+        env: Env,
+        dear_leader_addr: String,
+        proposal_id: u64,
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    ) -> Result<Response, ContractError> {
+        let proposal = validate_open_proposal(deps.as_ref(), &env, proposal_id)?;
+        let leader_vote = DEAR_LEADER_VOTES
+            .may_load(deps.storage, (dear_leader_addr.clone(), proposal_id))?
+            .ok_or(ContractError::LeaderVoteNotFound {})?;
+        let followers = load_voting_followers(deps.as_ref(), &dear_leader_addr, &proposal)?;
 
-        to_binary(&response)
+        let leaders: Vec<String> = std::iter::once(dear_leader_addr.clone())
+            .chain(followers)
+            .collect();
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let (msgs, last_delegator) = delegator_vote_msgs(
+            deps.storage,
+            &proposal,
+            &leaders,
+            &leader_vote,
+            start_after,
+            limit,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "deliver_dear_leader_vote")
+            .add_attribute("dear_leader_addr", dear_leader_addr)
+            .add_attribute("proposal_id", proposal_id.to_string())
+            .add_attribute("n_of_votes", msgs.len().to_string())
+            .add_attributes(last_delegator_attributes(last_delegator))
+            .add_messages(msgs))
     }
 
-    pub fn get_user_account_leader(deps: Deps, user_account_addr: String) -> StdResult<Binary> {
-        let leader_addr = BOSS_VOTE_POWER
-            .load(deps.storage, user_account_addr)
-            .map_err(|_| -> StdError { StdError::generic_err("User Account not registered") })?;
+    // (dear leader, user account) a page of dear leader votes ended with
+    type DelegatorCursor = (String, String);
 
-        to_binary(&leader_addr)
+    // votes of a page of the delegators of the dear leaders, in order, skipping the ones that voted
+    // directly or joined after the proposal start. Delegators that opted out of their dear leader
+    // vote keep their vote power. Returns the (dear leader, user account) the page ended with if
+    // the page is full
+    fn delegator_vote_msgs(
+        storage: &dyn Storage,
+        proposal: &RegisteredProposal,
+        leaders: &[String],
+        leader_vote: &DearLeaderVote,
+        start_after: Option<DelegatorCursor>,
+        limit: usize,
+    ) -> StdResult<(Vec<WasmMsg>, Option<DelegatorCursor>)> {
+        let proposal_id = leader_vote.proposal_id;
+        let (first_leader, mut start_after) = match start_after {
+            Some((leader, account)) => match leaders.iter().position(|l| *l == leader) {
+                Some(position) => (position, Some(account)),
+                // the dear leader doesn't vote for these delegators anymore
+                None => return Ok((vec![], None)),
+            },
+            None => (0, None),
+        };
+
+        let mut msgs = vec![];
+        for leader in leaders.iter().skip(first_leader) {
+            let accounts = DEAR_LEADER_DELEGATORS.prefix(leader.to_string()).keys(
+                storage,
+                start_after.take().map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            );
+            for account in accounts {
+                let account = account?;
+                if PROPOSAL_VOTE_HISTORY.has(storage, (proposal_id, account.clone()))
+                    || LEADER_VOTE_OPT_OUTS.has(storage, (proposal_id, account.clone()))
+                    || !delegated_at_start(storage, proposal, leader, &account)?
+                {
+                    continue;
+                }
+                msgs.push(WasmMsg::Execute {
+                    contract_addr: account.clone(),
+                    msg: to_binary(&CommonExecuteMsg::AssemblyVote {
+                        proposal_id,
+                        vote_option: leader_vote.vote_option,
+                    })?,
+                    funds: vec![],
+                });
+                if msgs.len() == limit {
+                    return Ok((msgs, Some((leader.to_string(), account))));
+                }
+            }
+        }
+
+        Ok((msgs, None))
     }
 
-    pub fn get_vote_proposal_by_user_and_prop(
+    // empty once every delegator got the vote
+    fn last_delegator_attributes(last_delegator: Option<DelegatorCursor>) -> Vec<(String, String)> {
+        let (dear_leader, user_account) = last_delegator.unwrap_or_default();
+        vec![
+            ("last_dear_leader".to_string(), dear_leader),
+            ("last_user_account".to_string(), user_account),
+        ]
+    }
+
+    // the stored status can be stale, so the voting end time is checked too
+    fn validate_open_proposal(
         deps: Deps,
-        user_account_addr: String,
+        env: &Env,
         proposal_id: u64,
-    ) -> StdResult<Binary> {
-        let vote = PROPOSAL_VOTE_HISTORY
-            .load(deps.storage, proposal_id)
-            .map_err(|_| -> StdError { StdError::generic_err("Proposal not registered") })?;
+    ) -> Result<RegisteredProposal, ContractError> {
+        let proposal = PROPOSALS
+            .may_load(deps.storage, proposal_id)?
+            .ok_or(ContractError::ProposalNotRegistered {})?;
+        match proposal.status {
+            ProposalStatus::VotingPeriod if env.block.time <= proposal.voting_end_time => {
+                Ok(proposal)
+            }
+            ProposalStatus::Unspecified | ProposalStatus::DepositPeriod => {
+                Err(ContractError::ProposalNotInVotingPeriod {})
+            }
+            _ => Err(ContractError::VotingEnded {}),
+        }
+    }
+
+    // permissionless, keeps the registry in line with x/gov
+    pub fn sync_proposal(
+        deps: DepsMut,
+        env: Env,
+        proposal_id: u64,
+    ) -> Result<Response, ContractError> {
+        let gov_proposal = query_gov_proposal(&deps.querier, proposal_id)?;
+        let previous = PROPOSALS.may_load(deps.storage, proposal_id)?;
+        let previous_status = previous.as_ref().map(|proposal| proposal.status.clone());
 
-        let voted = vote.into_iter().find(|addr| addr == &user_account_addr);
+        // dear leaders with delegators are expected to vote on every proposal entering its voting period
+        if gov_proposal.status == ProposalStatus::VotingPeriod
+            && previous_status != Some(ProposalStatus::VotingPeriod)
+        {
+            let proposal_count = PROPOSAL_COUNT.may_load(deps.storage)?.unwrap_or_default();
+            PROPOSAL_COUNT.save(deps.storage, &(proposal_count + 1))?;
+        }
 
-        let response = GetVoteProposalByUserAndPropResponse {
-            voted: voted.is_some(),
+        let proposal = RegisteredProposal {
+            proposal_id,
+            title: gov_proposal.title,
+            content_type: gov_proposal.content_type,
+            status: gov_proposal.status,
+            voting_end_time: gov_proposal.voting_end_time,
+            // power moved after the first sync doesn't change the tally
+            start_height: previous.map_or(env.block.height, |proposal| proposal.start_height),
         };
-        to_binary(&response)
-    }
+        PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
 
-    pub fn get_config(deps: Deps) -> StdResult<Binary> {
-        let admin_addr = ADMIN_ADDR.load(deps.storage).unwrap_or_default();
-        let accounts_factory_addr = DEAR_LEADER_ACCOUNT_FACTORY
-            .load(deps.storage)
-            .unwrap_or_default();
+        Ok(Response::new()
+            .add_attribute("action", "sync_proposal")
+            .add_attribute("proposal_id", proposal_id.to_string())
+            .add_attribute("status", proposal.status.as_str())
+            .add_attribute("voting_end_time", proposal.voting_end_time.to_string()))
+    }
+
+    pub fn set_vote_fallback(
+        deps: DepsMut,
+        info: MessageInfo,
+        fallback: VoteFallback,
+    ) -> Result<Response, ContractError> {
+        //validate that user_account is registered in the assembly
+        if BOSS_VOTE_POWER
+            .may_load(deps.storage, info.sender.to_string())?
+            .is_none()
+        {
+            return Err(ContractError::AccountNotRegistered {});
+        }
+
+        match &fallback {
+            VoteFallback::Nothing {} => {
+                VOTE_FALLBACKS.remove(deps.storage, info.sender.to_string())
+            }
+            VoteFallback::FollowLeader { dear_leader_addr }
+                if !DEAR_LEADERS.has(deps.storage, dear_leader_addr.to_string()) =>
+            {
+                return Err(ContractError::DearLeaderNotRegistered {});
+            }
+            _ => VOTE_FALLBACKS.save(deps.storage, info.sender.to_string(), &fallback)?,
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "set_vote_fallback")
+            .add_attribute("user_account", info.sender.to_string()))
+    }
+
+    // permissionless crank, votes the fallback of the user accounts in the page whose vote power
+    // would otherwise not take part in the proposal. The fallback only stands in for the dear
+    // leader, if it votes later its vote replaces the fallback
+    pub fn apply_fallbacks(
+        deps: DepsMut,
+        env: Env,
+        proposal_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<Response, ContractError> {
+        let proposal = validate_open_proposal(deps.as_ref(), &env, proposal_id)?;
+        if env.block.time.plus_seconds(FALLBACK_WINDOW) < proposal.voting_end_time {
+            return Err(ContractError::FallbackWindowNotOpen {});
+        }
+
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let fallbacks = VOTE_FALLBACKS
+            .range(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .collect::<StdResult<Vec<(String, VoteFallback)>>>()?;
+
+        let mut msgs = vec![];
+        let mut events = vec![];
+        for (account, fallback) in fallbacks.iter() {
+            if PROPOSAL_VOTE_HISTORY.has(deps.storage, (proposal_id, account.to_string()))
+                || FALLBACK_VOTES.has(deps.storage, (proposal_id, account.to_string()))
+                || follows_leader_vote(deps.as_ref(), &env, account, &proposal)?
+            {
+                continue;
+            }
+
+            // accounts whose dear leader has a pending announcement, or following a dear leader that
+            // did not vote yet, are retried on the next call
+            let vote_option = match fallback {
+                VoteFallback::Abstain {} => ABSTAIN_VOTE_OPTION,
+                VoteFallback::FollowLeader { dear_leader_addr } => match DEAR_LEADER_VOTES
+                    .may_load(deps.storage, (dear_leader_addr.to_string(), proposal_id))?
+                {
+                    Some(vote) => vote.vote_option,
+                    None => continue,
+                },
+                VoteFallback::Nothing {} => continue,
+            };
+
+            FALLBACK_VOTES.save(
+                deps.storage,
+                (proposal_id, account.to_string()),
+                &vote_option,
+            )?;
+            msgs.push(WasmMsg::Execute {
+                contract_addr: account.to_string(),
+                msg: to_binary(&CommonExecuteMsg::AssemblyVote {
+                    proposal_id,
+                    vote_option,
+                })?,
+                funds: vec![],
+            });
+            events.push(
+                Event::new("vote_fallback_applied")
+                    .add_attribute("user_account", account)
+                    .add_attribute("proposal_id", proposal_id.to_string())
+                    .add_attribute("vote_option", vote_option.to_string()),
+            );
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "apply_fallbacks")
+            .add_attribute("proposal_id", proposal_id.to_string())
+            .add_attribute("applied", msgs.len().to_string())
+            .add_attribute(
+                "last_account",
+                fallbacks
+                    .last()
+                    .map(|(account, _)| account.to_string())
+                    .unwrap_or_default(),
+            )
+            .add_events(events)
+            .add_messages(msgs))
+    }
+
+    // the dear leader vote reaches the user account, or will once its announced vote is cast
+    fn follows_leader_vote(
+        deps: Deps,
+        env: &Env,
+        account: &str,
+        proposal: &RegisteredProposal,
+    ) -> StdResult<bool> {
+        let proposal_id = proposal.proposal_id;
+        if LEADER_VOTE_OPT_OUTS.has(deps.storage, (proposal_id, account.to_string())) {
+            return Ok(false);
+        }
+
+        Ok(
+            match BOSS_VOTE_POWER
+                .may_load(deps.storage, account.to_string())?
+                .flatten()
+            {
+                Some(dear_leader) => {
+                    delegated_at_start(deps.storage, proposal, &dear_leader, account)?
+                        && leader_chain_votes(deps.storage, &dear_leader, proposal, env.block.time)?
+                }
+                None => false,
+            },
+        )
+    }
+
+    // the delegation held at the end of the block the proposal was first synced in, delegators
+    // that joined later don't get the dear leader vote on it
+    fn delegated_at_start(
+        storage: &dyn Storage,
+        proposal: &RegisteredProposal,
+        dear_leader: &str,
+        user_account: &str,
+    ) -> StdResult<bool> {
+        Ok(DEAR_LEADER_DELEGATORS
+            .may_load_at_height(
+                storage,
+                (dear_leader.to_string(), user_account.to_string()),
+                proposal.start_height + 1,
+            )?
+            .is_some())
+    }
+
+    // the dear leader defers its votes on the categories to another one, the vote cast at the end of
+    // the chain goes to the delegators of every dear leader in it
+    pub fn follow_dear_leader(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        dear_leader_addr: String,
+        categories: Option<Vec<String>>,
+    ) -> Result<Response, ContractError> {
+        let follower = info.sender.to_string();
+        if !DEAR_LEADERS.has(deps.storage, follower.clone())
+            || !DEAR_LEADERS.has(deps.storage, dear_leader_addr.clone())
+        {
+            return Err(ContractError::DearLeaderNotRegistered {});
+        }
+        if dear_leader_addr == follower || categories.as_ref().map_or(false, Vec::is_empty) {
+            return Err(ContractError::InvalidLeaderDelegation {});
+        }
+
+        // following the same dear leader again replaces its categories
+        let mut delegations: Vec<LeaderDelegation> = LEADER_DELEGATIONS
+            .may_load(deps.storage, follower.clone())?
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|delegation| delegation.dear_leader != dear_leader_addr)
+            .collect();
+        // each category goes to one dear leader at most, and so does every proposal
+        let overlaps =
+            delegations
+                .iter()
+                .any(|delegation| match (&delegation.categories, &categories) {
+                    (None, None) => true,
+                    (Some(current), Some(new)) => current.iter().any(|c| new.contains(c)),
+                    _ => false,
+                });
+        if overlaps {
+            return Err(ContractError::InvalidLeaderDelegation {});
+        }
+
+        delegations.push(LeaderDelegation {
+            dear_leader: dear_leader_addr.clone(),
+            categories: categories.clone(),
+        });
+        LEADER_DELEGATIONS.save(deps.storage, follower.clone(), &delegations)?;
+        LEADER_FOLLOWERS.save(
+            deps.storage,
+            (dear_leader_addr.clone(), follower.clone()),
+            &true,
+        )?;
+        index_leader_chain(deps.storage, env.block.height, &dear_leader_addr)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "follow_dear_leader")
+            .add_attribute("dear_leader_addr", follower)
+            .add_attribute("followed", dear_leader_addr)
+            .add_attribute(
+                "categories",
+                categories.map_or("all".to_string(), |categories| categories.join(",")),
+            ))
+    }
+
+    pub fn unfollow_dear_leader(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        dear_leader_addr: String,
+    ) -> Result<Response, ContractError> {
+        let follower = info.sender.to_string();
+        remove_leader_delegation(deps.storage, &follower, &dear_leader_addr)?;
+        index_leader_chain(deps.storage, env.block.height, &dear_leader_addr)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "unfollow_dear_leader")
+            .add_attribute("dear_leader_addr", follower)
+            .add_attribute("unfollowed", dear_leader_addr))
+    }
+
+    fn remove_leader_delegation(
+        storage: &mut dyn Storage,
+        follower: &str,
+        dear_leader: &str,
+    ) -> Result<(), ContractError> {
+        let mut delegations = LEADER_DELEGATIONS
+            .may_load(storage, follower.to_string())?
+            .unwrap_or_default();
+        let n_of_delegations = delegations.len();
+        delegations.retain(|delegation| delegation.dear_leader != dear_leader);
+        if delegations.len() == n_of_delegations {
+            return Err(ContractError::LeaderDelegationNotFound {});
+        }
+
+        if delegations.is_empty() {
+            LEADER_DELEGATIONS.remove(storage, follower.to_string());
+        } else {
+            LEADER_DELEGATIONS.save(storage, follower.to_string(), &delegations)?;
+        }
+        LEADER_FOLLOWERS.remove(storage, (dear_leader.to_string(), follower.to_string()));
+        Ok(())
+    }
+
+    // delegators follow the votes and their rationale through these events
+    fn dear_leader_vote_event(ty: &str, dear_leader: &str, vote: &DearLeaderVote) -> Event {
+        let event = Event::new(ty)
+            .add_attribute("dear_leader", dear_leader)
+            .add_attribute("proposal_id", vote.proposal_id.to_string())
+            .add_attribute("vote_option", vote.vote_option.to_string());
+
+        match &vote.rationale {
+            Some(VoteRationale::Text { text }) => event.add_attribute("rationale", text),
+            Some(VoteRationale::Uri { uri, hash }) => event
+                .add_attribute("rationale_uri", uri)
+                .add_attribute("rationale_hash", hash),
+            None => event,
+        }
+    }
+
+    pub fn set_account_factory_addr(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        account_factory_addr: String,
+    ) -> Result<Response, ContractError> {
+        // validate that the caller is the admin
+        let admin = ADMIN_ADDR.load(deps.storage)?;
+        if info.sender != admin {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        // validate that the account_factory_addr is a valid address
+        let _ = deps.api.addr_validate(&account_factory_addr)?;
+
+        // update the account_factory_addr
+        DEAR_LEADER_ACCOUNT_FACTORY.save(deps.storage, &account_factory_addr)?;
+
+        Ok(Response::new().add_attribute("action", "set_account_factory_addr"))
+    }
+
+    pub fn set_max_validator_commission(
+        deps: DepsMut,
+        info: MessageInfo,
+        max_commission: Option<Decimal>,
+    ) -> Result<Response, ContractError> {
+        // validate that the caller is the admin
+        let admin = ADMIN_ADDR.load(deps.storage)?;
+        if info.sender != admin {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        // a commission cap above 100% makes no sense
+        if let Some(max_commission) = max_commission {
+            if max_commission > Decimal::one() {
+                return Err(ContractError::InvalidCommission {});
+            }
+        }
+
+        MAX_VALIDATOR_COMMISSION.save(deps.storage, &max_commission)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_max_validator_commission")
+            .add_attribute(
+                "max_commission",
+                max_commission
+                    .map(|commission| commission.to_string())
+                    .unwrap_or_else(|| "none".to_string()),
+            ))
+    }
+
+    pub fn update_validator_allow_list(
+        deps: DepsMut,
+        info: MessageInfo,
+        to_add: Vec<String>,
+        to_remove: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        // validate that the caller is the admin
+        let admin = ADMIN_ADDR.load(deps.storage)?;
+        if info.sender != admin {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        // validator addresses use the valoper prefix, so only confirm the validator exists
+        for validator_addr in to_add.iter() {
+            if deps.querier.query_validator(validator_addr)?.is_none() {
+                return Err(ContractError::ValidatorNotFound {});
+            }
+            VALIDATOR_ALLOW_LIST.save(deps.storage, validator_addr.to_string(), &true)?;
+        }
+
+        for validator_addr in to_remove.iter() {
+            VALIDATOR_ALLOW_LIST.remove(deps.storage, validator_addr.to_string());
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "update_validator_allow_list")
+            .add_attribute("added", to_add.len().to_string())
+            .add_attribute("removed", to_remove.len().to_string()))
+    }
+
+    pub fn set_dear_leader_profile(
+        deps: DepsMut,
+        info: MessageInfo,
+        profile: DearLeaderProfile,
+    ) -> Result<Response, ContractError> {
+        // only registered dear_leader_accounts have a profile
+        if !DEAR_LEADERS.has(deps.storage, info.sender.to_string()) {
+            return Err(ContractError::DearLeaderNotRegistered {});
+        }
+        if !profile.is_valid() {
+            return Err(ContractError::InvalidProfile {});
+        }
+
+        DEAR_LEADER_PROFILES.save(deps.storage, info.sender.to_string(), &profile)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_dear_leader_profile")
+            .add_attribute("dear_leader_addr", info.sender.to_string())
+            .add_attribute("name", profile.name))
+    }
+
+    // permissionless crank, moves the next page of legacy entries left by the migration
+    pub fn migrate_composite_keys(
+        deps: DepsMut,
+        env: Env,
+        limit: Option<u32>,
+    ) -> Result<Response, ContractError> {
+        let limit = limit
+            .unwrap_or(DEFAULT_MIGRATION_LIMIT)
+            .min(MAX_MIGRATION_LIMIT) as usize;
+        let (migrated, done) = migration::migrate_to_composite_keys(deps, env.block.height, limit)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "migrate_composite_keys")
+            .add_attribute("migrated_entries", migrated.to_string())
+            .add_attribute("migration_done", done.to_string()))
+    }
+
+    // the dear leader leaves the board right away, its delegators get their vote power back
+    // in batches, the first one here and the rest through ReleaseRetiredDelegators
+    pub fn retire_dear_leader(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let dear_leader = info.sender.to_string();
+        let delegators = DEAR_LEADERS
+            .may_load(deps.storage, dear_leader.clone())?
+            .ok_or(ContractError::DearLeaderNotRegistered {})?;
+
+        DEAR_LEADERS.remove(deps.storage, dear_leader.clone());
+        DEAR_LEADER_PROFILES.remove(deps.storage, dear_leader.clone());
+        RETIRING_DEAR_LEADERS.save(deps.storage, dear_leader.clone(), &delegators)?;
+        // the dear leaders it followed stop voting for its delegators
+        let delegations = LEADER_DELEGATIONS
+            .may_load(deps.storage, dear_leader.clone())?
+            .unwrap_or_default();
+        LEADER_DELEGATIONS.remove(deps.storage, dear_leader.clone());
+        for delegation in delegations {
+            LEADER_FOLLOWERS.remove(
+                deps.storage,
+                (delegation.dear_leader.clone(), dear_leader.clone()),
+            );
+            index_leader_chain(deps.storage, env.block.height, &delegation.dear_leader)?;
+        }
+        // and the dear leaders following it vote on their own again
+        let followers = LEADER_FOLLOWERS
+            .prefix(dear_leader.clone())
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<String>>>()?;
+        let mut follower_events = vec![];
+        for follower in followers {
+            remove_leader_delegation(deps.storage, &follower, &dear_leader)?;
+            follower_events.push(
+                Event::new("leader_delegation_removed")
+                    .add_attribute("dear_leader", follower)
+                    .add_attribute("followed", &dear_leader),
+            );
+        }
+        // it leaves the leaderboard, without delegated power left
+        index_leader_chain(deps.storage, env.block.height, &dear_leader)?;
+        // proposals registered from now on don't count against its participation
+        let proposal_count = PROPOSAL_COUNT.may_load(deps.storage)?.unwrap_or_default();
+        update_leader_stats(deps.storage, &dear_leader, |stats| {
+            close_active_period(stats, proposal_count)
+        })?;
+
+        let res = release_retired_delegators(deps, env, dear_leader.clone(), None)?;
+
+        Ok(res
+            .add_event(
+                Event::new("dear_leader_retired")
+                    .add_attribute("dear_leader", dear_leader)
+                    .add_attribute("delegators", delegators.to_string()),
+            )
+            .add_events(follower_events))
+    }
+
+    // permissionless crank, resets the vote power of a page of delegators of a retired dear leader
+    pub fn release_retired_delegators(
+        deps: DepsMut,
+        env: Env,
+        dear_leader_addr: String,
+        limit: Option<u32>,
+    ) -> Result<Response, ContractError> {
+        let remaining = RETIRING_DEAR_LEADERS
+            .may_load(deps.storage, dear_leader_addr.clone())?
+            .ok_or(ContractError::DearLeaderNotRetiring {})?;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let released = DEAR_LEADER_DELEGATORS
+            .prefix(dear_leader_addr.clone())
+            .keys(deps.storage, None, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<String>>>()?;
+        let remaining = remaining.saturating_sub(released.len() as u64);
+
+        // each delegator gets an event so it knows its vote power is not used anymore
+        let mut events = vec![];
+        for account in released.iter() {
+            let stake = ACCOUNT_STAKE
+                .may_load(deps.storage, account.to_string())?
+                .unwrap_or_default();
+            update_leader_power(
+                deps.storage,
+                env.block.height,
+                &dear_leader_addr,
+                stake,
+                Uint128::zero(),
+            )?;
+            BOSS_VOTE_POWER.save(deps.storage, account.to_string(), &None, env.block.height)?;
+            DEAR_LEADER_DELEGATORS.remove(
+                deps.storage,
+                (dear_leader_addr.clone(), account.to_string()),
+                env.block.height,
+            )?;
+            record_delegator_left(deps.storage, env.block.time, &dear_leader_addr, false)?;
+            events.push(
+                Event::new("vote_power_released")
+                    .add_attribute("user_account", account)
+                    .add_attribute("dear_leader", &dear_leader_addr),
+            );
+        }
+
+        if remaining == 0 {
+            RETIRING_DEAR_LEADERS.remove(deps.storage, dear_leader_addr.clone());
+            DEAR_LEADER_POWER.remove(deps.storage, dear_leader_addr.clone(), env.block.height)?;
+        } else {
+            RETIRING_DEAR_LEADERS.save(deps.storage, dear_leader_addr.clone(), &remaining)?;
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "release_retired_delegators")
+            .add_attribute("dear_leader_addr", dear_leader_addr)
+            .add_attribute("released", released.len().to_string())
+            .add_attribute("remaining", remaining.to_string())
+            .add_events(events))
+    }
+
+    pub fn sync_account_stake(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        //validate that user_account is registered in the assembly
+        if BOSS_VOTE_POWER
+            .may_load(deps.storage, info.sender.to_string())?
+            .is_none()
+        {
+            return Err(ContractError::AccountNotRegistered {});
+        }
+
+        let (previous, stake) = sync_stake(deps, env.block.height, &info.sender)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "sync_account_stake")
+            .add_event(stake_sync_event(info.sender.as_str(), previous, stake)))
+    }
+
+    // permissionless crank, recomputes the stake of a page of registered user accounts
+    pub fn sync_accounts(
+        mut deps: DepsMut,
+        env: Env,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<Response, ContractError> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let accounts = BOSS_VOTE_POWER
+            .keys(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .collect::<StdResult<Vec<String>>>()?;
+
+        // only accounts whose stake changed get an event
+        let mut events = vec![];
+        for account in accounts.iter() {
+            let (previous, stake) =
+                sync_stake(deps.branch(), env.block.height, &Addr::unchecked(account))?;
+            if previous != stake {
+                events.push(stake_sync_event(account, previous, stake));
+            }
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "sync_accounts")
+            .add_attribute("synced", accounts.len().to_string())
+            .add_attribute("last_account", accounts.last().cloned().unwrap_or_default())
+            .add_events(events))
+    }
+
+    // recomputes the bonded stake of a user account from the chain and moves the difference
+    // to the dear leader holding its vote power. Returns the previous and the current stake.
+    pub fn sync_stake(
+        deps: DepsMut,
+        height: u64,
+        user_account: &Addr,
+    ) -> StdResult<(Uint128, Uint128)> {
+        let stake = deps
+            .querier
+            .query_all_delegations(user_account)?
+            .iter()
+            .map(|delegation| delegation.amount.amount)
+            .sum();
+        let previous = ACCOUNT_STAKE
+            .may_load(deps.storage, user_account.to_string())?
+            .unwrap_or_default();
+        ACCOUNT_STAKE.save(deps.storage, user_account.to_string(), &stake, height)?;
+
+        if let Some(dear_leader) = BOSS_VOTE_POWER.load(deps.storage, user_account.to_string())? {
+            update_leader_power(deps.storage, height, &dear_leader, previous, stake)?;
+        }
+
+        Ok((previous, stake))
+    }
+
+    // replaces the stake a supporter adds to the power of a dear leader
+    fn update_leader_power(
+        storage: &mut dyn Storage,
+        height: u64,
+        dear_leader: &str,
+        old_stake: Uint128,
+        new_stake: Uint128,
+    ) -> StdResult<()> {
+        DEAR_LEADER_POWER.update(
+            storage,
+            dear_leader.to_string(),
+            height,
+            |power| -> StdResult<_> {
+                Ok(power.unwrap_or_default().checked_sub(old_stake)? + new_stake)
+            },
+        )?;
+        index_leader_chain(storage, height, dear_leader)
+    }
+
+    // the power of a dear leader counts for the ones it follows on every proposal, up to
+    // MAX_DELEGATION_DEPTH hops, so their delegated power and leaderboard entries move too
+    fn index_leader_chain(
+        storage: &mut dyn Storage,
+        height: u64,
+        dear_leader: &str,
+    ) -> StdResult<()> {
+        let mut leader = dear_leader.to_string();
+        let mut visited = vec![];
+        for _ in 0..=MAX_DELEGATION_DEPTH {
+            let power = delegated_power(storage, &leader)?;
+            let previous = DEAR_LEADER_DELEGATED_POWER
+                .may_load(storage, leader.clone())?
+                .unwrap_or_default();
+            if power != previous {
+                DEAR_LEADER_DELEGATED_POWER.save(storage, leader.clone(), &power, height)?;
+            }
+            index_leader(storage, &leader)?;
+            visited.push(leader.clone());
+            let followed = LEADER_DELEGATIONS
+                .may_load(storage, leader.clone())?
+                .unwrap_or_default()
+                .into_iter()
+                .find(|delegation| delegation.categories.is_none());
+            match followed {
+                Some(delegation) if !visited.contains(&delegation.dear_leader) => {
+                    leader = delegation.dear_leader
+                }
+                _ => break,
+            }
+        }
+        Ok(())
+    }
+
+    // moves the leaderboard entries of the dear leader to its current power, with the power of the
+    // dear leaders following it, and delegators. Retired dear leaders leave the leaderboard
+    pub fn index_leader(storage: &mut dyn Storage, dear_leader: &str) -> StdResult<()> {
+        if let Some((power, delegators)) =
+            LEADERBOARD_KEYS.may_load(storage, dear_leader.to_string())?
+        {
+            LEADERS_BY_POWER.remove(storage, (power, dear_leader.to_string()));
+            LEADERS_BY_DELEGATORS.remove(storage, (delegators, dear_leader.to_string()));
+            LEADERBOARD_KEYS.remove(storage, dear_leader.to_string());
+        }
+
+        let delegators = match DEAR_LEADERS.may_load(storage, dear_leader.to_string())? {
+            Some(delegators) => delegators,
+            None => return Ok(()),
+        };
+        let power = DEAR_LEADER_POWER
+            .may_load(storage, dear_leader.to_string())?
+            .unwrap_or_default()
+            .checked_add(delegated_power(storage, dear_leader)?)?
+            .u128();
+        LEADERS_BY_POWER.save(storage, (power, dear_leader.to_string()), &true)?;
+        LEADERS_BY_DELEGATORS.save(storage, (delegators, dear_leader.to_string()), &true)?;
+        LEADERBOARD_KEYS.save(storage, dear_leader.to_string(), &(power, delegators))
+    }
+
+    // remove the user_account from the dear_leader list of supporters
+    fn remove_supporter(
+        storage: &mut dyn Storage,
+        now: Timestamp,
+        height: u64,
+        dear_leader: &str,
+        user_account: &Addr,
+    ) -> Result<(), ContractError> {
+        DEAR_LEADER_DELEGATORS.remove(
+            storage,
+            (dear_leader.to_string(), user_account.to_string()),
+            height,
+        )?;
+
+        // a retired dear leader keeps count of the delegators not released yet
+        if let Some(remaining) = RETIRING_DEAR_LEADERS.may_load(storage, dear_leader.to_string())? {
+            RETIRING_DEAR_LEADERS.save(
+                storage,
+                dear_leader.to_string(),
+                &remaining.saturating_sub(1),
+            )?;
+            record_delegator_left(storage, now, dear_leader, false)?;
+            return Ok(());
+        }
+
+        let delegators = DEAR_LEADERS
+            .may_load(storage, dear_leader.to_string())?
+            .and_then(|delegators| delegators.checked_sub(1))
+            .ok_or(ContractError::InternalErrorInLogic {})?;
+        DEAR_LEADERS.save(storage, dear_leader.to_string(), &delegators)?;
+        index_leader(storage, dear_leader)?;
+        record_delegator_left(storage, now, dear_leader, delegators == 0)?;
+        Ok(())
+    }
+
+    fn record_delegator_joined(
+        storage: &mut dyn Storage,
+        now: Timestamp,
+        dear_leader: &str,
+    ) -> StdResult<()> {
+        let proposal_count = PROPOSAL_COUNT.may_load(storage)?.unwrap_or_default();
+        update_leader_stats(storage, dear_leader, |stats| {
+            stats.delegators_gained += 1;
+            stats.active_since.get_or_insert(proposal_count);
+        })?;
+        update_churn(storage, now, dear_leader, |period| period.gained += 1)
+    }
+
+    // once the last delegator leaves, proposals stop counting against the participation
+    fn record_delegator_left(
+        storage: &mut dyn Storage,
+        now: Timestamp,
+        dear_leader: &str,
+        was_last: bool,
+    ) -> StdResult<()> {
+        let proposal_count = PROPOSAL_COUNT.may_load(storage)?.unwrap_or_default();
+        update_leader_stats(storage, dear_leader, |stats| {
+            stats.delegators_lost += 1;
+            if was_last {
+                close_active_period(stats, proposal_count);
+            }
+        })?;
+        update_churn(storage, now, dear_leader, |period| period.lost += 1)
+    }
+
+    fn close_active_period(stats: &mut LeaderStats, proposal_count: u64) {
+        if let Some(active_since) = stats.active_since.take() {
+            stats.eligible_proposals += proposal_count - active_since;
+        }
+    }
+
+    fn update_leader_stats(
+        storage: &mut dyn Storage,
+        dear_leader: &str,
+        action: impl FnOnce(&mut LeaderStats),
+    ) -> StdResult<()> {
+        LEADER_STATS.update(storage, dear_leader.to_string(), |stats| {
+            let mut stats = stats.unwrap_or_default();
+            action(&mut stats);
+            StdResult::Ok(stats)
+        })?;
+        Ok(())
+    }
+
+    fn update_churn(
+        storage: &mut dyn Storage,
+        now: Timestamp,
+        dear_leader: &str,
+        action: impl FnOnce(&mut ChurnPeriod),
+    ) -> StdResult<()> {
+        let period_start = now.seconds() / CHURN_PERIOD * CHURN_PERIOD;
+        LEADER_CHURN.update(storage, (dear_leader.to_string(), period_start), |period| {
+            let mut period = period.unwrap_or(ChurnPeriod {
+                period_start,
+                gained: 0,
+                lost: 0,
+            });
+            action(&mut period);
+            StdResult::Ok(period)
+        })?;
+        Ok(())
+    }
+
+    // a delegator voting on its own or opting out overrides its dear leader, once per proposal
+    fn record_override(
+        storage: &mut dyn Storage,
+        user_account: &Addr,
+        proposal_id: u64,
+    ) -> StdResult<()> {
+        let dear_leader = match BOSS_VOTE_POWER.may_load(storage, user_account.to_string())? {
+            Some(Some(dear_leader)) => dear_leader,
+            _ => return Ok(()),
+        };
+        if PROPOSAL_VOTE_HISTORY.has(storage, (proposal_id, user_account.to_string()))
+            || LEADER_VOTE_OPT_OUTS.has(storage, (proposal_id, user_account.to_string()))
+        {
+            return Ok(());
+        }
+        update_leader_stats(storage, &dear_leader, |stats| stats.overrides += 1)
+    }
+
+    fn stake_sync_event(user_account: &str, previous: Uint128, stake: Uint128) -> Event {
+        Event::new("stake_sync")
+            .add_attribute("user_account", user_account)
+            .add_attribute("previous_stake", previous.to_string())
+            .add_attribute("stake", stake.to_string())
+            .add_attribute("delta", stake_delta(previous, stake))
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetDearLeaderDelegatores {
+            dear_leader_addr,
+            start_after,
+            limit,
+        } => query::get_dear_leader_delegators(deps, dear_leader_addr, start_after, limit),
+        QueryMsg::GetUserAccountLeader { user_account_addr } => {
+            query::get_user_account_leader(deps, user_account_addr)
+        }
+        QueryMsg::GetVoteProposalByUserAndProp {
+            user_account_addr,
+            proposal_id,
+        } => query::get_vote_proposal_by_user_and_prop(deps, user_account_addr, proposal_id),
+        QueryMsg::GetConfig {} => query::get_config(deps),
+        QueryMsg::GetValidatorPolicy { validator_addr } => {
+            query::get_validator_policy(deps, validator_addr)
+        }
+        QueryMsg::GetAccountStake { user_account_addr } => {
+            query::get_account_stake(deps, user_account_addr)
+        }
+        QueryMsg::GetDearLeaderPower { dear_leader_addr } => {
+            query::get_dear_leader_power(deps, dear_leader_addr)
+        }
+        QueryMsg::VotingPowerAtHeight { address, height } => {
+            query::voting_power_at_height(deps, env, address, height)
+        }
+        QueryMsg::LeaderPowerAtHeight {
+            dear_leader_addr,
+            height,
+        } => query::leader_power_at_height(deps, env, dear_leader_addr, height),
+        QueryMsg::GetDearLeaderVotes {
+            dear_leader_addr,
+            start_after,
+            limit,
+        } => query::get_dear_leader_votes(deps, dear_leader_addr, start_after, limit),
+        QueryMsg::GetLeaderVote {
+            leader,
+            proposal_id,
+        } => query::get_leader_vote(deps, leader, proposal_id),
+        QueryMsg::GetAnnouncedVotes {
+            dear_leader_addr,
+            start_after,
+            limit,
+        } => query::get_announced_votes(deps, dear_leader_addr, start_after, limit),
+        QueryMsg::GetDelegatorAnnouncedVotes {
+            user_account_addr,
+            start_after,
+            limit,
+        } => query::get_delegator_announced_votes(deps, user_account_addr, start_after, limit),
+        QueryMsg::ListDearLeaders { start_after, limit } => {
+            query::list_dear_leaders(deps, start_after, limit)
+        }
+        QueryMsg::GetLeaderStats { dear_leader_addr } => {
+            query::get_leader_stats(deps, dear_leader_addr)
+        }
+        QueryMsg::GetLeaderChurn {
+            dear_leader_addr,
+            start_after,
+            limit,
+        } => query::get_leader_churn(deps, dear_leader_addr, start_after, limit),
+        QueryMsg::GetLeaderboard {
+            sort_by,
+            start_after,
+            limit,
+        } => query::get_leaderboard(deps, sort_by, start_after, limit),
+        QueryMsg::GetProposal { proposal_id } => query::get_proposal(deps, proposal_id),
+        QueryMsg::ListActiveProposals { start_after, limit } => {
+            query::list_active_proposals(deps, env, start_after, limit)
+        }
+        QueryMsg::GetVoteFallback { user_account_addr } => {
+            query::get_vote_fallback(deps, user_account_addr)
+        }
+        QueryMsg::GetLeaderDelegations { dear_leader_addr } => {
+            query::get_leader_delegations(deps, dear_leader_addr)
+        }
+        QueryMsg::GetDelegatedPower {
+            dear_leader_addr,
+            category,
+        } => query::get_delegated_power(deps, dear_leader_addr, category),
+    }
+}
+
+pub mod query {
+
+    use cosmwasm_std::{Decimal, Order, StdError, Uint128};
+    use cw_storage_plus::Bound;
+    use util_types::{GetValidatorPolicyResponse, ProposalStatus, VoteFallback};
+
+    use crate::{
+        msg::{
+            DearLeaderInfo, DelegatorAnnouncedVote, GetAccountStakeResponse,
+            GetAnnouncedVotesResponse, GetConfigResponse, GetDearLeaderDelegatoresResponse,
+            GetDearLeaderPowerResponse, GetDearLeaderVotesResponse, GetDelegatedPowerResponse,
+            GetDelegatorAnnouncedVotesResponse, GetLeaderChurnResponse,
+            GetLeaderDelegationsResponse, GetLeaderVoteResponse, GetLeaderboardResponse,
+            GetProposalResponse, GetVoteFallbackResponse, GetVoteProposalByUserAndPropResponse,
+            LeaderPowerAtHeightResponse, LeaderStatsResponse, LeaderboardSort,
+            ListDearLeadersResponse, ListProposalsResponse, VotingPowerAtHeightResponse,
+        },
+        state::{
+            VoteAnnouncement, ACCOUNT_STAKE, ANNOUNCED_VOTES, BOSS_VOTE_POWER,
+            DEAR_LEADER_ACCOUNT_FACTORY, DEAR_LEADER_DELEGATED_POWER, DEAR_LEADER_POWER,
+            DEAR_LEADER_PROFILES, DEAR_LEADER_VOTES, LEADERBOARD_KEYS, LEADERS_BY_DELEGATORS,
+            LEADERS_BY_POWER, LEADER_CHURN, LEADER_STATS, LEADER_VOTE_OPT_OUTS,
+            MAX_VALIDATOR_COMMISSION, PROPOSALS, PROPOSAL_COUNT, VALIDATOR_ALLOW_LIST,
+            VOTE_FALLBACKS,
+        },
+    };
+
+    use super::*;
+
+    pub fn get_dear_leader_delegators(
+        deps: Deps,
+        dear_leader_addr: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        if !DEAR_LEADERS.has(deps.storage, dear_leader_addr.clone()) {
+            return Err(StdError::generic_err("Dear Leader not registered"));
+        }
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let delegatores_list = DEAR_LEADER_DELEGATORS
+            .prefix(dear_leader_addr)
+            .keys(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .collect::<StdResult<Vec<String>>>()?;
+
+        let response = GetDearLeaderDelegatoresResponse {
+            delegatores_list: Some(delegatores_list).filter(|list| !list.is_empty()),
+        };
+
+        to_binary(&response)
+    }
+
+    pub fn get_user_account_leader(deps: Deps, user_account_addr: String) -> StdResult<Binary> {
+        let leader_addr = BOSS_VOTE_POWER
+            .load(deps.storage, user_account_addr)
+            .map_err(|_| -> StdError { StdError::generic_err("User Account not registered") })?;
+
+        to_binary(&leader_addr)
+    }
+
+    pub fn get_vote_proposal_by_user_and_prop(
+        deps: Deps,
+        user_account_addr: String,
+        proposal_id: u64,
+    ) -> StdResult<Binary> {
+        if !PROPOSALS.has(deps.storage, proposal_id) {
+            return Err(StdError::generic_err("Proposal not registered"));
+        }
+
+        let response = GetVoteProposalByUserAndPropResponse {
+            voted: PROPOSAL_VOTE_HISTORY.has(deps.storage, (proposal_id, user_account_addr)),
+        };
+        to_binary(&response)
+    }
+
+    pub fn get_config(deps: Deps) -> StdResult<Binary> {
+        let admin_addr = ADMIN_ADDR.load(deps.storage).unwrap_or_default();
+        let accounts_factory_addr = DEAR_LEADER_ACCOUNT_FACTORY
+            .load(deps.storage)
+            .unwrap_or_default();
+
+        let review_window = REVIEW_WINDOW
+            .may_load(deps.storage)?
+            .unwrap_or(DEFAULT_REVIEW_WINDOW);
 
         let resp = GetConfigResponse {
             admin_addr,
             accounts_factory_addr,
+            review_window,
         };
 
         to_binary(&resp)
     }
+
+    pub fn get_validator_policy(deps: Deps, validator_addr: String) -> StdResult<Binary> {
+        let max_commission = MAX_VALIDATOR_COMMISSION
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+
+        // an empty allow list means every validator is allowed
+        let allow_list_is_empty = VALIDATOR_ALLOW_LIST
+            .keys(deps.storage, None, None, Order::Ascending)
+            .next()
+            .is_none();
+        let is_allowed =
+            allow_list_is_empty || VALIDATOR_ALLOW_LIST.has(deps.storage, validator_addr);
+
+        to_binary(&GetValidatorPolicyResponse {
+            max_commission,
+            is_allowed,
+        })
+    }
+
+    pub fn get_account_stake(deps: Deps, user_account_addr: String) -> StdResult<Binary> {
+        let stake = ACCOUNT_STAKE
+            .may_load(deps.storage, user_account_addr)?
+            .unwrap_or_default();
+
+        to_binary(&GetAccountStakeResponse { stake })
+    }
+
+    pub fn get_dear_leader_power(deps: Deps, dear_leader_addr: String) -> StdResult<Binary> {
+        let power = DEAR_LEADER_POWER
+            .may_load(deps.storage, dear_leader_addr)?
+            .unwrap_or_default();
+
+        to_binary(&GetDearLeaderPowerResponse { power })
+    }
+
+    pub fn voting_power_at_height(
+        deps: Deps,
+        env: Env,
+        address: String,
+        height: Option<u64>,
+    ) -> StdResult<Binary> {
+        let height = height.unwrap_or(env.block.height);
+        let power = ACCOUNT_STAKE
+            .may_load_at_height(deps.storage, address.clone(), height)?
+            .unwrap_or_default();
+        let dear_leader_addr = BOSS_VOTE_POWER
+            .may_load_at_height(deps.storage, address, height)?
+            .flatten();
+
+        to_binary(&VotingPowerAtHeightResponse {
+            power,
+            dear_leader_addr,
+            height,
+        })
+    }
+
+    pub fn leader_power_at_height(
+        deps: Deps,
+        env: Env,
+        dear_leader_addr: String,
+        height: Option<u64>,
+    ) -> StdResult<Binary> {
+        let height = height.unwrap_or(env.block.height);
+        let power = DEAR_LEADER_POWER
+            .may_load_at_height(deps.storage, dear_leader_addr.clone(), height)?
+            .unwrap_or_default();
+        let delegated_power = DEAR_LEADER_DELEGATED_POWER
+            .may_load_at_height(deps.storage, dear_leader_addr, height)?
+            .unwrap_or_default();
+
+        to_binary(&LeaderPowerAtHeightResponse {
+            power,
+            delegated_power,
+            height,
+        })
+    }
+
+    pub fn get_dear_leader_votes(
+        deps: Deps,
+        dear_leader_addr: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let votes = DEAR_LEADER_VOTES
+            .prefix(dear_leader_addr)
+            .range(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .map(|item| item.map(|(_, vote)| vote))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        to_binary(&GetDearLeaderVotesResponse { votes })
+    }
+
+    pub fn get_leader_vote(deps: Deps, leader: String, proposal_id: u64) -> StdResult<Binary> {
+        let vote = DEAR_LEADER_VOTES.may_load(deps.storage, (leader, proposal_id))?;
+
+        to_binary(&GetLeaderVoteResponse { vote })
+    }
+
+    pub fn get_announced_votes(
+        deps: Deps,
+        dear_leader_addr: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let announcements = load_announced_votes(deps, dear_leader_addr, start_after, limit)?;
+
+        to_binary(&GetAnnouncedVotesResponse { announcements })
+    }
+
+    pub fn get_delegator_announced_votes(
+        deps: Deps,
+        user_account_addr: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        // accounts without a dear leader have nothing to review
+        let announcements = match BOSS_VOTE_POWER
+            .may_load(deps.storage, user_account_addr.clone())?
+            .flatten()
+        {
+            Some(dear_leader) => load_announced_votes(deps, dear_leader, start_after, limit)?
+                .into_iter()
+                .map(|announcement| {
+                    let opted_out = LEADER_VOTE_OPT_OUTS.has(
+                        deps.storage,
+                        (announcement.vote.proposal_id, user_account_addr.clone()),
+                    );
+                    DelegatorAnnouncedVote {
+                        announcement,
+                        opted_out,
+                    }
+                })
+                .collect(),
+            None => vec![],
+        };
+
+        to_binary(&GetDelegatorAnnouncedVotesResponse { announcements })
+    }
+
+    fn load_announced_votes(
+        deps: Deps,
+        dear_leader_addr: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<VoteAnnouncement>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        ANNOUNCED_VOTES
+            .prefix(dear_leader_addr)
+            .range(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .map(|item| item.map(|(_, announcement)| announcement))
+            .collect()
+    }
+
+    pub fn list_dear_leaders(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let dear_leaders = DEAR_LEADERS
+            .range(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .map(|item| {
+                let (dear_leader_addr, delegators) = item?;
+                Ok(DearLeaderInfo {
+                    profile: DEAR_LEADER_PROFILES
+                        .may_load(deps.storage, dear_leader_addr.clone())?,
+                    delegators,
+                    power: DEAR_LEADER_POWER
+                        .may_load(deps.storage, dear_leader_addr.clone())?
+                        .unwrap_or_default(),
+                    delegated_power: delegated_power(deps.storage, &dear_leader_addr)?,
+                    dear_leader_addr,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        to_binary(&ListDearLeadersResponse { dear_leaders })
+    }
+
+    pub fn get_leader_stats(deps: Deps, dear_leader_addr: String) -> StdResult<Binary> {
+        to_binary(&leader_stats(deps, dear_leader_addr)?)
+    }
+
+    pub fn get_leader_churn(
+        deps: Deps,
+        dear_leader_addr: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let periods = LEADER_CHURN
+            .prefix(dear_leader_addr)
+            .range(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .map(|item| item.map(|(_, period)| period))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        to_binary(&GetLeaderChurnResponse { periods })
+    }
+
+    // voting power and delegators page over their indexes, participation rate is computed and
+    // sorted for every registered dear leader on each call
+    pub fn get_leaderboard(
+        deps: Deps,
+        sort_by: LeaderboardSort,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        // the page continues after the dear leader where it is on the board now. A dear leader
+        // that retired between pages has no place left to continue from
+        let start_after = match start_after {
+            Some(dear_leader_addr) => {
+                match LEADERBOARD_KEYS.may_load(deps.storage, dear_leader_addr.clone())? {
+                    Some(keys) => Some((keys, dear_leader_addr)),
+                    None => return to_binary(&GetLeaderboardResponse { leaders: vec![] }),
+                }
+            }
+            None => None,
+        };
+
+        // best first, ties in reverse address order
+        let dear_leader_addrs = match sort_by {
+            LeaderboardSort::VotingPower => LEADERS_BY_POWER
+                .keys(
+                    deps.storage,
+                    None,
+                    start_after.map(|((power, _), addr)| Bound::exclusive((power, addr))),
+                    Order::Descending,
+                )
+                .take(limit)
+                .map(|key| key.map(|(_, dear_leader_addr)| dear_leader_addr))
+                .collect::<StdResult<Vec<_>>>()?,
+            LeaderboardSort::Delegators => LEADERS_BY_DELEGATORS
+                .keys(
+                    deps.storage,
+                    None,
+                    start_after.map(|((_, delegators), addr)| Bound::exclusive((delegators, addr))),
+                    Order::Descending,
+                )
+                .take(limit)
+                .map(|key| key.map(|(_, dear_leader_addr)| dear_leader_addr))
+                .collect::<StdResult<Vec<_>>>()?,
+            // the rate of every active dear leader moves with each registered proposal so it
+            // can't be indexed. This is not paged internally, it is bounded by the number of
+            // dear leaders, which are only registered through the factory admin
+            LeaderboardSort::ParticipationRate => {
+                let mut rates = LEADERBOARD_KEYS
+                    .keys(deps.storage, None, None, Order::Ascending)
+                    .map(|dear_leader_addr| {
+                        let stats = leader_stats(deps, dear_leader_addr?)?;
+                        Ok((stats.participation_rate, stats.dear_leader_addr))
+                    })
+                    .collect::<StdResult<Vec<_>>>()?;
+                rates.sort_by(|a, b| b.cmp(a));
+                // start_after is on the board, checked above
+                let start = match start_after {
+                    Some((_, addr)) => rates
+                        .iter()
+                        .position(|(_, dear_leader_addr)| *dear_leader_addr == addr)
+                        .map_or(rates.len(), |position| position + 1),
+                    None => 0,
+                };
+                rates
+                    .into_iter()
+                    .skip(start)
+                    .take(limit)
+                    .map(|(_, dear_leader_addr)| dear_leader_addr)
+                    .collect()
+            }
+        };
+        let leaders = dear_leader_addrs
+            .into_iter()
+            .map(|dear_leader_addr| leader_stats(deps, dear_leader_addr))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        to_binary(&GetLeaderboardResponse { leaders })
+    }
+
+    fn leader_stats(deps: Deps, dear_leader_addr: String) -> StdResult<LeaderStatsResponse> {
+        let stats = LEADER_STATS
+            .may_load(deps.storage, dear_leader_addr.clone())?
+            .unwrap_or_default();
+        let proposal_count = PROPOSAL_COUNT.may_load(deps.storage)?.unwrap_or_default();
+
+        // the current period counts up to the last registered proposal
+        let eligible_proposals = stats.eligible_proposals
+            + stats
+                .active_since
+                .map_or(0, |active_since| proposal_count - active_since);
+        // votes on proposals registered before the first delegator arrived can exceed them
+        let participation_rate = if eligible_proposals == 0 {
+            Decimal::zero()
+        } else {
+            Decimal::from_ratio(stats.votes_cast.min(eligible_proposals), eligible_proposals)
+        };
+
+        Ok(LeaderStatsResponse {
+            delegators: DEAR_LEADERS
+                .may_load(deps.storage, dear_leader_addr.clone())?
+                .unwrap_or_default(),
+            power: DEAR_LEADER_POWER
+                .may_load(deps.storage, dear_leader_addr.clone())?
+                .unwrap_or_default(),
+            delegated_power: delegated_power(deps.storage, &dear_leader_addr)?,
+            votes_cast: stats.votes_cast,
+            eligible_proposals,
+            participation_rate,
+            overrides: stats.overrides,
+            delegators_gained: stats.delegators_gained,
+            delegators_lost: stats.delegators_lost,
+            dear_leader_addr,
+        })
+    }
+
+    pub fn get_proposal(deps: Deps, proposal_id: u64) -> StdResult<Binary> {
+        let proposal = PROPOSALS.may_load(deps.storage, proposal_id)?;
+
+        to_binary(&GetProposalResponse { proposal })
+    }
+
+    pub fn list_active_proposals(
+        deps: Deps,
+        env: Env,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let proposals = PROPOSALS
+            .range(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .filter(|item| {
+                item.as_ref().map_or(true, |(_, proposal)| {
+                    proposal.status == ProposalStatus::VotingPeriod
+                        && env.block.time <= proposal.voting_end_time
+                })
+            })
+            .take(limit)
+            .map(|item| item.map(|(_, proposal)| proposal))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        to_binary(&ListProposalsResponse { proposals })
+    }
+
+    pub fn get_vote_fallback(deps: Deps, user_account_addr: String) -> StdResult<Binary> {
+        let fallback = VOTE_FALLBACKS
+            .may_load(deps.storage, user_account_addr)?
+            .unwrap_or(VoteFallback::Nothing {});
+
+        to_binary(&GetVoteFallbackResponse { fallback })
+    }
+
+    pub fn get_leader_delegations(deps: Deps, dear_leader_addr: String) -> StdResult<Binary> {
+        let delegations = LEADER_DELEGATIONS
+            .may_load(deps.storage, dear_leader_addr)?
+            .unwrap_or_default();
+
+        to_binary(&GetLeaderDelegationsResponse { delegations })
+    }
+
+    pub fn get_delegated_power(
+        deps: Deps,
+        dear_leader_addr: String,
+        category: Option<String>,
+    ) -> StdResult<Binary> {
+        let power = DEAR_LEADER_POWER
+            .may_load(deps.storage, dear_leader_addr.clone())?
+            .unwrap_or_default();
+        let followers =
+            transitive_followers(deps.storage, &dear_leader_addr, category.as_deref(), None)?;
+        let delegated_power = followers
+            .iter()
+            .map(|follower| {
+                DEAR_LEADER_POWER
+                    .may_load(deps.storage, follower.to_string())
+                    .map(Option::unwrap_or_default)
+            })
+            .sum::<StdResult<Uint128>>()?;
+
+        to_binary(&GetDelegatedPowerResponse {
+            power,
+            delegated_power,
+            followers,
+        })
+    }
+}
+
+pub mod migration {
+    use cosmwasm_std::Addr;
+
+    use crate::state::{LEGACY_DEAR_LEADER_BOARD, LEGACY_PROPOSAL_VOTE_HISTORY};
+
+    use super::*;
+
+    // moves up to `limit` delegators and votes from the vec values to composite keys. What is
+    // left of a partly moved list is saved back, so the legacy maps are the cursor the next page
+    // starts from. Returns the number of entries moved and whether the legacy maps are empty.
+    // The stake of each moved delegator is read from the chain, so dear leaders get their power
+    // back. User accounts that didn't delegate get theirs from SyncAccounts.
+    pub fn migrate_to_composite_keys(
+        mut deps: DepsMut,
+        height: u64,
+        limit: usize,
+    ) -> StdResult<(u64, bool)> {
+        let mut remaining = limit;
+
+        while remaining > 0 {
+            let (dear_leader, delegators) = match LEGACY_DEAR_LEADER_BOARD
+                .range(deps.storage, None, None, Order::Ascending)
+                .next()
+                .transpose()?
+            {
+                Some(entry) => entry,
+                None => break,
+            };
+            let mut delegators = delegators.unwrap_or_default();
+            let page = take_page(&mut delegators, &mut remaining);
+            let count = DEAR_LEADERS
+                .may_load(deps.storage, dear_leader.clone())?
+                .unwrap_or_default();
+            DEAR_LEADERS.save(
+                deps.storage,
+                dear_leader.clone(),
+                &(count + page.len() as u64),
+            )?;
+            for user_account in page {
+                DEAR_LEADER_DELEGATORS.save(
+                    deps.storage,
+                    (dear_leader.clone(), user_account.clone()),
+                    &true,
+                    height,
+                )?;
+                execute::sync_stake(deps.branch(), height, &Addr::unchecked(user_account))?;
+            }
+            execute::index_leader(deps.storage, &dear_leader)?;
+            if delegators.is_empty() {
+                LEGACY_DEAR_LEADER_BOARD.remove(deps.storage, dear_leader);
+            } else {
+                LEGACY_DEAR_LEADER_BOARD.save(deps.storage, dear_leader, &Some(delegators))?;
+            }
+        }
+
+        while remaining > 0 {
+            let (proposal_id, mut user_accounts) = match LEGACY_PROPOSAL_VOTE_HISTORY
+                .range(deps.storage, None, None, Order::Ascending)
+                .next()
+                .transpose()?
+            {
+                Some(entry) => entry,
+                None => break,
+            };
+            for user_account in take_page(&mut user_accounts, &mut remaining) {
+                PROPOSAL_VOTE_HISTORY.save(deps.storage, (proposal_id, user_account), &true)?;
+            }
+            if user_accounts.is_empty() {
+                LEGACY_PROPOSAL_VOTE_HISTORY.remove(deps.storage, proposal_id);
+            } else {
+                LEGACY_PROPOSAL_VOTE_HISTORY.save(deps.storage, proposal_id, &user_accounts)?;
+            }
+        }
+
+        let done = LEGACY_DEAR_LEADER_BOARD.is_empty(deps.storage)
+            && LEGACY_PROPOSAL_VOTE_HISTORY.is_empty(deps.storage);
+        Ok(((limit - remaining) as u64, done))
+    }
+
+    // splits off the last entries of the list that still fit in the page
+    fn take_page(entries: &mut Vec<String>, remaining: &mut usize) -> Vec<String> {
+        let page = entries.split_off(entries.len().saturating_sub(*remaining));
+        *remaining -= page.len();
+        page
+    }
 }
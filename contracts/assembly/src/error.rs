@@ -33,14 +33,56 @@ pub enum ContractError {
     #[error("User account not registered")]
     AccountNotRegistered {},
 
-    #[error("Proposal was not registered yet")]
+    #[error("Proposal was not registered yet, sync it first")]
     ProposalNotRegistered {},
 
+    #[error("Proposal is not in its voting period yet")]
+    ProposalNotInVotingPeriod {},
+
     #[error("The vote power for this user account is already registered")]
     UserAccountAlreadyRegister {},
 
     #[error("There is a bug somehere")]
     InternalErrorInLogic {},
+
+    #[error("Validator not found")]
+    ValidatorNotFound {},
+
+    #[error("Commission must be between 0 and 1")]
+    InvalidCommission {},
+
+    #[error("Profile is missing a name or a field is too long")]
+    InvalidProfile {},
+
+    #[error("Rationale is empty or too long")]
+    InvalidRationale {},
+
+    #[error("Voting period of the proposal is over")]
+    VotingEnded {},
+
+    #[error("Announced vote not found")]
+    AnnouncementNotFound {},
+
+    #[error("The dear leader did not vote on this proposal")]
+    LeaderVoteNotFound {},
+
+    #[error("Delegators can still review the announced vote")]
+    ReviewWindowNotOver {},
+
+    #[error("Dear leader is not retiring")]
+    DearLeaderNotRetiring {},
+
+    #[error("Fallbacks can only be applied in the last hours of the voting period")]
+    FallbackWindowNotOpen {},
+
+    #[error("Dear leaders can't follow themselves nor defer a category to two dear leaders")]
+    InvalidLeaderDelegation {},
+
+    #[error("Dear leader is not followed")]
+    LeaderDelegationNotFound {},
+
+    #[error("Only an assembly can be migrated to this contract")]
+    CannotMigrate {},
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
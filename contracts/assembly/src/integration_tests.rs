@@ -1,13 +1,37 @@
 #[cfg(test)]
 mod tests {
     use std::borrow::BorrowMut;
+    use std::cell::Cell;
+    use std::marker::PhantomData;
 
-    use cosmwasm_std::{coins, Addr, Coin, Empty, Uint128};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockStorage};
+    use cosmwasm_std::{
+        coin, coins, from_binary, Addr, Coin, Decimal, Empty, Event, FullDelegation, Order,
+        OwnedDeps, Record, Response, Storage, Timestamp, Uint128, Validator,
+    };
     use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+    use cw_storage_plus::Map;
 
     use crate::{
-        contract::{execute, instantiate, query},
-        msg::{ExecuteMsg, GetConfigResponse, InstantiateMsg},
+        contract::{execute, instantiate, migrate, query},
+        msg::{
+            ExecuteMsg, GetAccountStakeResponse, GetAnnouncedVotesResponse, GetConfigResponse,
+            GetDearLeaderDelegatoresResponse, GetDearLeaderPowerResponse,
+            GetDelegatedPowerResponse, GetDelegatorAnnouncedVotesResponse, GetLeaderChurnResponse,
+            GetLeaderVoteResponse, GetLeaderboardResponse, GetProposalResponse,
+            GetVoteFallbackResponse, GetVoteProposalByUserAndPropResponse, InstantiateMsg,
+            LeaderPowerAtHeightResponse, LeaderStatsResponse, LeaderboardSort,
+            ListDearLeadersResponse, ListProposalsResponse, MigrateMsg, QueryMsg,
+            VotingPowerAtHeightResponse,
+        },
+        state::{LEGACY_DEAR_LEADER_BOARD, LEGACY_PROPOSAL_VOTE_HISTORY},
+        ContractError,
+    };
+    use util_types::proto::GovProposal;
+    use util_types::testing::{mock_dependencies_with_gov, MockGovQuerier};
+    use util_types::{
+        DearLeaderProfile, ExecuteMsg as CommonExecuteMsg, GetValidatorPolicyResponse,
+        ProposalStatus, SocialHandle, VoteFallback, VoteRationale,
     };
 
     const ADMIN: &str = "admin";
@@ -19,6 +43,37 @@ mod tests {
         Box::new(contract)
     }
 
+    fn gov_proposal(
+        proposal_id: u64,
+        status: ProposalStatus,
+        voting_end_time: Timestamp,
+    ) -> GovProposal {
+        GovProposal {
+            proposal_id,
+            content_type: "/cosmos.gov.v1beta1.TextProposal".to_string(),
+            title: format!("Proposal {}", proposal_id),
+            status,
+            voting_end_time,
+        }
+    }
+
+    fn attr(res: &Response, key: &str) -> String {
+        res.attributes
+            .iter()
+            .find(|attribute| attribute.key == key)
+            .map(|attribute| attribute.value.clone())
+            .unwrap_or_default()
+    }
+
+    fn event_attr(event: &Event, key: &str) -> String {
+        event
+            .attributes
+            .iter()
+            .find(|attribute| attribute.key == key)
+            .map(|attribute| attribute.value.clone())
+            .unwrap_or_default()
+    }
+
     fn bank_balance(router: &mut App, addr: &Addr, denom: String) -> Coin {
         router
             .wrap()
@@ -82,4 +137,2174 @@ mod tests {
         assert_eq!(config.admin_addr, ADMIN);
         assert_eq!(config.accounts_factory_addr, ACCOUNT_FACTORY);
     }
+
+    #[test]
+    fn test_validator_policy() {
+        let mut app = App::default();
+
+        let assembly_code_id = app.store_code(assembly_contract());
+        let assembly = app
+            .instantiate_contract(
+                assembly_code_id,
+                Addr::unchecked(ADMIN),
+                &InstantiateMsg {},
+                &[],
+                "assembly",
+                None,
+            )
+            .unwrap();
+
+        // without a policy every validator is allowed and there is no commission cap
+        let policy: GetValidatorPolicyResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &assembly,
+                &crate::msg::QueryMsg::GetValidatorPolicy {
+                    validator_addr: "validator".to_string(),
+                },
+            )
+            .unwrap();
+        assert!(policy.is_allowed);
+        assert_eq!(policy.max_commission, None);
+
+        // only the admin can set the commission cap
+        app.execute_contract(
+            Addr::unchecked(ACCOUNT_FACTORY),
+            assembly.clone(),
+            &ExecuteMsg::SetMaxValidatorCommission {
+                max_commission: Some(Decimal::percent(10)),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        // commission cap can't be above 100%
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            assembly.clone(),
+            &ExecuteMsg::SetMaxValidatorCommission {
+                max_commission: Some(Decimal::percent(101)),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            assembly.clone(),
+            &ExecuteMsg::SetMaxValidatorCommission {
+                max_commission: Some(Decimal::percent(10)),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let policy: GetValidatorPolicyResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &assembly,
+                &crate::msg::QueryMsg::GetValidatorPolicy {
+                    validator_addr: "validator".to_string(),
+                },
+            )
+            .unwrap();
+        assert!(policy.is_allowed);
+        assert_eq!(policy.max_commission, Some(Decimal::percent(10)));
+    }
+
+    fn delegation(delegator: &str, amount: u128) -> FullDelegation {
+        FullDelegation {
+            delegator: Addr::unchecked(delegator),
+            validator: "validator".to_string(),
+            amount: coin(amount, USED_DENOM),
+            can_redelegate: coin(amount, USED_DENOM),
+            accumulated_rewards: vec![],
+        }
+    }
+
+    // cw-multi-test has no staking module yet, so the stake sync runs against the mock querier
+    #[test]
+    fn test_stake_sync() {
+        let mut deps = mock_dependencies();
+        let validators = [Validator {
+            address: "validator".to_string(),
+            commission: Decimal::percent(5),
+            max_commission: Decimal::percent(100),
+            max_change_rate: Decimal::percent(1),
+        }];
+        deps.querier
+            .update_staking(USED_DENOM, &validators, &[delegation("account", 100)]);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADMIN, &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+        for (sender, msg) in [
+            (
+                ADMIN,
+                ExecuteMsg::SetAccountFactoryAddr {
+                    account_factory_addr: ACCOUNT_FACTORY.to_string(),
+                },
+            ),
+            (
+                ACCOUNT_FACTORY,
+                ExecuteMsg::RegisterDearLeader {
+                    new_dear_leader_addr: "leader".to_string(),
+                },
+            ),
+            ("account", ExecuteMsg::RegisterUserAccount {}),
+            (
+                "account",
+                ExecuteMsg::TransferVotePower {
+                    dear_leader_addr: "leader".to_string(),
+                },
+            ),
+        ] {
+            execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).unwrap();
+        }
+
+        let power = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| -> Uint128 {
+            let res: GetDearLeaderPowerResponse = from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::GetDearLeaderPower {
+                        dear_leader_addr: "leader".to_string(),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            res.power
+        };
+        assert_eq!(power(&deps), Uint128::new(100));
+
+        // the validator gets slashed, the crank brings the power back in line with the chain
+        deps.querier
+            .update_staking(USED_DENOM, &validators, &[delegation("account", 80)]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::SyncAccounts {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(event_attr(&res.events[0], "delta"), "-20");
+        assert_eq!(power(&deps), Uint128::new(80));
+
+        let stake: GetAccountStakeResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetAccountStake {
+                    user_account_addr: "account".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(stake.stake, Uint128::new(80));
+
+        // unregistered accounts can't report their stake
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("stranger", &[]),
+            ExecuteMsg::SyncAccountStake {},
+        )
+        .unwrap_err();
+
+        // reclaiming the vote power takes the stake away from the dear leader
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("account", &[]),
+            ExecuteMsg::ReclaimVotePower {},
+        )
+        .unwrap();
+        assert_eq!(power(&deps), Uint128::zero());
+    }
+
+    #[test]
+    fn test_dear_leader_profiles() {
+        let mut app = App::default();
+
+        let assembly_code_id = app.store_code(assembly_contract());
+        let assembly = app
+            .instantiate_contract(
+                assembly_code_id,
+                Addr::unchecked(ADMIN),
+                &InstantiateMsg {},
+                &[],
+                "assembly",
+                None,
+            )
+            .unwrap();
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            assembly.clone(),
+            &ExecuteMsg::SetAccountFactoryAddr {
+                account_factory_addr: ACCOUNT_FACTORY.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+        for dear_leader in ["leader_a", "leader_b"] {
+            app.execute_contract(
+                Addr::unchecked(ACCOUNT_FACTORY),
+                assembly.clone(),
+                &ExecuteMsg::RegisterDearLeader {
+                    new_dear_leader_addr: dear_leader.to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        let mut profile = DearLeaderProfile {
+            name: "Leader A".to_string(),
+            description: Some("Votes on every proposal".to_string()),
+            website: None,
+            socials: vec![SocialHandle {
+                platform: "twitter".to_string(),
+                handle: "@leader_a".to_string(),
+            }],
+            logo_url: None,
+            voting_philosophy: None,
+        };
+
+        // only registered dear leaders can publish a profile
+        app.execute_contract(
+            Addr::unchecked("stranger"),
+            assembly.clone(),
+            &ExecuteMsg::SetDearLeaderProfile {
+                profile: profile.clone(),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        // names are limited to 64 characters
+        profile.name = "a".repeat(65);
+        app.execute_contract(
+            Addr::unchecked("leader_a"),
+            assembly.clone(),
+            &ExecuteMsg::SetDearLeaderProfile {
+                profile: profile.clone(),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        profile.name = "Leader A".to_string();
+        app.execute_contract(
+            Addr::unchecked("leader_a"),
+            assembly.clone(),
+            &ExecuteMsg::SetDearLeaderProfile {
+                profile: profile.clone(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let list: ListDearLeadersResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &assembly,
+                &QueryMsg::ListDearLeaders {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(list.dear_leaders.len(), 2);
+        assert_eq!(list.dear_leaders[0].profile, Some(profile));
+        assert_eq!(list.dear_leaders[0].delegators, 0);
+        assert_eq!(list.dear_leaders[1].profile, None);
+
+        let list: ListDearLeadersResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &assembly,
+                &QueryMsg::ListDearLeaders {
+                    start_after: Some("leader_a".to_string()),
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(list.dear_leaders.len(), 1);
+        assert_eq!(list.dear_leaders[0].dear_leader_addr, "leader_b");
+    }
+
+    #[test]
+    fn test_dear_leader_vote_rationale() {
+        let mut deps = mock_dependencies_with_gov();
+        deps.querier.set_proposal(gov_proposal(
+            1,
+            ProposalStatus::VotingPeriod,
+            mock_env().block.time.plus_seconds(3600),
+        ));
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADMIN, &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+        for (sender, msg) in [
+            (
+                ADMIN,
+                ExecuteMsg::SetAccountFactoryAddr {
+                    account_factory_addr: ACCOUNT_FACTORY.to_string(),
+                },
+            ),
+            (
+                ACCOUNT_FACTORY,
+                ExecuteMsg::RegisterDearLeader {
+                    new_dear_leader_addr: "leader".to_string(),
+                },
+            ),
+            ("account", ExecuteMsg::RegisterUserAccount {}),
+            (
+                "account",
+                ExecuteMsg::TransferVotePower {
+                    dear_leader_addr: "leader".to_string(),
+                },
+            ),
+            ("anyone", ExecuteMsg::SyncProposal { proposal_id: 1 }),
+        ] {
+            execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).unwrap();
+        }
+
+        // empty rationales are rejected
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("leader", &[]),
+            ExecuteMsg::DearLeaderVote {
+                proposal_id: 1,
+                vote_option: 2,
+                rationale: Some(VoteRationale::Text {
+                    text: " ".to_string(),
+                }),
+            },
+        )
+        .unwrap_err();
+
+        let rationale = VoteRationale::Uri {
+            uri: "ipfs://rationale".to_string(),
+            hash: "abcdef".to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("leader", &[]),
+            ExecuteMsg::DearLeaderVote {
+                proposal_id: 1,
+                vote_option: 2,
+                rationale: Some(rationale.clone()),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.events[0].ty, "dear_leader_vote");
+        assert_eq!(
+            event_attr(&res.events[0], "rationale_uri"),
+            "ipfs://rationale"
+        );
+
+        let vote: GetLeaderVoteResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetLeaderVote {
+                    leader: "leader".to_string(),
+                    proposal_id: 1,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let vote = vote.vote.unwrap();
+        assert_eq!(vote.vote_option, 2);
+        assert_eq!(vote.rationale, Some(rationale));
+    }
+
+    #[test]
+    fn test_announced_vote_opt_out() {
+        let voting_end = mock_env().block.time.plus_seconds(1800);
+        let mut deps = mock_dependencies_with_gov();
+        deps.querier
+            .set_proposal(gov_proposal(1, ProposalStatus::VotingPeriod, voting_end));
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADMIN, &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+        let mut msgs = vec![
+            (
+                ADMIN,
+                ExecuteMsg::SetAccountFactoryAddr {
+                    account_factory_addr: ACCOUNT_FACTORY.to_string(),
+                },
+            ),
+            (
+                ADMIN,
+                ExecuteMsg::SetReviewWindow {
+                    review_window: 3600,
+                },
+            ),
+            (
+                ACCOUNT_FACTORY,
+                ExecuteMsg::RegisterDearLeader {
+                    new_dear_leader_addr: "leader".to_string(),
+                },
+            ),
+        ];
+        for account in ["account_a", "account_b"] {
+            msgs.push((account, ExecuteMsg::RegisterUserAccount {}));
+            msgs.push((
+                account,
+                ExecuteMsg::TransferVotePower {
+                    dear_leader_addr: "leader".to_string(),
+                },
+            ));
+        }
+        msgs.push(("anyone", ExecuteMsg::SyncProposal { proposal_id: 1 }));
+        for (sender, msg) in msgs {
+            execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).unwrap();
+        }
+
+        // the review window is capped by the end of the voting period
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("leader", &[]),
+            ExecuteMsg::AnnounceDearLeaderVote {
+                proposal_id: 1,
+                vote_option: 2,
+                rationale: None,
+            },
+        )
+        .unwrap();
+        let announced: GetAnnouncedVotesResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetAnnouncedVotes {
+                    dear_leader_addr: "leader".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(announced.announcements.len(), 1);
+        assert_eq!(announced.announcements[0].execute_at, voting_end);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("account_a", &[]),
+            ExecuteMsg::OptOutOfLeaderVote { proposal_id: 1 },
+        )
+        .unwrap();
+        let delegator: GetDelegatorAnnouncedVotesResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetDelegatorAnnouncedVotes {
+                    user_account_addr: "account_a".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(delegator.announcements[0].opted_out);
+
+        let execute_msg = ExecuteMsg::ExecuteAnnouncedVote {
+            dear_leader_addr: "leader".to_string(),
+            proposal_id: 1,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            execute_msg.clone(),
+        )
+        .unwrap_err();
+
+        // once the window is over only the delegators that did not opt out vote
+        let mut env = mock_env();
+        env.block.time = voting_end;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            execute_msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+        execute(deps.as_mut(), env, mock_info("anyone", &[]), execute_msg).unwrap_err();
+    }
+
+    #[test]
+    fn test_retire_dear_leader() {
+        let mut deps = mock_dependencies();
+        let accounts: Vec<String> = (0..12).map(|i| format!("account_{:02}", i)).collect();
+        let validators = [Validator {
+            address: "validator".to_string(),
+            commission: Decimal::percent(5),
+            max_commission: Decimal::percent(100),
+            max_change_rate: Decimal::percent(1),
+        }];
+        let delegations: Vec<FullDelegation> = accounts
+            .iter()
+            .map(|account| delegation(account, 10))
+            .collect();
+        deps.querier
+            .update_staking(USED_DENOM, &validators, &delegations);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADMIN, &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+        let mut msgs = vec![
+            (
+                ADMIN.to_string(),
+                ExecuteMsg::SetAccountFactoryAddr {
+                    account_factory_addr: ACCOUNT_FACTORY.to_string(),
+                },
+            ),
+            (
+                ACCOUNT_FACTORY.to_string(),
+                ExecuteMsg::RegisterDearLeader {
+                    new_dear_leader_addr: "leader".to_string(),
+                },
+            ),
+        ];
+        for account in accounts.iter() {
+            msgs.push((account.clone(), ExecuteMsg::RegisterUserAccount {}));
+            msgs.push((
+                account.clone(),
+                ExecuteMsg::TransferVotePower {
+                    dear_leader_addr: "leader".to_string(),
+                },
+            ));
+        }
+        for (sender, msg) in msgs {
+            execute(deps.as_mut(), mock_env(), mock_info(&sender, &[]), msg).unwrap();
+        }
+
+        // the first batch is released right away, every delegator gets an event
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("leader", &[]),
+            ExecuteMsg::RetireDearLeader {},
+        )
+        .unwrap();
+        assert_eq!(attr(&res, "released"), "10");
+        assert_eq!(attr(&res, "remaining"), "2");
+        assert_eq!(res.events.len(), 11);
+        assert_eq!(res.events[10].ty, "dear_leader_retired");
+
+        let leader_of = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, account: &str| {
+            from_binary::<Option<String>>(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::GetUserAccountLeader {
+                        user_account_addr: account.to_string(),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+        assert_eq!(leader_of(&deps, "account_00"), None);
+        assert_eq!(leader_of(&deps, "account_11"), Some("leader".to_string()));
+
+        // the retired leader can't take new delegators nor vote
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("account_00", &[]),
+            ExecuteMsg::TransferVotePower {
+                dear_leader_addr: "leader".to_string(),
+            },
+        )
+        .unwrap_err();
+
+        // delegators not released yet can still leave on their own
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("account_10", &[]),
+            ExecuteMsg::ReclaimVotePower {},
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::ReleaseRetiredDelegators {
+                dear_leader_addr: "leader".to_string(),
+                limit: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(event_attr(&res.events[0], "user_account"), "account_11");
+        assert_eq!(leader_of(&deps, "account_11"), None);
+
+        let power: GetDearLeaderPowerResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetDearLeaderPower {
+                    dear_leader_addr: "leader".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(power.power, Uint128::zero());
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::ReleaseRetiredDelegators {
+                dear_leader_addr: "leader".to_string(),
+                limit: None,
+            },
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_leader_stats() {
+        let mut deps = mock_dependencies_with_gov();
+        for proposal_id in 1..=3 {
+            deps.querier.set_proposal(gov_proposal(
+                proposal_id,
+                ProposalStatus::VotingPeriod,
+                mock_env().block.time.plus_seconds(3600),
+            ));
+        }
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADMIN, &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+
+        let transfer = |dear_leader_addr: &str| ExecuteMsg::TransferVotePower {
+            dear_leader_addr: dear_leader_addr.to_string(),
+        };
+        let user_vote = |proposal_id| ExecuteMsg::UserAccountVote {
+            proposal_id,
+            vote_option: 1,
+        };
+        let leader_vote = |proposal_id| ExecuteMsg::DearLeaderVote {
+            proposal_id,
+            vote_option: 1,
+            rationale: None,
+        };
+        let mut msgs = vec![(
+            ADMIN,
+            ExecuteMsg::SetAccountFactoryAddr {
+                account_factory_addr: ACCOUNT_FACTORY.to_string(),
+            },
+        )];
+        for dear_leader in ["leader_a", "leader_b"] {
+            msgs.push((
+                ACCOUNT_FACTORY,
+                ExecuteMsg::RegisterDearLeader {
+                    new_dear_leader_addr: dear_leader.to_string(),
+                },
+            ));
+        }
+        for account in ["account_a", "account_b", "account_c"] {
+            msgs.push((account, ExecuteMsg::RegisterUserAccount {}));
+        }
+        msgs.extend([
+            ("account_a", transfer("leader_a")),
+            ("account_b", transfer("leader_a")),
+            ("account_c", transfer("leader_b")),
+            ("anyone", ExecuteMsg::SyncProposal { proposal_id: 1 }),
+            ("anyone", ExecuteMsg::SyncProposal { proposal_id: 2 }),
+            // account_a overrides leader_a on proposal 1, account_c overrides leader_b on 2
+            ("account_a", user_vote(1)),
+            ("leader_a", leader_vote(1)),
+            ("leader_a", leader_vote(1)),
+            ("account_c", user_vote(2)),
+            (
+                "account_c",
+                ExecuteMsg::OptOutOfLeaderVote { proposal_id: 2 },
+            ),
+            ("leader_a", leader_vote(2)),
+            // leader_b loses its only delegator, proposal 3 doesn't count for it
+            ("account_c", transfer("leader_a")),
+            ("anyone", ExecuteMsg::SyncProposal { proposal_id: 3 }),
+            ("account_b", user_vote(3)),
+        ]);
+        for (sender, msg) in msgs {
+            execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).unwrap();
+        }
+
+        let stats = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, dear_leader: &str| {
+            from_binary::<LeaderStatsResponse>(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::GetLeaderStats {
+                        dear_leader_addr: dear_leader.to_string(),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+        let leader_a = stats(&deps, "leader_a");
+        assert_eq!(leader_a.delegators, 3);
+        assert_eq!(leader_a.votes_cast, 2);
+        assert_eq!(leader_a.eligible_proposals, 3);
+        assert_eq!(leader_a.participation_rate, Decimal::from_ratio(2u64, 3u64));
+        assert_eq!(leader_a.overrides, 2);
+        assert_eq!(leader_a.delegators_gained, 3);
+        let leader_b = stats(&deps, "leader_b");
+        assert_eq!(leader_b.delegators, 0);
+        assert_eq!(leader_b.eligible_proposals, 2);
+        assert_eq!(leader_b.participation_rate, Decimal::zero());
+        assert_eq!(leader_b.overrides, 1);
+        assert_eq!(leader_b.delegators_lost, 1);
+
+        let churn: GetLeaderChurnResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetLeaderChurn {
+                    dear_leader_addr: "leader_b".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(churn.periods.len(), 1);
+        assert_eq!(
+            churn.periods[0].period_start,
+            mock_env().block.time.seconds() / 604_800 * 604_800
+        );
+        assert_eq!((churn.periods[0].gained, churn.periods[0].lost), (1, 1));
+
+        let leaderboard = |sort_by, start_after: Option<&str>| {
+            let res: GetLeaderboardResponse = from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::GetLeaderboard {
+                        sort_by,
+                        start_after: start_after.map(str::to_string),
+                        limit: Some(1),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            res.leaders
+                .into_iter()
+                .map(|leader| leader.dear_leader_addr)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            leaderboard(LeaderboardSort::ParticipationRate, None),
+            vec!["leader_a".to_string()]
+        );
+        assert_eq!(
+            leaderboard(LeaderboardSort::Delegators, None),
+            vec!["leader_a".to_string()]
+        );
+        assert_eq!(
+            leaderboard(LeaderboardSort::Delegators, Some("leader_a")),
+            vec!["leader_b".to_string()]
+        );
+        assert_eq!(
+            leaderboard(LeaderboardSort::ParticipationRate, Some("leader_a")),
+            vec!["leader_b".to_string()]
+        );
+        assert!(leaderboard(LeaderboardSort::VotingPower, Some("leader_a")).is_empty());
+        // a dear leader that left the board ends the paging instead of failing it
+        for sort_by in [
+            LeaderboardSort::ParticipationRate,
+            LeaderboardSort::Delegators,
+            LeaderboardSort::VotingPower,
+        ] {
+            assert!(leaderboard(sort_by, Some("unknown_leader")).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_proposal_registry() {
+        let mut deps = mock_dependencies_with_gov();
+        let voting_end = mock_env().block.time.plus_seconds(3600);
+        deps.querier
+            .set_proposal(gov_proposal(1, ProposalStatus::DepositPeriod, voting_end));
+        deps.querier
+            .set_proposal(gov_proposal(2, ProposalStatus::VotingPeriod, voting_end));
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADMIN, &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("account", &[]),
+            ExecuteMsg::RegisterUserAccount {},
+        )
+        .unwrap();
+
+        let vote = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, env, proposal_id| {
+            execute(
+                deps.as_mut(),
+                env,
+                mock_info("account", &[]),
+                ExecuteMsg::UserAccountVote {
+                    proposal_id,
+                    vote_option: 1,
+                },
+            )
+        };
+        let sync = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, proposal_id| {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("anyone", &[]),
+                ExecuteMsg::SyncProposal { proposal_id },
+            )
+        };
+        let active = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, env| {
+            from_binary::<ListProposalsResponse>(
+                &query(
+                    deps.as_ref(),
+                    env,
+                    QueryMsg::ListActiveProposals {
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+            .proposals
+            .into_iter()
+            .map(|proposal| proposal.proposal_id)
+            .collect::<Vec<_>>()
+        };
+
+        // votes are only taken on synced proposals
+        let err = vote(&mut deps, mock_env(), 1).unwrap_err();
+        assert!(matches!(err, ContractError::ProposalNotRegistered {}));
+        sync(&mut deps, 1).unwrap();
+        let err = vote(&mut deps, mock_env(), 1).unwrap_err();
+        assert!(matches!(err, ContractError::ProposalNotInVotingPeriod {}));
+        let res = sync(&mut deps, 2).unwrap();
+        assert_eq!(attr(&res, "status"), "voting_period");
+        assert_eq!(active(&deps, mock_env()), vec![2]);
+
+        // a resync picks up the new status
+        deps.querier
+            .set_proposal(gov_proposal(1, ProposalStatus::VotingPeriod, voting_end));
+        sync(&mut deps, 1).unwrap();
+        assert_eq!(active(&deps, mock_env()), vec![1, 2]);
+        vote(&mut deps, mock_env(), 1).unwrap();
+        let proposal: GetProposalResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetProposal { proposal_id: 1 },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(proposal.proposal.unwrap().title, "Proposal 1");
+
+        // past the voting end the proposal is closed even before the next sync
+        let mut env = mock_env();
+        env.block.time = voting_end.plus_seconds(1);
+        let err = vote(&mut deps, env.clone(), 2).unwrap_err();
+        assert!(matches!(err, ContractError::VotingEnded {}));
+        assert!(active(&deps, env).is_empty());
+        deps.querier
+            .set_proposal(gov_proposal(2, ProposalStatus::Passed, voting_end));
+        sync(&mut deps, 2).unwrap();
+        let err = vote(&mut deps, mock_env(), 2).unwrap_err();
+        assert!(matches!(err, ContractError::VotingEnded {}));
+
+        // proposals unknown to x/gov can't be synced
+        sync(&mut deps, 3).unwrap_err();
+    }
+
+    #[test]
+    fn test_vote_fallbacks() {
+        let mut deps = mock_dependencies_with_gov();
+        let voting_end = mock_env().block.time.plus_seconds(86_400);
+        deps.querier
+            .set_proposal(gov_proposal(1, ProposalStatus::VotingPeriod, voting_end));
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADMIN, &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+
+        let transfer = |dear_leader_addr: &str| ExecuteMsg::TransferVotePower {
+            dear_leader_addr: dear_leader_addr.to_string(),
+        };
+        let set_fallback = |fallback| ExecuteMsg::SetVoteFallback { fallback };
+        let mut msgs = vec![(
+            ADMIN,
+            ExecuteMsg::SetAccountFactoryAddr {
+                account_factory_addr: ACCOUNT_FACTORY.to_string(),
+            },
+        )];
+        for dear_leader in ["leader_a", "leader_b"] {
+            msgs.push((
+                ACCOUNT_FACTORY,
+                ExecuteMsg::RegisterDearLeader {
+                    new_dear_leader_addr: dear_leader.to_string(),
+                },
+            ));
+        }
+        for account in [
+            "account_a",
+            "account_b",
+            "account_c",
+            "account_d",
+            "account_e",
+        ] {
+            msgs.push((account, ExecuteMsg::RegisterUserAccount {}));
+        }
+        msgs.extend([
+            ("account_a", transfer("leader_a")),
+            ("account_b", transfer("leader_a")),
+            ("account_e", transfer("leader_b")),
+            ("account_a", set_fallback(VoteFallback::Abstain {})),
+            (
+                "account_b",
+                set_fallback(VoteFallback::FollowLeader {
+                    dear_leader_addr: "leader_b".to_string(),
+                }),
+            ),
+            // account_c voted itself, account_d removed its fallback
+            ("account_c", set_fallback(VoteFallback::Abstain {})),
+            ("account_d", set_fallback(VoteFallback::Abstain {})),
+            ("account_d", set_fallback(VoteFallback::Nothing {})),
+            ("anyone", ExecuteMsg::SyncProposal { proposal_id: 1 }),
+            (
+                "account_c",
+                ExecuteMsg::UserAccountVote {
+                    proposal_id: 1,
+                    vote_option: 1,
+                },
+            ),
+            (
+                "leader_b",
+                ExecuteMsg::DearLeaderVote {
+                    proposal_id: 1,
+                    vote_option: 2,
+                    rationale: None,
+                },
+            ),
+        ]);
+        for (sender, msg) in msgs {
+            execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).unwrap();
+        }
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("account_c", &[]),
+            set_fallback(VoteFallback::FollowLeader {
+                dear_leader_addr: "unknown".to_string(),
+            }),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::DearLeaderNotRegistered {}));
+        let fallback: GetVoteFallbackResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetVoteFallback {
+                    user_account_addr: "account_d".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(fallback.fallback, VoteFallback::Nothing {});
+
+        // fallbacks wait for the last hours of the voting period
+        let apply = ExecuteMsg::ApplyFallbacks {
+            proposal_id: 1,
+            start_after: None,
+            limit: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            apply.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::FallbackWindowNotOpen {}));
+
+        // leader_a never voted, its delegators abstain or follow leader_b
+        let mut env = mock_env();
+        env.block.time = voting_end.minus_seconds(3600);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            apply.clone(),
+        )
+        .unwrap();
+        assert_eq!(attr(&res, "applied"), "2");
+        assert_eq!(attr(&res, "last_account"), "account_c");
+        let votes = |res: &Response| -> Vec<(String, u64)> {
+            res.messages
+                .iter()
+                .map(|msg| match &msg.msg {
+                    cosmwasm_std::CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute {
+                        contract_addr,
+                        msg,
+                        ..
+                    }) => match from_binary(msg).unwrap() {
+                        CommonExecuteMsg::AssemblyVote { vote_option, .. } => {
+                            (contract_addr.clone(), vote_option)
+                        }
+                        _ => panic!("unexpected message"),
+                    },
+                    _ => panic!("unexpected message"),
+                })
+                .collect()
+        };
+        assert_eq!(
+            votes(&res),
+            vec![("account_a".to_string(), 3), ("account_b".to_string(), 2)]
+        );
+
+        // each fallback is only applied once
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), apply).unwrap();
+        assert!(res.messages.is_empty());
+
+        // a pending announcement holds the fallback back until it can be cast, account_c has no
+        // dear leader and abstains right away
+        deps.querier
+            .set_proposal(gov_proposal(2, ProposalStatus::VotingPeriod, voting_end));
+        let apply = ExecuteMsg::ApplyFallbacks {
+            proposal_id: 2,
+            start_after: None,
+            limit: None,
+        };
+        env.block.time = voting_end.minus_seconds(4 * 3600);
+        for (sender, msg) in [
+            (
+                ADMIN,
+                ExecuteMsg::SetReviewWindow {
+                    review_window: 7200,
+                },
+            ),
+            ("anyone", ExecuteMsg::SyncProposal { proposal_id: 2 }),
+            (
+                "leader_a",
+                ExecuteMsg::AnnounceDearLeaderVote {
+                    proposal_id: 2,
+                    vote_option: 1,
+                    rationale: None,
+                },
+            ),
+        ] {
+            execute(deps.as_mut(), env.clone(), mock_info(sender, &[]), msg).unwrap();
+        }
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            apply.clone(),
+        )
+        .unwrap();
+        assert_eq!(votes(&res), vec![("account_c".to_string(), 3)]);
+
+        // nobody cast it once the window was over, account_a abstains. account_b waits for leader_b
+        env.block.time = voting_end.minus_seconds(3600);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), apply).unwrap();
+        assert_eq!(votes(&res), vec![("account_a".to_string(), 3)]);
+
+        // the dear leader vote cast later replaces the fallback
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("anyone", &[]),
+            ExecuteMsg::ExecuteAnnouncedVote {
+                dear_leader_addr: "leader_a".to_string(),
+                proposal_id: 2,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            votes(&res),
+            vec![("account_a".to_string(), 1), ("account_b".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn test_transitive_delegation() {
+        const UPGRADE_PROPOSAL: &str = "/cosmos.upgrade.v1beta1.SoftwareUpgradeProposal";
+        let mut deps = mock_dependencies_with_gov();
+        let voting_end = mock_env().block.time.plus_seconds(86_400);
+        deps.querier
+            .set_proposal(gov_proposal(1, ProposalStatus::VotingPeriod, voting_end));
+        deps.querier.set_proposal(GovProposal {
+            content_type: UPGRADE_PROPOSAL.to_string(),
+            ..gov_proposal(2, ProposalStatus::VotingPeriod, voting_end)
+        });
+        let validators = [Validator {
+            address: "validator".to_string(),
+            commission: Decimal::percent(5),
+            max_commission: Decimal::percent(100),
+            max_change_rate: Decimal::percent(1),
+        }];
+        let leaders = ["leader_a", "leader_b", "leader_c", "leader_d"];
+        let delegations: Vec<FullDelegation> = leaders
+            .iter()
+            .map(|leader| delegation(&leader.replace("leader", "account"), 10))
+            .collect();
+        deps.querier
+            .base
+            .update_staking(USED_DENOM, &validators, &delegations);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADMIN, &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+
+        let follow =
+            |dear_leader_addr: &str, categories: Option<&str>| ExecuteMsg::FollowDearLeader {
+                dear_leader_addr: dear_leader_addr.to_string(),
+                categories: categories.map(|category| vec![category.to_string()]),
+            };
+        let text = Some("/cosmos.gov.v1beta1.TextProposal");
+        let mut msgs = vec![
+            (
+                ADMIN.to_string(),
+                ExecuteMsg::SetAccountFactoryAddr {
+                    account_factory_addr: ACCOUNT_FACTORY.to_string(),
+                },
+            ),
+            (
+                "anyone".to_string(),
+                ExecuteMsg::SyncProposal { proposal_id: 1 },
+            ),
+            (
+                "anyone".to_string(),
+                ExecuteMsg::SyncProposal { proposal_id: 2 },
+            ),
+        ];
+        for leader in leaders {
+            let account = leader.replace("leader", "account");
+            msgs.push((
+                ACCOUNT_FACTORY.to_string(),
+                ExecuteMsg::RegisterDearLeader {
+                    new_dear_leader_addr: leader.to_string(),
+                },
+            ));
+            msgs.push((account.clone(), ExecuteMsg::RegisterUserAccount {}));
+            msgs.push((
+                account,
+                ExecuteMsg::TransferVotePower {
+                    dear_leader_addr: leader.to_string(),
+                },
+            ));
+        }
+        // leader_b defers text proposals to leader_c and leader_d upgrades, leader_a follows
+        // leader_b on everything and leader_c follows leader_a back
+        msgs.extend([
+            ("leader_b".to_string(), follow("leader_c", text)),
+            (
+                "leader_d".to_string(),
+                follow("leader_c", Some(UPGRADE_PROPOSAL)),
+            ),
+            ("leader_a".to_string(), follow("leader_b", None)),
+            ("leader_c".to_string(), follow("leader_a", None)),
+        ]);
+        for (sender, msg) in msgs {
+            execute(deps.as_mut(), mock_env(), mock_info(&sender, &[]), msg).unwrap();
+        }
+
+        for (sender, msg) in [
+            ("leader_a", follow("leader_a", None)),
+            ("leader_a", follow("leader_d", None)),
+            ("leader_b", follow("leader_d", text)),
+        ] {
+            let err = execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidLeaderDelegation {}));
+        }
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("leader_a", &[]),
+            ExecuteMsg::UnfollowDearLeader {
+                dear_leader_addr: "leader_d".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::LeaderDelegationNotFound {}));
+
+        let delegated_power = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, category: Option<&str>| {
+            from_binary::<GetDelegatedPowerResponse>(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::GetDelegatedPower {
+                        dear_leader_addr: "leader_c".to_string(),
+                        category: category.map(str::to_string),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+        let power = delegated_power(&deps, text);
+        assert_eq!(power.followers, vec!["leader_b", "leader_a"]);
+        assert_eq!(power.power, Uint128::new(10));
+        assert_eq!(power.delegated_power, Uint128::new(20));
+        assert!(delegated_power(&deps, None).followers.is_empty());
+
+        let vote = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _>, leader: &str, proposal_id| {
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(leader, &[]),
+                ExecuteMsg::DearLeaderVote {
+                    proposal_id,
+                    vote_option: 1,
+                    rationale: None,
+                },
+            )
+            .unwrap();
+            res.messages
+                .iter()
+                .map(|msg| match &msg.msg {
+                    cosmwasm_std::CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute {
+                        contract_addr,
+                        ..
+                    }) => contract_addr.clone(),
+                    _ => panic!("unexpected message"),
+                })
+                .collect::<Vec<_>>()
+        };
+        // the cycle back to leader_a ends the chain
+        assert_eq!(
+            vote(&mut deps, "leader_a", 1),
+            vec!["account_a", "account_c", "account_b"]
+        );
+        // leader_a keeps its own vote
+        assert_eq!(
+            vote(&mut deps, "leader_c", 1),
+            vec!["account_c", "account_b"]
+        );
+        assert_eq!(
+            vote(&mut deps, "leader_c", 2),
+            vec!["account_c", "account_d"]
+        );
+    }
+
+    #[test]
+    fn test_dear_leader_vote_delivery() {
+        let mut deps = mock_dependencies_with_gov();
+        let voting_end = mock_env().block.time.plus_seconds(86_400);
+        deps.querier
+            .set_proposal(gov_proposal(1, ProposalStatus::VotingPeriod, voting_end));
+        let validators = [Validator {
+            address: "validator".to_string(),
+            commission: Decimal::percent(5),
+            max_commission: Decimal::percent(100),
+            max_change_rate: Decimal::percent(1),
+        }];
+        // leader_1 has more delegators than a page, the others one each
+        let leaders = ["leader_1", "leader_2", "leader_3", "leader_4", "leader_5"];
+        let mut accounts: Vec<(String, &str)> = (0..12)
+            .map(|i| (format!("account_1_{:02}", i), "leader_1"))
+            .collect();
+        accounts.extend(
+            leaders[1..]
+                .iter()
+                .map(|leader| (leader.replace("leader", "account"), *leader)),
+        );
+        let delegations: Vec<FullDelegation> = accounts
+            .iter()
+            .map(|(account, _)| delegation(account, 10))
+            .collect();
+        deps.querier
+            .base
+            .update_staking(USED_DENOM, &validators, &delegations);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADMIN, &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+
+        let mut msgs = vec![
+            (
+                ADMIN.to_string(),
+                ExecuteMsg::SetAccountFactoryAddr {
+                    account_factory_addr: ACCOUNT_FACTORY.to_string(),
+                },
+            ),
+            (
+                "anyone".to_string(),
+                ExecuteMsg::SyncProposal { proposal_id: 1 },
+            ),
+        ];
+        msgs.extend(leaders.iter().map(|leader| {
+            (
+                ACCOUNT_FACTORY.to_string(),
+                ExecuteMsg::RegisterDearLeader {
+                    new_dear_leader_addr: leader.to_string(),
+                },
+            )
+        }));
+        for (account, leader) in accounts.iter() {
+            msgs.push((account.clone(), ExecuteMsg::RegisterUserAccount {}));
+            msgs.push((
+                account.clone(),
+                ExecuteMsg::TransferVotePower {
+                    dear_leader_addr: leader.to_string(),
+                },
+            ));
+        }
+        // every dear leader follows the previous one, one hop more than the vote reaches
+        for pair in leaders.windows(2) {
+            msgs.push((
+                pair[1].to_string(),
+                ExecuteMsg::FollowDearLeader {
+                    dear_leader_addr: pair[0].to_string(),
+                    categories: None,
+                },
+            ));
+        }
+        for (sender, msg) in msgs {
+            execute(deps.as_mut(), mock_env(), mock_info(&sender, &[]), msg).unwrap();
+        }
+
+        // the leaderboard and the dear leader list count the power of the followers
+        let list: ListDearLeadersResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ListDearLeaders {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            list.dear_leaders
+                .iter()
+                .map(|leader| (leader.power.u128(), leader.delegated_power.u128()))
+                .collect::<Vec<_>>(),
+            vec![(120, 30), (10, 30), (10, 20), (10, 10), (10, 0)]
+        );
+        let leaderboard: GetLeaderboardResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetLeaderboard {
+                    sort_by: LeaderboardSort::VotingPower,
+                    start_after: None,
+                    limit: Some(2),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            leaderboard
+                .leaders
+                .iter()
+                .map(|leader| leader.dear_leader_addr.as_str())
+                .collect::<Vec<_>>(),
+            vec!["leader_1", "leader_2"]
+        );
+        assert_eq!(leaderboard.leaders[1].delegated_power, Uint128::new(30));
+
+        let voted_accounts = |res: &Response| {
+            res.messages
+                .iter()
+                .map(|msg| match &msg.msg {
+                    cosmwasm_std::CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute {
+                        contract_addr,
+                        ..
+                    }) => contract_addr.clone(),
+                    _ => panic!("unexpected message"),
+                })
+                .collect::<Vec<_>>()
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("leader_1", &[]),
+            ExecuteMsg::DearLeaderVote {
+                proposal_id: 1,
+                vote_option: 1,
+                rationale: None,
+            },
+        )
+        .unwrap();
+        // the vote reaches MAX_DELEGATION_DEPTH followers, leader_5 is one hop too far
+        assert_eq!(
+            res.events
+                .iter()
+                .filter(|event| event.ty == "dear_leader_vote_delegated")
+                .map(|event| event_attr(event, "dear_leader"))
+                .collect::<Vec<_>>(),
+            vec!["leader_2", "leader_3", "leader_4"]
+        );
+        assert_eq!(
+            voted_accounts(&res),
+            (0..10)
+                .map(|i| format!("account_1_{:02}", i))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(attr(&res, "last_dear_leader"), "leader_1");
+        assert_eq!(attr(&res, "last_user_account"), "account_1_09");
+
+        // anyone delivers the rest, starting after the last delegator of the page
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::DeliverDearLeaderVote {
+                dear_leader_addr: "leader_1".to_string(),
+                proposal_id: 1,
+                start_after: Some((
+                    attr(&res, "last_dear_leader"),
+                    attr(&res, "last_user_account"),
+                )),
+                limit: Some(3),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            voted_accounts(&res),
+            vec!["account_1_10", "account_1_11", "account_2"]
+        );
+        assert_eq!(attr(&res, "last_dear_leader"), "leader_2");
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::DeliverDearLeaderVote {
+                dear_leader_addr: "leader_1".to_string(),
+                proposal_id: 1,
+                start_after: Some((
+                    attr(&res, "last_dear_leader"),
+                    attr(&res, "last_user_account"),
+                )),
+                limit: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(voted_accounts(&res), vec!["account_3", "account_4"]);
+        assert_eq!(attr(&res, "last_dear_leader"), "");
+        assert_eq!(attr(&res, "last_user_account"), "");
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::DeliverDearLeaderVote {
+                dear_leader_addr: "leader_5".to_string(),
+                proposal_id: 1,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::LeaderVoteNotFound {}));
+
+        // retiring leader_2 ends both its follow of leader_1 and leader_3 following it
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("leader_2", &[]),
+            ExecuteMsg::RetireDearLeader {},
+        )
+        .unwrap();
+        let removed = res
+            .events
+            .iter()
+            .find(|event| event.ty == "leader_delegation_removed")
+            .unwrap();
+        assert_eq!(event_attr(removed, "dear_leader"), "leader_3");
+        assert_eq!(event_attr(removed, "followed"), "leader_2");
+        let delegated_power = |deps: &OwnedDeps<_, _, _>, dear_leader: &str| {
+            from_binary::<GetDelegatedPowerResponse>(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::GetDelegatedPower {
+                        dear_leader_addr: dear_leader.to_string(),
+                        category: None,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+        assert!(delegated_power(&deps, "leader_1").followers.is_empty());
+        assert!(delegated_power(&deps, "leader_2").followers.is_empty());
+        assert_eq!(
+            delegated_power(&deps, "leader_3").followers,
+            vec!["leader_4", "leader_5"]
+        );
+        let stats: LeaderStatsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetLeaderStats {
+                    dear_leader_addr: "leader_1".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(stats.delegated_power, Uint128::zero());
+    }
+
+    #[test]
+    fn test_migrate_to_composite_keys() {
+        let mut deps = mock_dependencies_with_gov();
+        let validators = [Validator {
+            address: "validator".to_string(),
+            commission: Decimal::percent(5),
+            max_commission: Decimal::percent(100),
+            max_change_rate: Decimal::percent(1),
+        }];
+        deps.querier.base.update_staking(
+            USED_DENOM,
+            &validators,
+            &[
+                delegation("account_1", 100),
+                delegation("account_2", 50),
+                delegation("account_3", 30),
+            ],
+        );
+        deps.querier.set_proposal(gov_proposal(
+            1,
+            ProposalStatus::VotingPeriod,
+            mock_env().block.time.plus_seconds(86_400),
+        ));
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADMIN, &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::SyncProposal { proposal_id: 1 },
+        )
+        .unwrap();
+
+        // state as written before the migration
+        let storage = deps.as_mut().storage;
+        LEGACY_DEAR_LEADER_BOARD
+            .save(
+                storage,
+                "leader".to_string(),
+                &Some(vec!["account_1".to_string(), "account_2".to_string()]),
+            )
+            .unwrap();
+        LEGACY_DEAR_LEADER_BOARD
+            .save(storage, "new_leader".to_string(), &None)
+            .unwrap();
+        // account_3 is registered without a dear leader
+        let legacy_boss_vote_power: Map<String, Option<String>> = Map::new("boss_vote_power");
+        for (account, dear_leader) in [
+            ("account_1", Some("leader")),
+            ("account_2", Some("leader")),
+            ("account_3", None),
+        ] {
+            legacy_boss_vote_power
+                .save(
+                    storage,
+                    account.to_string(),
+                    &dear_leader.map(str::to_string),
+                )
+                .unwrap();
+        }
+        LEGACY_PROPOSAL_VOTE_HISTORY
+            .save(storage, 1, &vec!["account_1".to_string()])
+            .unwrap();
+
+        // the upgrade moves one delegator, the rest of the list stays for the next page
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg { limit: Some(1) }).unwrap();
+        assert_eq!(attr(&res, "migrated_entries"), "1");
+        assert_eq!(attr(&res, "migration_done"), "false");
+        assert_eq!(
+            LEGACY_DEAR_LEADER_BOARD
+                .load(deps.as_ref().storage, "leader".to_string())
+                .unwrap(),
+            Some(vec!["account_1".to_string()])
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::MigrateCompositeKeys { limit: None },
+        )
+        .unwrap();
+        assert_eq!(attr(&res, "migrated_entries"), "2");
+        assert_eq!(attr(&res, "migration_done"), "true");
+        assert!(LEGACY_DEAR_LEADER_BOARD.is_empty(deps.as_ref().storage));
+
+        let delegators = |deps: &OwnedDeps<_, _, _>, dear_leader: &str| {
+            from_binary::<GetDearLeaderDelegatoresResponse>(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::GetDearLeaderDelegatores {
+                        dear_leader_addr: dear_leader.to_string(),
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+            .delegatores_list
+        };
+        assert_eq!(
+            delegators(&deps, "leader"),
+            Some(vec!["account_1".to_string(), "account_2".to_string()])
+        );
+        assert_eq!(delegators(&deps, "new_leader"), None);
+        let page: GetDearLeaderDelegatoresResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetDearLeaderDelegatores {
+                    dear_leader_addr: "leader".to_string(),
+                    start_after: Some("account_1".to_string()),
+                    limit: Some(1),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(page.delegatores_list, Some(vec!["account_2".to_string()]));
+
+        let leaders: ListDearLeadersResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ListDearLeaders {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(leaders.dear_leaders.len(), 2);
+        assert_eq!(leaders.dear_leaders[0].dear_leader_addr, "leader");
+        assert_eq!(leaders.dear_leaders[0].delegators, 2);
+        // the stake of the moved delegators is read from the chain
+        assert_eq!(leaders.dear_leaders[0].power, Uint128::new(150));
+        let leaderboard: GetLeaderboardResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetLeaderboard {
+                    sort_by: LeaderboardSort::VotingPower,
+                    start_after: None,
+                    limit: Some(1),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(leaderboard.leaders[0].dear_leader_addr, "leader");
+
+        let voted: GetVoteProposalByUserAndPropResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetVoteProposalByUserAndProp {
+                    user_account_addr: "account_1".to_string(),
+                    proposal_id: 1,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(voted.voted);
+
+        // accounts without a dear leader get their stake from SyncAccounts
+        let account_3_stake = |deps: &OwnedDeps<_, _, _>| {
+            from_binary::<VotingPowerAtHeightResponse>(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::VotingPowerAtHeight {
+                        address: "account_3".to_string(),
+                        height: Some(mock_env().block.height + 1),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+            .power
+        };
+        assert_eq!(account_3_stake(&deps), Uint128::zero());
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::SyncAccounts {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(account_3_stake(&deps), Uint128::new(30));
+
+        // nothing left to move the second time
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg { limit: None }).unwrap();
+        assert_eq!(attr(&res, "migrated_entries"), "0");
+        assert_eq!(attr(&res, "migration_done"), "true");
+    }
+
+    /// MockStorage that charges gas like the sdk KV store does, to compare the cost of
+    /// the same call as the state grows.
+    #[derive(Default)]
+    struct GasMeteredStorage {
+        storage: MockStorage,
+        gas_used: Cell<u64>,
+    }
+
+    impl GasMeteredStorage {
+        fn charge(&self, gas: u64) {
+            self.gas_used.set(self.gas_used.get() + gas);
+        }
+    }
+
+    impl Storage for GasMeteredStorage {
+        fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+            let value = self.storage.get(key);
+            self.charge(1000 + 3 * (key.len() + value.as_ref().map_or(0, Vec::len)) as u64);
+            value
+        }
+
+        fn range<'a>(
+            &'a self,
+            start: Option<&[u8]>,
+            end: Option<&[u8]>,
+            order: Order,
+        ) -> Box<dyn Iterator<Item = Record> + 'a> {
+            self.charge(1000);
+            Box::new(
+                self.storage
+                    .range(start, end, order)
+                    .map(move |(key, value)| {
+                        self.charge(30 + 3 * (key.len() + value.len()) as u64);
+                        (key, value)
+                    }),
+            )
+        }
+
+        fn set(&mut self, key: &[u8], value: &[u8]) {
+            self.charge(2000 + 30 * (key.len() + value.len()) as u64);
+            self.storage.set(key, value)
+        }
+
+        fn remove(&mut self, key: &[u8]) {
+            self.charge(1000);
+            self.storage.remove(key)
+        }
+    }
+
+    // gas of a transfer, a direct vote and a reclaim by one more delegator of a dear leader
+    // that already has `delegators`, then of the dear leader vote and a delegator query.
+    fn delegator_gas(delegators: usize) -> [u64; 5] {
+        let mut deps = OwnedDeps {
+            storage: GasMeteredStorage::default(),
+            api: MockApi::default(),
+            querier: MockGovQuerier::new(),
+            custom_query_type: PhantomData,
+        };
+        deps.querier.set_proposal(gov_proposal(
+            1,
+            ProposalStatus::VotingPeriod,
+            mock_env().block.time.plus_seconds(86_400),
+        ));
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADMIN, &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+        let mut msgs = vec![
+            (
+                ADMIN.to_string(),
+                ExecuteMsg::SetAccountFactoryAddr {
+                    account_factory_addr: ACCOUNT_FACTORY.to_string(),
+                },
+            ),
+            (
+                ACCOUNT_FACTORY.to_string(),
+                ExecuteMsg::RegisterDearLeader {
+                    new_dear_leader_addr: "leader".to_string(),
+                },
+            ),
+            (
+                "anyone".to_string(),
+                ExecuteMsg::SyncProposal { proposal_id: 1 },
+            ),
+            ("account".to_string(), ExecuteMsg::RegisterUserAccount {}),
+        ];
+        for i in 0..delegators {
+            let account = format!("account_{:04}", i);
+            msgs.push((account.clone(), ExecuteMsg::RegisterUserAccount {}));
+            msgs.push((
+                account,
+                ExecuteMsg::TransferVotePower {
+                    dear_leader_addr: "leader".to_string(),
+                },
+            ));
+        }
+        for (sender, msg) in msgs {
+            execute(deps.as_mut(), mock_env(), mock_info(&sender, &[]), msg).unwrap();
+        }
+
+        let mut gas = [
+            (
+                "account",
+                ExecuteMsg::TransferVotePower {
+                    dear_leader_addr: "leader".to_string(),
+                },
+            ),
+            (
+                "account",
+                ExecuteMsg::UserAccountVote {
+                    proposal_id: 1,
+                    vote_option: 1,
+                },
+            ),
+            ("account", ExecuteMsg::ReclaimVotePower {}),
+            (
+                "leader",
+                ExecuteMsg::DearLeaderVote {
+                    proposal_id: 1,
+                    vote_option: 1,
+                    rationale: None,
+                },
+            ),
+        ]
+        .map(|(sender, msg)| {
+            deps.storage.gas_used.set(0);
+            execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).unwrap();
+            deps.storage.gas_used.get()
+        })
+        .to_vec();
+
+        deps.storage.gas_used.set(0);
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetDearLeaderDelegatores {
+                dear_leader_addr: "leader".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        gas.push(deps.storage.gas_used.get());
+
+        gas.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_delegator_gas_does_not_grow_with_delegators() {
+        let small = delegator_gas(10);
+        let large = delegator_gas(300);
+        for (small, large) in small.into_iter().zip(large) {
+            // only the longer account keys in the larger case may cost a bit more
+            assert!(large * 10 <= small * 11, "{} vs {}", small, large);
+        }
+    }
+
+    #[test]
+    fn test_power_snapshots() {
+        let mut deps = mock_dependencies_with_gov();
+        let validators = [Validator {
+            address: "validator".to_string(),
+            commission: Decimal::percent(5),
+            max_commission: Decimal::percent(100),
+            max_change_rate: Decimal::percent(1),
+        }];
+        deps.querier.base.update_staking(
+            USED_DENOM,
+            &validators,
+            &[
+                delegation("account_1", 100),
+                delegation("account_2", 50),
+                delegation("account_3", 30),
+            ],
+        );
+        let voting_end = mock_env().block.time.plus_seconds(86_400);
+        deps.querier
+            .set_proposal(gov_proposal(1, ProposalStatus::VotingPeriod, voting_end));
+        let env_at = |height: u64| {
+            let mut env = mock_env();
+            env.block.height = height;
+            env
+        };
+        let start = mock_env().block.height;
+
+        instantiate(
+            deps.as_mut(),
+            env_at(start),
+            mock_info(ADMIN, &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+        let msgs = vec![
+            (
+                start,
+                ADMIN,
+                ExecuteMsg::SetAccountFactoryAddr {
+                    account_factory_addr: ACCOUNT_FACTORY.to_string(),
+                },
+            ),
+            (
+                start,
+                ACCOUNT_FACTORY,
+                ExecuteMsg::RegisterDearLeader {
+                    new_dear_leader_addr: "leader".to_string(),
+                },
+            ),
+            (start, "account_1", ExecuteMsg::RegisterUserAccount {}),
+            (
+                start,
+                "account_1",
+                ExecuteMsg::TransferVotePower {
+                    dear_leader_addr: "leader".to_string(),
+                },
+            ),
+            // the delegators of follower get the vote of leader
+            (
+                start,
+                ACCOUNT_FACTORY,
+                ExecuteMsg::RegisterDearLeader {
+                    new_dear_leader_addr: "follower".to_string(),
+                },
+            ),
+            (start, "account_3", ExecuteMsg::RegisterUserAccount {}),
+            (
+                start,
+                "account_3",
+                ExecuteMsg::TransferVotePower {
+                    dear_leader_addr: "follower".to_string(),
+                },
+            ),
+            (
+                start,
+                "follower",
+                ExecuteMsg::FollowDearLeader {
+                    dear_leader_addr: "leader".to_string(),
+                    categories: None,
+                },
+            ),
+            (
+                start + 1,
+                "anyone",
+                ExecuteMsg::SyncProposal { proposal_id: 1 },
+            ),
+            // delegations shifting after the proposal started
+            (start + 2, "account_2", ExecuteMsg::RegisterUserAccount {}),
+            (
+                start + 2,
+                "account_2",
+                ExecuteMsg::TransferVotePower {
+                    dear_leader_addr: "leader".to_string(),
+                },
+            ),
+            (start + 2, "account_1", ExecuteMsg::ReclaimVotePower {}),
+            (
+                start + 2,
+                "account_2",
+                ExecuteMsg::SetVoteFallback {
+                    fallback: VoteFallback::Abstain {},
+                },
+            ),
+            (
+                start + 3,
+                "anyone",
+                ExecuteMsg::SyncProposal { proposal_id: 1 },
+            ),
+        ];
+        for (height, sender, msg) in msgs {
+            execute(deps.as_mut(), env_at(height), mock_info(sender, &[]), msg).unwrap();
+        }
+
+        let proposal: GetProposalResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetProposal { proposal_id: 1 },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let start_height = proposal.proposal.unwrap().start_height;
+        assert_eq!(start_height, start + 1);
+
+        let leader_power = |deps: &OwnedDeps<_, _, _>, height: u64| {
+            from_binary::<LeaderPowerAtHeightResponse>(
+                &query(
+                    deps.as_ref(),
+                    env_at(start + 3),
+                    QueryMsg::LeaderPowerAtHeight {
+                        dear_leader_addr: "leader".to_string(),
+                        height: Some(height),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+        assert_eq!(leader_power(&deps, start_height).power, Uint128::new(100));
+        assert_eq!(leader_power(&deps, start + 3).power, Uint128::new(50));
+        // the power of the follower counts from the block after the follow
+        assert_eq!(leader_power(&deps, start).delegated_power, Uint128::zero());
+        assert_eq!(
+            leader_power(&deps, start_height).delegated_power,
+            Uint128::new(30)
+        );
+
+        let voting_power = |deps: &OwnedDeps<_, _, _>, height: Option<u64>| {
+            from_binary::<VotingPowerAtHeightResponse>(
+                &query(
+                    deps.as_ref(),
+                    env_at(start + 3),
+                    QueryMsg::VotingPowerAtHeight {
+                        address: "account_1".to_string(),
+                        height,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+        assert_eq!(
+            voting_power(&deps, Some(start_height)),
+            VotingPowerAtHeightResponse {
+                power: Uint128::new(100),
+                dear_leader_addr: Some("leader".to_string()),
+                height: start_height,
+            }
+        );
+        // the current block is used when no height is given
+        assert_eq!(
+            voting_power(&deps, None),
+            VotingPowerAtHeightResponse {
+                power: Uint128::new(100),
+                dear_leader_addr: None,
+                height: start + 3,
+            }
+        );
+
+        // the vote goes to the delegations held at the start, account_2 joined later
+        let voted_accounts = |res: &Response| {
+            res.messages
+                .iter()
+                .map(|msg| match &msg.msg {
+                    cosmwasm_std::CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute {
+                        contract_addr,
+                        ..
+                    }) => contract_addr.clone(),
+                    _ => panic!("unexpected message"),
+                })
+                .collect::<Vec<_>>()
+        };
+        let res = execute(
+            deps.as_mut(),
+            env_at(start + 4),
+            mock_info("leader", &[]),
+            ExecuteMsg::DearLeaderVote {
+                proposal_id: 1,
+                vote_option: 1,
+                rationale: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(voted_accounts(&res), vec!["account_3"]);
+
+        // so its fallback stands in for the dear leader vote
+        let mut env = env_at(start + 5);
+        env.block.time = voting_end.minus_seconds(60);
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("anyone", &[]),
+            ExecuteMsg::ApplyFallbacks {
+                proposal_id: 1,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(voted_accounts(&res), vec!["account_2"]);
+        assert_eq!(event_attr(&res.events[0], "vote_option"), "3");
+    }
 }
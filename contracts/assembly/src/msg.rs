@@ -1,26 +1,128 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Decimal, Uint128};
+use util_types::{DearLeaderProfile, VoteFallback, VoteRationale};
+
+use crate::state::{ChurnPeriod, LeaderDelegation, RegisteredProposal, VoteAnnouncement};
+
+pub use util_types::{
+    GetAccountStakeResponse, GetDearLeaderDelegatoresResponse, GetDearLeaderPowerResponse,
+    GetDearLeaderVotesResponse, GetLeaderVoteResponse,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {}
 
+#[cw_serde]
+pub struct MigrateMsg {
+    /// delegators and votes moved to composite keys with the upgrade, MigrateCompositeKeys
+    /// moves the rest
+    pub limit: Option<u32>,
+}
+
 #[cw_serde]
 pub enum ExecuteMsg {
-    UserAccountVote { proposal_id: u64, vote_option: u64 },
-    DearLeaderVote { proposal_id: u64, vote_option: u64 },
-    TransferVotePower { dear_leader_addr: String },
+    UserAccountVote {
+        proposal_id: u64,
+        vote_option: u64,
+    },
+    DearLeaderVote {
+        proposal_id: u64,
+        vote_option: u64,
+        rationale: Option<VoteRationale>,
+    },
+    TransferVotePower {
+        dear_leader_addr: String,
+    },
     ReclaimVotePower {},
-    RegisterDearLeader { new_dear_leader_addr: String },
+    RegisterDearLeader {
+        new_dear_leader_addr: String,
+    },
     RegisterUserAccount {},
     UnregisterUserAccount {},
-    SetAccountFactoryAddr { account_factory_addr: String },
+    SetAccountFactoryAddr {
+        account_factory_addr: String,
+    },
+    SetMaxValidatorCommission {
+        max_commission: Option<Decimal>,
+    },
+    UpdateValidatorAllowList {
+        to_add: Vec<String>,
+        to_remove: Vec<String>,
+    },
+    SyncAccountStake {},
+    SyncAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    SetDearLeaderProfile {
+        profile: DearLeaderProfile,
+    },
+    AnnounceDearLeaderVote {
+        proposal_id: u64,
+        vote_option: u64,
+        rationale: Option<VoteRationale>,
+    },
+    ExecuteAnnouncedVote {
+        dear_leader_addr: String,
+        proposal_id: u64,
+    },
+    // permissionless, sends the dear leader vote to the next page of delegators, starting after
+    // the (dear leader, user account) the previous page ended with
+    DeliverDearLeaderVote {
+        dear_leader_addr: String,
+        proposal_id: u64,
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    },
+    OptOutOfLeaderVote {
+        proposal_id: u64,
+    },
+    SetReviewWindow {
+        review_window: u64,
+    },
+    RetireDearLeader {},
+    ReleaseRetiredDelegators {
+        dear_leader_addr: String,
+        limit: Option<u32>,
+    },
+    // permissionless, registers the proposal or refreshes its status from x/gov
+    SyncProposal {
+        proposal_id: u64,
+    },
+    // permissionless, moves the next page of delegators and votes left in the layout before
+    // composite keys
+    MigrateCompositeKeys {
+        limit: Option<u32>,
+    },
+    SetVoteFallback {
+        fallback: VoteFallback,
+    },
+    // permissionless, votes the fallback of a page of user accounts near the proposal voting end
+    ApplyFallbacks {
+        proposal_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // categories are gov proposal content types, None follows the dear leader on every proposal
+    FollowDearLeader {
+        dear_leader_addr: String,
+        categories: Option<Vec<String>>,
+    },
+    UnfollowDearLeader {
+        dear_leader_addr: String,
+    },
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
-    // returns the list of user accounts delegated to the dear leader
+    // returns a page of the user accounts delegated to the dear leader, sorted by address
     #[returns(GetDearLeaderDelegatoresResponse)]
-    GetDearLeaderDelegatores { dear_leader_addr: String },
+    GetDearLeaderDelegatores {
+        dear_leader_addr: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 
     // returns the dear leader addr to whom the user account is delegated
     #[returns(GetUserAccountLeaderResponse)]
@@ -36,11 +138,153 @@ pub enum QueryMsg {
     // return user_accounts_code_id, dear_leader_accounts_code_id and assembly_addr
     #[returns(GetConfigResponse)]
     GetConfig {},
+
+    // returns the max validator commission and if the validator is in the allow list
+    #[returns(util_types::GetValidatorPolicyResponse)]
+    GetValidatorPolicy { validator_addr: String },
+
+    // returns the stake of the user account as of its last sync
+    #[returns(GetAccountStakeResponse)]
+    GetAccountStake { user_account_addr: String },
+
+    // returns the stake of the user accounts delegated to the dear leader
+    #[returns(GetDearLeaderPowerResponse)]
+    GetDearLeaderPower { dear_leader_addr: String },
+
+    // returns the stake of the user account and the dear leader holding its vote power at
+    // the start of the block, the current one if no height is given
+    #[returns(VotingPowerAtHeightResponse)]
+    VotingPowerAtHeight {
+        address: String,
+        height: Option<u64>,
+    },
+
+    // returns the power of the dear leader and of the dear leaders following it on every proposal
+    // at the start of the block, the current one if no height is given
+    #[returns(LeaderPowerAtHeightResponse)]
+    LeaderPowerAtHeight {
+        dear_leader_addr: String,
+        height: Option<u64>,
+    },
+
+    // returns the vote and rationale of the dear leader on the proposal
+    #[returns(GetLeaderVoteResponse)]
+    GetLeaderVote { leader: String, proposal_id: u64 },
+
+    // returns the proposals the dear leader voted on and the option used
+    #[returns(GetDearLeaderVotesResponse)]
+    GetDearLeaderVotes {
+        dear_leader_addr: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    // returns the votes the dear leader announced that were not cast yet
+    #[returns(GetAnnouncedVotesResponse)]
+    GetAnnouncedVotes {
+        dear_leader_addr: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    // returns the votes announced by the dear leader of the user account and if it opted out
+    #[returns(GetDelegatorAnnouncedVotesResponse)]
+    GetDelegatorAnnouncedVotes {
+        user_account_addr: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    // returns the registered dear leaders with their profile, delegator count and power
+    #[returns(ListDearLeadersResponse)]
+    ListDearLeaders {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    // returns the participation, overrides and churn of the dear leader
+    #[returns(LeaderStatsResponse)]
+    GetLeaderStats { dear_leader_addr: String },
+
+    // returns the delegators the dear leader gained and lost in each period
+    #[returns(GetLeaderChurnResponse)]
+    GetLeaderChurn {
+        dear_leader_addr: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    // returns the registered dear leaders stats, best first. The page continues after
+    // start_after, and is empty if that dear leader retired. Sorting by participation rate
+    // computes the rate of every registered dear leader, it is not paged internally
+    #[returns(GetLeaderboardResponse)]
+    GetLeaderboard {
+        sort_by: LeaderboardSort,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    // returns the registered proposal, as of its last sync
+    #[returns(GetProposalResponse)]
+    GetProposal { proposal_id: u64 },
+
+    // returns the registered proposals that can still be voted on
+    #[returns(ListProposalsResponse)]
+    ListActiveProposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    // returns the fallback of the user account, Nothing if it didn't set one
+    #[returns(GetVoteFallbackResponse)]
+    GetVoteFallback { user_account_addr: String },
+
+    // returns the dear leaders the dear leader follows and for which categories
+    #[returns(GetLeaderDelegationsResponse)]
+    GetLeaderDelegations { dear_leader_addr: String },
+
+    // returns the power of the dear leader plus the power of the dear leaders following it on the
+    // category, directly or through other dear leaders. Without a category only the dear leaders
+    // following on every proposal count
+    #[returns(GetDelegatedPowerResponse)]
+    GetDelegatedPower {
+        dear_leader_addr: String,
+        category: Option<String>,
+    },
+}
+
+#[cw_serde]
+pub enum LeaderboardSort {
+    ParticipationRate,
+    Delegators,
+    VotingPower,
+}
+
+#[cw_serde]
+pub struct GetProposalResponse {
+    pub proposal: Option<RegisteredProposal>,
+}
+
+#[cw_serde]
+pub struct ListProposalsResponse {
+    pub proposals: Vec<RegisteredProposal>,
+}
+
+#[cw_serde]
+pub struct GetVoteFallbackResponse {
+    pub fallback: VoteFallback,
 }
 
 #[cw_serde]
-pub struct GetDearLeaderDelegatoresResponse {
-    pub delegatores_list: Option<Vec<String>>,
+pub struct GetLeaderDelegationsResponse {
+    pub delegations: Vec<LeaderDelegation>,
+}
+
+#[cw_serde]
+pub struct GetDelegatedPowerResponse {
+    pub power: Uint128,
+    pub delegated_power: Uint128,
+    pub followers: Vec<String>,
 }
 
 #[cw_serde]
@@ -53,8 +297,83 @@ pub struct GetVoteProposalByUserAndPropResponse {
     pub voted: bool,
 }
 
+#[cw_serde]
+pub struct GetAnnouncedVotesResponse {
+    pub announcements: Vec<VoteAnnouncement>,
+}
+
+#[cw_serde]
+pub struct GetDelegatorAnnouncedVotesResponse {
+    pub announcements: Vec<DelegatorAnnouncedVote>,
+}
+
+#[cw_serde]
+pub struct DelegatorAnnouncedVote {
+    pub announcement: VoteAnnouncement,
+    pub opted_out: bool,
+}
+
+#[cw_serde]
+pub struct ListDearLeadersResponse {
+    pub dear_leaders: Vec<DearLeaderInfo>,
+}
+
+#[cw_serde]
+pub struct DearLeaderInfo {
+    pub dear_leader_addr: String,
+    pub profile: Option<DearLeaderProfile>,
+    pub delegators: u64,
+    pub power: Uint128,
+    /// power of the dear leaders following it on every proposal
+    pub delegated_power: Uint128,
+}
+
+#[cw_serde]
+pub struct LeaderStatsResponse {
+    pub dear_leader_addr: String,
+    pub delegators: u64,
+    pub power: Uint128,
+    /// power of the dear leaders following it on every proposal, the leaderboard sorts by
+    /// voting power with it
+    pub delegated_power: Uint128,
+    pub votes_cast: u64,
+    pub eligible_proposals: u64,
+    /// votes cast over eligible proposals, capped at one
+    pub participation_rate: Decimal,
+    pub overrides: u64,
+    pub delegators_gained: u64,
+    pub delegators_lost: u64,
+}
+
+#[cw_serde]
+pub struct GetLeaderChurnResponse {
+    pub periods: Vec<ChurnPeriod>,
+}
+
+#[cw_serde]
+pub struct GetLeaderboardResponse {
+    pub leaders: Vec<LeaderStatsResponse>,
+}
+
+#[cw_serde]
+pub struct VotingPowerAtHeightResponse {
+    pub power: Uint128,
+    /// dear leader the vote power was transferred to, if any
+    pub dear_leader_addr: Option<String>,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct LeaderPowerAtHeightResponse {
+    pub power: Uint128,
+    /// power of the dear leaders following it on every proposal, up to MAX_DELEGATION_DEPTH hops
+    pub delegated_power: Uint128,
+    pub height: u64,
+}
+
 #[cw_serde]
 pub struct GetConfigResponse {
     pub admin_addr: String,
     pub accounts_factory_addr: String,
+    pub review_window: u64,
 }
@@ -1,16 +1,174 @@
-use cw_storage_plus::{Item, Map};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Decimal, Timestamp, Uint128};
+use cw_storage_plus::{Item, Map, SnapshotMap, Strategy};
+use util_types::{DearLeaderProfile, DearLeaderVote, ProposalStatus, VoteFallback};
 
 // contract admin.
 pub const ADMIN_ADDR: Item<String> = Item::new("admin_addr");
 
-// relation between dear_leader_account and the list of vote delegatores.
-pub const DEAR_LEADER_BOARD: Map<String, Option<Vec<String>>> = Map::new("dear_leader_board");
+// registered dear_leader_accounts and the number of user accounts delegated to each.
+pub const DEAR_LEADERS: Map<String, u64> = Map::new("dear_leaders");
+
+// relation between dear_leader_account and its vote delegatores, keyed by (dear leader, user account).
+// Retired dear leaders keep the delegators not released yet. Snapshotted so a dear leader vote only
+// goes to the delegators it had when the proposal was first synced.
+pub const DEAR_LEADER_DELEGATORS: SnapshotMap<(String, String), bool> = SnapshotMap::new(
+    "dear_leader_delegators",
+    "dear_leader_delegators__checkpoints",
+    "dear_leader_delegators__changelog",
+    Strategy::EveryBlock,
+);
+
+// retired dear leaders and the number of delegators whose vote power was not released yet.
+pub const RETIRING_DEAR_LEADERS: Map<String, u64> = Map::new("retiring_leaders");
+
+// profile each dear_leader_account publishes for delegators to discover it.
+pub const DEAR_LEADER_PROFILES: Map<String, DearLeaderProfile> = Map::new("dear_leader_profiles");
 
 // relation between user_account and if the vote power is delegated, and if so to whom.
-pub const BOSS_VOTE_POWER: Map<String, Option<String>> = Map::new("boss_vote_power");
+// Snapshotted so proposals are tallied against the delegations at their start height.
+pub const BOSS_VOTE_POWER: SnapshotMap<String, Option<String>> = SnapshotMap::new(
+    "boss_vote_power",
+    "boss_vote_power__checkpoints",
+    "boss_vote_power__changelog",
+    Strategy::EveryBlock,
+);
+
+// bonded stake of each user account, recomputed from the chain on every sync.
+pub const ACCOUNT_STAKE: SnapshotMap<String, Uint128> = SnapshotMap::new(
+    "account_stake",
+    "account_stake__checkpoints",
+    "account_stake__changelog",
+    Strategy::EveryBlock,
+);
+
+// sum of the stake of the user accounts that transferred their vote power to each dear leader.
+pub const DEAR_LEADER_POWER: SnapshotMap<String, Uint128> = SnapshotMap::new(
+    "dear_leader_power",
+    "dear_leader_power__checkpoints",
+    "dear_leader_power__changelog",
+    Strategy::EveryBlock,
+);
+
+// power of the dear leaders following each dear leader on every proposal, up to
+// MAX_DELEGATION_DEPTH hops.
+pub const DEAR_LEADER_DELEGATED_POWER: SnapshotMap<String, Uint128> = SnapshotMap::new(
+    "dear_leader_delegated_power",
+    "dear_leader_delegated_power__checkpoints",
+    "dear_leader_delegated_power__changelog",
+    Strategy::EveryBlock,
+);
+
+// registered dear leaders by voting power, with the power of the dear leaders following them on
+// every proposal, and by number of delegators. The leaderboard pages over these instead of
+// sorting every dear leader.
+pub const LEADERS_BY_POWER: Map<(u128, String), bool> = Map::new("leaders_by_power");
+pub const LEADERS_BY_DELEGATORS: Map<(u64, String), bool> = Map::new("leaders_by_delegators");
+
+// power and delegators each dear leader is indexed with above, to move its entries.
+pub const LEADERBOARD_KEYS: Map<String, (u128, u64)> = Map::new("leaderboard_keys");
+
+// gov proposals synced into the assembly, votes are only taken on these.
+pub const PROPOSALS: Map<u64, RegisteredProposal> = Map::new("proposals");
+
+// user accounts that voted directly on each proposal, keyed by (proposal, user account).
+pub const PROPOSAL_VOTE_HISTORY: Map<(u64, String), bool> = Map::new("proposal_votes");
+
+// number of proposals that entered their voting period, dear leader participation is measured against it.
+pub const PROPOSAL_COUNT: Item<u64> = Item::new("proposal_count");
+
+// participation and alignment of each dear leader.
+pub const LEADER_STATS: Map<String, LeaderStats> = Map::new("leader_stats");
+
+// delegators each dear leader gained and lost, by period of CHURN_PERIOD seconds.
+pub const LEADER_CHURN: Map<(String, u64), ChurnPeriod> = Map::new("leader_churn");
+
+// dear leaders each dear leader defers its votes to, by proposal category.
+pub const LEADER_DELEGATIONS: Map<String, Vec<LeaderDelegation>> = Map::new("leader_delegations");
 
-//relation between proposals and user_accounts
-pub const PROPOSAL_VOTE_HISTORY: Map<u64, Vec<String>> = Map::new("proposal_vote_history");
+// relation between followed dear leaders and their followers, to resolve chains from the voter.
+pub const LEADER_FOLLOWERS: Map<(String, String), bool> = Map::new("leader_followers");
+
+// vote each dear leader cast on each proposal, with its rationale.
+pub const DEAR_LEADER_VOTES: Map<(String, u64), DearLeaderVote> = Map::new("dear_leader_votes");
+
+// votes dear leaders announced, cast by anyone once the review window is over.
+pub const ANNOUNCED_VOTES: Map<(String, u64), VoteAnnouncement> = Map::new("announced_votes");
+
+// user accounts that don't follow their dear leader on a proposal.
+pub const LEADER_VOTE_OPT_OUTS: Map<(u64, String), bool> = Map::new("leader_vote_opt_outs");
+
+// fallback each user account set for when its dear leader doesn't vote, Nothing is not stored.
+pub const VOTE_FALLBACKS: Map<String, VoteFallback> = Map::new("vote_fallbacks");
+
+// vote option ApplyFallbacks cast for each user account, so it's only cast once per proposal.
+pub const FALLBACK_VOTES: Map<(u64, String), u64> = Map::new("fallback_votes");
+
+// seconds delegators get to review an announced vote, capped by the proposal voting end.
+pub const REVIEW_WINDOW: Item<u64> = Item::new("review_window");
 
 // address of the dear_leader_account factory
 pub const DEAR_LEADER_ACCOUNT_FACTORY: Item<String> = Item::new("dear_leader_account_factory");
+
+// max commission a validator can charge to receive delegations from user accounts.
+pub const MAX_VALIDATOR_COMMISSION: Item<Option<Decimal>> = Item::new("max_validator_commission");
+
+// validators user accounts can delegate to. If empty, every validator is allowed.
+pub const VALIDATOR_ALLOW_LIST: Map<String, bool> = Map::new("validator_allow_list");
+
+// layout before delegators and votes got composite keys, only read by the migration.
+pub const LEGACY_DEAR_LEADER_BOARD: Map<String, Option<Vec<String>>> =
+    Map::new("dear_leader_board");
+pub const LEGACY_PROPOSAL_VOTE_HISTORY: Map<u64, Vec<String>> = Map::new("proposal_vote_history");
+
+#[cw_serde]
+pub struct RegisteredProposal {
+    pub proposal_id: u64,
+    pub title: String,
+    /// content type of the proposal, used as its category
+    pub content_type: String,
+    /// status as of the last sync
+    pub status: ProposalStatus,
+    pub voting_end_time: Timestamp,
+    /// block height the proposal was first synced at. Dear leader votes and fallbacks go to the
+    /// delegations held at the end of this block that still hold
+    pub start_height: u64,
+}
+
+#[cw_serde]
+pub struct LeaderDelegation {
+    pub dear_leader: String,
+    /// gov proposal content types deferred to the dear leader, all of them if None
+    pub categories: Option<Vec<String>>,
+}
+
+#[cw_serde]
+pub struct VoteAnnouncement {
+    pub dear_leader: String,
+    pub vote: DearLeaderVote,
+    /// The vote can't be cast before this time.
+    pub execute_at: Timestamp,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct LeaderStats {
+    /// proposals the dear leader voted on
+    pub votes_cast: u64,
+    /// proposals registered while the dear leader had delegators, up to its last delegator leaving
+    pub eligible_proposals: u64,
+    /// proposal count when the dear leader got its first delegator, while it has any
+    pub active_since: Option<u64>,
+    /// direct votes and opt outs of delegators, at most one per delegator and proposal
+    pub overrides: u64,
+    pub delegators_gained: u64,
+    pub delegators_lost: u64,
+}
+
+#[cw_serde]
+pub struct ChurnPeriod {
+    /// start of the period, in seconds
+    pub period_start: u64,
+    pub gained: u64,
+    pub lost: u64,
+}
@@ -5,7 +5,7 @@ use cw2::set_contract_version;
 
 use crate::error::ContractError;
 use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Config, ASSEMBLY_ADDR, CONFIG};
+use crate::state::{Config, ACCOUNTS_FACTORY_ADDR, ASSEMBLY_ADDR, CONFIG};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:dear_leader_acount";
@@ -15,21 +15,35 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    info: MessageInfo,
+    _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    let (owner_addr, assembly_addr, accounts_factory_addr) = match msg {
+        InstantiateMsg::InstatiateDearLeaderAccount {
+            owner_addr,
+            assembly_addr,
+            accounts_factory_addr,
+        } => (owner_addr, assembly_addr, accounts_factory_addr),
+        _ => return Err(ContractError::InvalidInstantiateMsg {}),
+    };
+
+    // the factory instantiates the account on behalf of the dear leader
+    let owner = deps.api.addr_validate(&owner_addr)?;
     let config = Config {
-        owner: info.sender.to_string(),
+        owner: owner.to_string(),
     };
     CONFIG.save(deps.storage, &config)?;
 
-    ASSEMBLY_ADDR.save(deps.storage, &msg.assembly_addr)?;
+    ASSEMBLY_ADDR.save(deps.storage, &assembly_addr)?;
+    if let Some(accounts_factory_addr) = accounts_factory_addr {
+        ACCOUNTS_FACTORY_ADDR.save(deps.storage, &accounts_factory_addr)?;
+    }
 
     Ok(Response::new()
         .add_attribute("action", "instantiate_dear_leader_account")
-        .add_attribute("owner", info.sender.to_string()))
+        .add_attribute("owner", owner))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -39,46 +53,604 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    // accounts run by a council only take these through ProposeCouncilAction
+    if execute::requires_council_approval(deps.as_ref(), &info, &msg)? {
+        return Err(ContractError::CouncilApprovalRequired {});
+    }
+
     match msg {
         ExecuteMsg::Vote {
             proposal_id,
             vote_option,
-        } => execute::vote(deps, env, info, proposal_id, vote_option),
+            rationale,
+        } => execute::vote(deps, env, info, proposal_id, vote_option, rationale),
+        ExecuteMsg::AnnounceVote {
+            proposal_id,
+            vote_option,
+            rationale,
+        } => execute::announce_vote(deps, info, proposal_id, vote_option, rationale),
+        ExecuteMsg::SetProfile { profile } => execute::set_profile(deps, info, profile),
+        ExecuteMsg::EnableCouncil { members, threshold } => {
+            execute::enable_council(deps, info, members, threshold)
+        }
+        ExecuteMsg::ProposeCouncilAction { action } => {
+            execute::propose_council_action(deps, info, action)
+        }
+        ExecuteMsg::ApproveCouncilAction { proposal_id } => {
+            execute::approve_council_action(deps, info, proposal_id)
+        }
+        ExecuteMsg::GrantStaff {
+            staffer,
+            role,
+            categories,
+            expires,
+        } => execute::grant_staff(deps, info, staffer, role, categories, expires),
+        ExecuteMsg::RevokeStaff { staffer } => execute::revoke_staff(deps, info, staffer),
+        ExecuteMsg::TransferOwnership { new_owner } => {
+            execute::transfer_ownership(deps, info, new_owner)
+        }
+        ExecuteMsg::AcceptOwnership {} => execute::accept_ownership(deps, info),
+        ExecuteMsg::Retire {} => execute::retire(deps, info),
+        ExecuteMsg::FollowDearLeader {
+            dear_leader_addr,
+            categories,
+        } => execute::follow_dear_leader(deps, info, dear_leader_addr, categories),
+        ExecuteMsg::UnfollowDearLeader { dear_leader_addr } => {
+            execute::unfollow_dear_leader(deps, info, dear_leader_addr)
+        }
     }
 }
 
 pub mod execute {
-    use cosmwasm_std::WasmMsg;
+    use cosmwasm_std::{Timestamp, WasmMsg};
+    use util_types::proto::query_gov_proposal;
 
-    use crate::state::{ASSEMBLY_ADDR, CONFIG};
-    use util_types::ExecuteMsg as ExecuteMsgCommon;
+    use crate::msg::{CouncilAction, StaffRole};
+    use crate::state::{
+        Council, CouncilProposal, SignedVote, StaffGrant, ACCOUNTS_FACTORY_ADDR, ASSEMBLY_ADDR,
+        CONFIG, COUNCIL, COUNCIL_PROPOSALS, NEXT_COUNCIL_PROPOSAL_ID, PENDING_OWNER, STAFF_GRANTS,
+        VOTE_SIGNERS,
+    };
+    use util_types::{DearLeaderProfile, ExecuteMsg as ExecuteMsgCommon, VoteRationale};
 
     use super::*;
 
     pub fn vote(
         deps: DepsMut,
-        _env: Env,
+        env: Env,
         info: MessageInfo,
         proposal_id: u64,
         vote_option: u64,
+        rationale: Option<VoteRationale>,
     ) -> Result<Response, ContractError> {
-        // validate contract caller is owner
-        validate_owner(deps.as_ref(), &info)?;
-        // create vote message to assembly contract
+        // validate contract caller is owner or a staffer allowed to vote on this proposal
+        validate_voter(deps.as_ref(), &env, &info, proposal_id)?;
+
+        cast_vote(
+            deps,
+            vec![info.sender.to_string()],
+            proposal_id,
+            vote_option,
+            rationale,
+        )
+    }
+
+    fn cast_vote(
+        deps: DepsMut,
+        signers: Vec<String>,
+        proposal_id: u64,
+        vote_option: u64,
+        rationale: Option<VoteRationale>,
+    ) -> Result<Response, ContractError> {
+        VOTE_SIGNERS.update(deps.storage, proposal_id, |votes| -> StdResult<_> {
+            let mut votes = votes.unwrap_or_default();
+            votes.push(SignedVote {
+                vote_option,
+                signers: signers.clone(),
+            });
+            Ok(votes)
+        })?;
+
+        // create vote message to assembly contract, the assembly stores the rationale
         let msg = WasmMsg::Execute {
             contract_addr: ASSEMBLY_ADDR.load(deps.storage)?,
             msg: to_binary(&ExecuteMsgCommon::DearLeaderVote {
                 proposal_id,
                 vote_option,
+                rationale,
             })?,
             funds: vec![],
         };
 
         Ok(Response::new()
             .add_attribute("action", "vote")
+            .add_attribute("signed_by", signers.join(","))
+            .add_message(msg))
+    }
+
+    pub fn grant_staff(
+        deps: DepsMut,
+        info: MessageInfo,
+        staffer: String,
+        role: StaffRole,
+        categories: Option<Vec<String>>,
+        expires: Option<Timestamp>,
+    ) -> Result<Response, ContractError> {
+        // validate contract caller is owner
+        validate_owner(deps.as_ref(), &info)?;
+        save_staff_grant(deps, staffer, role, categories, expires)
+    }
+
+    fn save_staff_grant(
+        deps: DepsMut,
+        staffer: String,
+        role: StaffRole,
+        categories: Option<Vec<String>>,
+        expires: Option<Timestamp>,
+    ) -> Result<Response, ContractError> {
+        let staffer = deps
+            .api
+            .addr_validate(&staffer)
+            .map_err(|_| ContractError::InvalidAddr {})?
+            .to_string();
+
+        // granting again replaces the previous grant
+        STAFF_GRANTS.save(
+            deps.storage,
+            staffer.clone(),
+            &StaffGrant {
+                staffer: staffer.clone(),
+                role,
+                categories,
+                expires,
+            },
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "grant_staff")
+            .add_attribute("staffer", staffer))
+    }
+
+    pub fn revoke_staff(
+        deps: DepsMut,
+        info: MessageInfo,
+        staffer: String,
+    ) -> Result<Response, ContractError> {
+        // validate contract caller is owner
+        validate_owner(deps.as_ref(), &info)?;
+        remove_staff_grant(deps, staffer)
+    }
+
+    fn remove_staff_grant(deps: DepsMut, staffer: String) -> Result<Response, ContractError> {
+        if !STAFF_GRANTS.has(deps.storage, staffer.clone()) {
+            return Err(ContractError::StaffGrantNotFound {});
+        }
+        STAFF_GRANTS.remove(deps.storage, staffer.clone());
+
+        Ok(Response::new()
+            .add_attribute("action", "revoke_staff")
+            .add_attribute("staffer", staffer))
+    }
+
+    fn validate_voter(
+        deps: Deps,
+        env: &Env,
+        info: &MessageInfo,
+        proposal_id: u64,
+    ) -> Result<(), ContractError> {
+        if validate_owner(deps, info).is_ok() {
+            return Ok(());
+        }
+
+        let grant = STAFF_GRANTS
+            .may_load(deps.storage, info.sender.to_string())?
+            .ok_or(ContractError::Unauthorized {})?;
+        if grant
+            .expires
+            .map_or(false, |expires| expires <= env.block.time)
+        {
+            return Err(ContractError::StaffGrantExpired {});
+        }
+
+        match grant.role {
+            StaffRole::Voter => {
+                // categories are checked against the content type of the gov proposal
+                if let Some(categories) = grant.categories {
+                    let proposal = query_gov_proposal(&deps.querier, proposal_id)?;
+                    if !categories.contains(&proposal.content_type) {
+                        return Err(ContractError::CategoryNotGranted {});
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn announce_vote(
+        deps: DepsMut,
+        info: MessageInfo,
+        proposal_id: u64,
+        vote_option: u64,
+        rationale: Option<VoteRationale>,
+    ) -> Result<Response, ContractError> {
+        // validate contract caller is owner
+        validate_owner(deps.as_ref(), &info)?;
+        // the assembly casts the vote once delegators had the time to review it
+        let msg = WasmMsg::Execute {
+            contract_addr: ASSEMBLY_ADDR.load(deps.storage)?,
+            msg: to_binary(&ExecuteMsgCommon::AnnounceDearLeaderVote {
+                proposal_id,
+                vote_option,
+                rationale,
+            })?,
+            funds: vec![],
+        };
+
+        Ok(Response::new()
+            .add_attribute("action", "announce_vote")
+            .add_message(msg))
+    }
+
+    pub fn set_profile(
+        deps: DepsMut,
+        info: MessageInfo,
+        profile: DearLeaderProfile,
+    ) -> Result<Response, ContractError> {
+        // validate contract caller is owner
+        validate_owner(deps.as_ref(), &info)?;
+        // the assembly keeps the profiles so they can be listed together
+        let msg = WasmMsg::Execute {
+            contract_addr: ASSEMBLY_ADDR.load(deps.storage)?,
+            msg: to_binary(&ExecuteMsgCommon::SetDearLeaderProfile { profile })?,
+            funds: vec![],
+        };
+
+        Ok(Response::new()
+            .add_attribute("action", "set_profile")
+            .add_message(msg))
+    }
+
+    pub fn transfer_ownership(
+        deps: DepsMut,
+        info: MessageInfo,
+        new_owner: String,
+    ) -> Result<Response, ContractError> {
+        // validate contract caller is owner
+        validate_owner(deps.as_ref(), &info)?;
+        let new_owner = deps
+            .api
+            .addr_validate(&new_owner)
+            .map_err(|_| ContractError::InvalidAddr {})?;
+
+        // proposing again replaces the pending owner
+        PENDING_OWNER.save(deps.storage, &new_owner.to_string())?;
+
+        Ok(Response::new()
+            .add_attribute("action", "transfer_ownership")
+            .add_attribute("pending_owner", new_owner))
+    }
+
+    pub fn accept_ownership(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+        let pending_owner = PENDING_OWNER
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoPendingOwner {})?;
+        if info.sender != pending_owner {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        let mut config = CONFIG.load(deps.storage)?;
+        let previous_owner = std::mem::replace(&mut config.owner, pending_owner);
+        CONFIG.save(deps.storage, &config)?;
+        PENDING_OWNER.remove(deps.storage);
+        // staff was chosen by the previous owner
+        STAFF_GRANTS.clear(deps.storage);
+
+        // the factory indexes dear leader accounts by owner
+        let msgs = match ACCOUNTS_FACTORY_ADDR.may_load(deps.storage)? {
+            Some(accounts_factory_addr) => vec![WasmMsg::Execute {
+                contract_addr: accounts_factory_addr,
+                msg: to_binary(&ExecuteMsgCommon::UpdateDearLeaderAccountOwner {
+                    previous_owner: previous_owner.clone(),
+                    new_owner: config.owner.clone(),
+                })?,
+                funds: vec![],
+            }],
+            None => vec![],
+        };
+
+        Ok(Response::new()
+            .add_attribute("action", "accept_ownership")
+            .add_attribute("previous_owner", previous_owner)
+            .add_attribute("owner", config.owner)
+            .add_messages(msgs))
+    }
+
+    pub fn retire(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+        // validate contract caller is owner
+        validate_owner(deps.as_ref(), &info)?;
+        // the assembly releases the delegators and notifies them
+        let msg = WasmMsg::Execute {
+            contract_addr: ASSEMBLY_ADDR.load(deps.storage)?,
+            msg: to_binary(&ExecuteMsgCommon::RetireDearLeader {})?,
+            funds: vec![],
+        };
+
+        Ok(Response::new()
+            .add_attribute("action", "retire")
+            .add_message(msg))
+    }
+
+    pub fn follow_dear_leader(
+        deps: DepsMut,
+        info: MessageInfo,
+        dear_leader_addr: String,
+        categories: Option<Vec<String>>,
+    ) -> Result<Response, ContractError> {
+        // validate contract caller is owner
+        validate_owner(deps.as_ref(), &info)?;
+        // the assembly resolves the chain when the followed dear leader votes
+        let msg = WasmMsg::Execute {
+            contract_addr: ASSEMBLY_ADDR.load(deps.storage)?,
+            msg: to_binary(&ExecuteMsgCommon::FollowDearLeader {
+                dear_leader_addr: dear_leader_addr.clone(),
+                categories,
+            })?,
+            funds: vec![],
+        };
+
+        Ok(Response::new()
+            .add_attribute("action", "follow_dear_leader")
+            .add_attribute("dear_leader_addr", dear_leader_addr)
             .add_message(msg))
     }
 
+    pub fn unfollow_dear_leader(
+        deps: DepsMut,
+        info: MessageInfo,
+        dear_leader_addr: String,
+    ) -> Result<Response, ContractError> {
+        // validate contract caller is owner
+        validate_owner(deps.as_ref(), &info)?;
+        let msg = WasmMsg::Execute {
+            contract_addr: ASSEMBLY_ADDR.load(deps.storage)?,
+            msg: to_binary(&ExecuteMsgCommon::UnfollowDearLeader {
+                dear_leader_addr: dear_leader_addr.clone(),
+            })?,
+            funds: vec![],
+        };
+
+        Ok(Response::new()
+            .add_attribute("action", "unfollow_dear_leader")
+            .add_attribute("dear_leader_addr", dear_leader_addr)
+            .add_message(msg))
+    }
+
+    pub fn requires_council_approval(
+        deps: Deps,
+        info: &MessageInfo,
+        msg: &ExecuteMsg,
+    ) -> StdResult<bool> {
+        let guarded = match msg {
+            // staff granted by the council keep voting on their own, the owner can't
+            ExecuteMsg::Vote { .. } => {
+                info.sender == CONFIG.load(deps.storage)?.owner
+                    || !STAFF_GRANTS.has(deps.storage, info.sender.to_string())
+            }
+            _ => matches!(
+                msg,
+                ExecuteMsg::AnnounceVote { .. }
+                    | ExecuteMsg::SetProfile { .. }
+                    | ExecuteMsg::EnableCouncil { .. }
+                    | ExecuteMsg::GrantStaff { .. }
+                    | ExecuteMsg::RevokeStaff { .. }
+                    | ExecuteMsg::TransferOwnership { .. }
+                    | ExecuteMsg::Retire { .. }
+                    | ExecuteMsg::FollowDearLeader { .. }
+                    | ExecuteMsg::UnfollowDearLeader { .. }
+            ),
+        };
+        Ok(guarded && COUNCIL.may_load(deps.storage)?.is_some())
+    }
+
+    // once enabled, the owner can't act alone anymore
+    pub fn enable_council(
+        deps: DepsMut,
+        info: MessageInfo,
+        members: Vec<String>,
+        threshold: u64,
+    ) -> Result<Response, ContractError> {
+        // validate contract caller is owner
+        validate_owner(deps.as_ref(), &info)?;
+        if COUNCIL.may_load(deps.storage)?.is_some() {
+            return Err(ContractError::CouncilAlreadyEnabled {});
+        }
+
+        let council = validate_council(deps.as_ref(), vec![], members, threshold, 0)?;
+        COUNCIL.save(deps.storage, &council)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "enable_council")
+            .add_attribute("members", council.members.join(","))
+            .add_attribute("threshold", council.threshold.to_string()))
+    }
+
+    pub fn propose_council_action(
+        deps: DepsMut,
+        info: MessageInfo,
+        action: CouncilAction,
+    ) -> Result<Response, ContractError> {
+        let council = validate_council_member(deps.as_ref(), &info)?;
+
+        // proposing counts as the proposer approval
+        let id = NEXT_COUNCIL_PROPOSAL_ID
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        let proposal = CouncilProposal {
+            id,
+            action,
+            proposer: info.sender.to_string(),
+            approvals: vec![info.sender.to_string()],
+            epoch: council.epoch,
+        };
+        COUNCIL_PROPOSALS.save(deps.storage, id, &proposal)?;
+        NEXT_COUNCIL_PROPOSAL_ID.save(deps.storage, &(id + 1))?;
+
+        let response = execute_if_approved(deps, proposal)?;
+
+        Ok(response
+            .add_attribute("council_action", "propose")
+            .add_attribute("proposal_id", id.to_string())
+            .add_attribute("proposer", info.sender.to_string()))
+    }
+
+    pub fn approve_council_action(
+        deps: DepsMut,
+        info: MessageInfo,
+        proposal_id: u64,
+    ) -> Result<Response, ContractError> {
+        let council = validate_council_member(deps.as_ref(), &info)?;
+
+        let mut proposal = COUNCIL_PROPOSALS
+            .may_load(deps.storage, proposal_id)?
+            .ok_or(ContractError::CouncilProposalNotFound {})?;
+        if proposal.epoch != council.epoch {
+            return Err(ContractError::CouncilProposalStale {});
+        }
+        if proposal.approvals.contains(&info.sender.to_string()) {
+            return Err(ContractError::AlreadyApproved {});
+        }
+        proposal.approvals.push(info.sender.to_string());
+        COUNCIL_PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+
+        let response = execute_if_approved(deps, proposal)?;
+
+        Ok(response
+            .add_attribute("council_action", "approve")
+            .add_attribute("proposal_id", proposal_id.to_string())
+            .add_attribute("approved_by", info.sender.to_string()))
+    }
+
+    // runs the action as soon as enough members approved it, all of them are current members
+    // since any membership change bumps the epoch
+    fn execute_if_approved(
+        deps: DepsMut,
+        proposal: CouncilProposal,
+    ) -> Result<Response, ContractError> {
+        let council = COUNCIL.load(deps.storage)?;
+        let n_of_approvals = proposal.approvals.len() as u64;
+        if n_of_approvals < council.threshold {
+            return Ok(Response::new().add_attribute(
+                "approvals",
+                format!("{}/{}", n_of_approvals, council.threshold),
+            ));
+        }
+        COUNCIL_PROPOSALS.remove(deps.storage, proposal.id);
+
+        // the council approval stands in for the owner signature
+        let owner_info = MessageInfo {
+            sender: deps.api.addr_validate(&CONFIG.load(deps.storage)?.owner)?,
+            funds: vec![],
+        };
+
+        let response = match proposal.action {
+            CouncilAction::Vote {
+                proposal_id,
+                vote_option,
+                rationale,
+            } => cast_vote(
+                deps,
+                proposal.approvals,
+                proposal_id,
+                vote_option,
+                rationale,
+            )?,
+            CouncilAction::AnnounceVote {
+                proposal_id,
+                vote_option,
+                rationale,
+            } => announce_vote(deps, owner_info, proposal_id, vote_option, rationale)?,
+            CouncilAction::SetProfile { profile } => set_profile(deps, owner_info, profile)?,
+            CouncilAction::UpdateMembers {
+                to_add,
+                to_remove,
+                threshold,
+            } => {
+                let members = council
+                    .members
+                    .into_iter()
+                    .filter(|member| !to_remove.contains(member))
+                    .collect();
+                let council =
+                    validate_council(deps.as_ref(), members, to_add, threshold, council.epoch + 1)?;
+                COUNCIL.save(deps.storage, &council)?;
+
+                Response::new()
+                    .add_attribute("action", "update_council_members")
+                    .add_attribute("members", council.members.join(","))
+                    .add_attribute("threshold", council.threshold.to_string())
+            }
+            CouncilAction::GrantStaff {
+                staffer,
+                role,
+                categories,
+                expires,
+            } => save_staff_grant(deps, staffer, role, categories, expires)?,
+            CouncilAction::RevokeStaff { staffer } => remove_staff_grant(deps, staffer)?,
+            CouncilAction::TransferOwnership { new_owner } => {
+                transfer_ownership(deps, owner_info, new_owner)?
+            }
+            CouncilAction::Retire {} => retire(deps, owner_info)?,
+            CouncilAction::FollowDearLeader {
+                dear_leader_addr,
+                categories,
+            } => follow_dear_leader(deps, owner_info, dear_leader_addr, categories)?,
+            CouncilAction::UnfollowDearLeader { dear_leader_addr } => {
+                unfollow_dear_leader(deps, owner_info, dear_leader_addr)?
+            }
+        };
+
+        Ok(response.add_attribute("executed_proposal_id", proposal.id.to_string()))
+    }
+
+    fn validate_council(
+        deps: Deps,
+        mut members: Vec<String>,
+        to_add: Vec<String>,
+        threshold: u64,
+        epoch: u64,
+    ) -> Result<Council, ContractError> {
+        for member in to_add.iter() {
+            let member = deps
+                .api
+                .addr_validate(member)
+                .map_err(|_| ContractError::InvalidAddr {})?
+                .to_string();
+            if !members.contains(&member) {
+                members.push(member);
+            }
+        }
+
+        if threshold == 0 || threshold > members.len() as u64 {
+            return Err(ContractError::InvalidThreshold {});
+        }
+
+        Ok(Council {
+            members,
+            threshold,
+            epoch,
+        })
+    }
+
+    fn validate_council_member(deps: Deps, info: &MessageInfo) -> Result<Council, ContractError> {
+        let council = COUNCIL
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoCouncil {})?;
+        if !council.members.contains(&info.sender.to_string()) {
+            return Err(ContractError::Unauthorized {});
+        }
+        Ok(council)
+    }
+
     pub fn validate_owner(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
         let config = CONFIG.load(deps.storage)?;
         if info.sender != config.owner {
@@ -89,24 +661,783 @@ pub mod execute {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::GetOwnerAddr {} => to_binary(&query::get_owner_addr(deps)?),
+        QueryMsg::GetOwnerAddr {} => query::get_owner_addr(deps),
+        QueryMsg::GetConfig {} => query::get_config(deps),
+        QueryMsg::GetDelegators { start_after, limit } => {
+            query::get_delegators(deps, env, start_after, limit)
+        }
+        QueryMsg::GetVotingPower {} => query::get_voting_power(deps, env),
+        QueryMsg::GetCouncil {} => query::get_council(deps),
+        QueryMsg::GetStaffGrants { start_after, limit } => {
+            query::get_staff_grants(deps, env, start_after, limit)
+        }
+        QueryMsg::GetVoteSigners { proposal_id } => query::get_vote_signers(deps, proposal_id),
+        QueryMsg::GetCouncilProposals { start_after, limit } => {
+            query::get_council_proposals(deps, start_after, limit)
+        }
+        QueryMsg::GetVotes { start_after, limit } => {
+            query::get_votes(deps, env, start_after, limit)
+        }
     }
 }
 
 pub mod query {
 
-    use crate::msg::GetOwnerAddrResponse;
+    use util_types::{
+        GetAccountStakeResponse, GetDearLeaderDelegatoresResponse, GetDearLeaderPowerResponse,
+        GetDearLeaderVotesResponse, QueryMsg as QueryMsgCommon,
+    };
+
+    use cosmwasm_std::Order;
+    use cw_storage_plus::Bound;
+
+    use crate::msg::{
+        Delegator, GetConfigResponse, GetCouncilProposalsResponse, GetCouncilResponse,
+        GetDelegatorsResponse, GetOwnerAddrResponse, GetStaffGrantsResponse,
+        GetVoteSignersResponse, GetVotingPowerResponse,
+    };
+    use crate::state::{COUNCIL, COUNCIL_PROPOSALS, PENDING_OWNER, STAFF_GRANTS, VOTE_SIGNERS};
 
     use super::*;
 
+    const DEFAULT_LIMIT: u32 = 10;
+    const MAX_LIMIT: u32 = 30;
+
     pub fn get_owner_addr(deps: Deps) -> StdResult<Binary> {
         let owner_addr = CONFIG.load(deps.storage)?.owner;
         let response = GetOwnerAddrResponse { owner_addr };
         to_binary(&response)
     }
+
+    pub fn get_config(deps: Deps) -> StdResult<Binary> {
+        to_binary(&GetConfigResponse {
+            owner: CONFIG.load(deps.storage)?.owner,
+            pending_owner: PENDING_OWNER.may_load(deps.storage)?,
+            assembly_addr: ASSEMBLY_ADDR.load(deps.storage)?,
+            accounts_factory_addr: ACCOUNTS_FACTORY_ADDR.may_load(deps.storage)?,
+        })
+    }
+
+    // the assembly keeps the delegators of each dear leader, this account is the dear leader
+    pub fn get_delegators(
+        deps: Deps,
+        env: Env,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let assembly_addr = ASSEMBLY_ADDR.load(deps.storage)?;
+        let response: GetDearLeaderDelegatoresResponse = deps.querier.query_wasm_smart(
+            &assembly_addr,
+            &QueryMsgCommon::GetDearLeaderDelegatores {
+                dear_leader_addr: env.contract.address.to_string(),
+                start_after,
+                limit: Some(limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT)),
+            },
+        )?;
+
+        let delegators = response
+            .delegatores_list
+            .unwrap_or_default()
+            .into_iter()
+            .map(|user_account| {
+                let stake: GetAccountStakeResponse = deps.querier.query_wasm_smart(
+                    &assembly_addr,
+                    &QueryMsgCommon::GetAccountStake {
+                        user_account_addr: user_account.clone(),
+                    },
+                )?;
+                Ok(Delegator {
+                    user_account,
+                    stake: stake.stake,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        to_binary(&GetDelegatorsResponse { delegators })
+    }
+
+    pub fn get_voting_power(deps: Deps, env: Env) -> StdResult<Binary> {
+        let response: GetDearLeaderPowerResponse = deps.querier.query_wasm_smart(
+            ASSEMBLY_ADDR.load(deps.storage)?,
+            &QueryMsgCommon::GetDearLeaderPower {
+                dear_leader_addr: env.contract.address.to_string(),
+            },
+        )?;
+
+        to_binary(&GetVotingPowerResponse {
+            power: response.power,
+        })
+    }
+
+    pub fn get_staff_grants(
+        deps: Deps,
+        env: Env,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let grants = STAFF_GRANTS
+            .range(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .filter(|item| {
+                item.as_ref().map_or(true, |(_, grant)| {
+                    grant
+                        .expires
+                        .map_or(true, |expires| expires > env.block.time)
+                })
+            })
+            .take(limit)
+            .map(|item| item.map(|(_, grant)| grant))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        to_binary(&GetStaffGrantsResponse { grants })
+    }
+
+    pub fn get_vote_signers(deps: Deps, proposal_id: u64) -> StdResult<Binary> {
+        let votes = VOTE_SIGNERS
+            .may_load(deps.storage, proposal_id)?
+            .unwrap_or_default();
+
+        to_binary(&GetVoteSignersResponse { votes })
+    }
+
+    pub fn get_council(deps: Deps) -> StdResult<Binary> {
+        to_binary(&GetCouncilResponse {
+            council: COUNCIL.may_load(deps.storage)?,
+        })
+    }
+
+    pub fn get_council_proposals(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        // proposals from before the last membership change can't be approved anymore
+        let epoch = COUNCIL.may_load(deps.storage)?.map(|council| council.epoch);
+        let proposals = COUNCIL_PROPOSALS
+            .range(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .filter(|item| {
+                item.as_ref()
+                    .map_or(true, |(_, proposal)| Some(proposal.epoch) == epoch)
+            })
+            .take(limit)
+            .map(|item| item.map(|(_, proposal)| proposal))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        to_binary(&GetCouncilProposalsResponse { proposals })
+    }
+
+    pub fn get_votes(
+        deps: Deps,
+        env: Env,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let response: GetDearLeaderVotesResponse = deps.querier.query_wasm_smart(
+            ASSEMBLY_ADDR.load(deps.storage)?,
+            &QueryMsgCommon::GetDearLeaderVotes {
+                dear_leader_addr: env.contract.address.to_string(),
+                start_after,
+                limit,
+            },
+        )?;
+
+        to_binary(&response)
+    }
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{from_binary, from_slice, SystemResult, Uint128, WasmQuery};
+    use cosmwasm_std::{CosmosMsg, WasmMsg};
+    use util_types::proto::GovProposal;
+    use util_types::testing::mock_dependencies_with_gov;
+    use util_types::{
+        DearLeaderVote, ExecuteMsg as ExecuteMsgCommon, GetAccountStakeResponse,
+        GetDearLeaderDelegatoresResponse, GetDearLeaderPowerResponse, GetDearLeaderVotesResponse,
+        ProposalStatus, QueryMsg as QueryMsgCommon,
+    };
+
+    use super::*;
+    use crate::msg::{
+        CouncilAction, GetConfigResponse, GetCouncilProposalsResponse, GetCouncilResponse,
+        GetDelegatorsResponse, GetStaffGrantsResponse, GetVoteSignersResponse,
+        GetVotingPowerResponse, StaffRole,
+    };
+    use crate::state::SignedVote;
+
+    const OWNER: &str = "owner";
+    const ASSEMBLY: &str = "assembly";
+
+    #[test]
+    fn queries_go_through_the_assembly() {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(|query| {
+            let msg = match query {
+                WasmQuery::Smart { contract_addr, msg } if contract_addr == ASSEMBLY => msg,
+                _ => unimplemented!(),
+            };
+            let response = match from_slice(msg).unwrap() {
+                // the assembly pages the delegators
+                QueryMsgCommon::GetDearLeaderDelegatores {
+                    start_after, limit, ..
+                } => {
+                    let delegatores_list = ["account_a", "account_b", "account_c"]
+                        .iter()
+                        .filter(|user_account| Some(**user_account) > start_after.as_deref())
+                        .take(limit.unwrap() as usize)
+                        .map(|user_account| user_account.to_string())
+                        .collect();
+                    to_binary(&GetDearLeaderDelegatoresResponse {
+                        delegatores_list: Some(delegatores_list),
+                    })
+                }
+                QueryMsgCommon::GetAccountStake { user_account_addr } => {
+                    to_binary(&GetAccountStakeResponse {
+                        stake: Uint128::new(if user_account_addr == "account_b" {
+                            20
+                        } else {
+                            10
+                        }),
+                    })
+                }
+                QueryMsgCommon::GetDearLeaderPower { .. } => {
+                    to_binary(&GetDearLeaderPowerResponse {
+                        power: Uint128::new(40),
+                    })
+                }
+                QueryMsgCommon::GetDearLeaderVotes { .. } => {
+                    to_binary(&GetDearLeaderVotesResponse {
+                        votes: vec![DearLeaderVote {
+                            proposal_id: 1,
+                            vote_option: 1,
+                            rationale: None,
+                        }],
+                    })
+                }
+                _ => unimplemented!(),
+            };
+            SystemResult::Ok(response.into())
+        });
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            InstantiateMsg::InstatiateDearLeaderAccount {
+                owner_addr: OWNER.to_string(),
+                assembly_addr: ASSEMBLY.to_string(),
+                accounts_factory_addr: None,
+            },
+        )
+        .unwrap();
+
+        let config: GetConfigResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap())
+                .unwrap();
+        assert_eq!(config.owner, OWNER);
+        assert_eq!(config.assembly_addr, ASSEMBLY);
+
+        // the page is forwarded to the assembly
+        let delegators: GetDelegatorsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetDelegators {
+                    start_after: Some("account_a".to_string()),
+                    limit: Some(1),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(delegators.delegators.len(), 1);
+        assert_eq!(delegators.delegators[0].user_account, "account_b");
+        assert_eq!(delegators.delegators[0].stake, Uint128::new(20));
+
+        let power: GetVotingPowerResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetVotingPower {}).unwrap())
+                .unwrap();
+        assert_eq!(power.power, Uint128::new(40));
+
+        let votes: GetDearLeaderVotesResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetVotes {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(votes.votes[0].vote_option, 1);
+    }
+
+    #[test]
+    fn council_votes_need_threshold_approvals() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            InstantiateMsg::InstatiateDearLeaderAccount {
+                owner_addr: OWNER.to_string(),
+                assembly_addr: ASSEMBLY.to_string(),
+                accounts_factory_addr: None,
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::EnableCouncil {
+                members: vec!["alice".to_string(), "bob".to_string(), "carol".to_string()],
+                threshold: 2,
+            },
+        )
+        .unwrap();
+
+        // the owner can't vote alone anymore
+        let vote = CouncilAction::Vote {
+            proposal_id: 1,
+            vote_option: 1,
+            rationale: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::Vote {
+                proposal_id: 1,
+                vote_option: 1,
+                rationale: None,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::CouncilApprovalRequired {}));
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::ProposeCouncilAction {
+                action: vote.clone(),
+            },
+        )
+        .unwrap();
+        assert!(res.messages.is_empty());
+
+        let proposals: GetCouncilProposalsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetCouncilProposals {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(proposals.proposals[0].approvals, vec!["alice".to_string()]);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::ApproveCouncilAction { proposal_id: 0 },
+        )
+        .unwrap_err();
+
+        // the second approval sends the vote to the assembly
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            ExecuteMsg::ApproveCouncilAction { proposal_id: 0 },
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        // left open while the members change
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("carol", &[]),
+            ExecuteMsg::ProposeCouncilAction {
+                action: vote.clone(),
+            },
+        )
+        .unwrap();
+
+        // members are managed through the same flow
+        for (sender, msg) in [
+            (
+                "alice",
+                ExecuteMsg::ProposeCouncilAction {
+                    action: CouncilAction::UpdateMembers {
+                        to_add: vec![],
+                        to_remove: vec!["carol".to_string()],
+                        threshold: 1,
+                    },
+                },
+            ),
+            ("bob", ExecuteMsg::ApproveCouncilAction { proposal_id: 2 }),
+        ] {
+            execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).unwrap();
+        }
+        let council: GetCouncilResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetCouncil {}).unwrap())
+                .unwrap();
+        let council = council.council.unwrap();
+        assert_eq!(
+            council.members,
+            vec!["alice".to_string(), "bob".to_string()]
+        );
+        assert_eq!(council.threshold, 1);
+        assert_eq!(council.epoch, 1);
+
+        // the proposal of the removed member can't be approved by the new council
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            ExecuteMsg::ApproveCouncilAction { proposal_id: 1 },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::CouncilProposalStale {}));
+        let proposals: GetCouncilProposalsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetCouncilProposals {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(proposals.proposals.is_empty());
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            ExecuteMsg::ProposeCouncilAction { action: vote },
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        // staff granted by the council votes without the council, the owner still can't
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            ExecuteMsg::ProposeCouncilAction {
+                action: CouncilAction::GrantStaff {
+                    staffer: "dave".to_string(),
+                    role: StaffRole::Voter,
+                    categories: None,
+                    expires: None,
+                },
+            },
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("dave", &[]),
+            ExecuteMsg::Vote {
+                proposal_id: 1,
+                vote_option: 2,
+                rationale: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        // every vote on the proposal is kept with its signers
+        let signers: GetVoteSignersResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetVoteSigners { proposal_id: 1 },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            signers.votes,
+            vec![
+                SignedVote {
+                    vote_option: 1,
+                    signers: vec!["alice".to_string(), "bob".to_string()],
+                },
+                SignedVote {
+                    vote_option: 1,
+                    signers: vec!["bob".to_string()],
+                },
+                SignedVote {
+                    vote_option: 2,
+                    signers: vec!["dave".to_string()],
+                },
+            ]
+        );
+    }
+
+    const TEXT_PROPOSAL: &str = "/cosmos.gov.v1beta1.TextProposal";
+    const UPGRADE_PROPOSAL: &str = "/cosmos.upgrade.v1beta1.SoftwareUpgradeProposal";
+
+    #[test]
+    fn staff_votes_within_their_grant() {
+        let mut deps = mock_dependencies_with_gov();
+        let env = mock_env();
+        // odd proposals are text proposals, even ones are upgrades
+        for proposal_id in 1..=3 {
+            deps.querier.set_proposal(GovProposal {
+                proposal_id,
+                content_type: if proposal_id % 2 == 1 {
+                    TEXT_PROPOSAL
+                } else {
+                    UPGRADE_PROPOSAL
+                }
+                .to_string(),
+                title: format!("proposal {}", proposal_id),
+                status: ProposalStatus::VotingPeriod,
+                voting_end_time: env.block.time.plus_seconds(3600),
+            });
+        }
+
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(OWNER, &[]),
+            InstantiateMsg::InstatiateDearLeaderAccount {
+                owner_addr: OWNER.to_string(),
+                assembly_addr: ASSEMBLY.to_string(),
+                accounts_factory_addr: None,
+            },
+        )
+        .unwrap();
+
+        let vote = |proposal_id| ExecuteMsg::Vote {
+            proposal_id,
+            vote_option: 1,
+            rationale: None,
+        };
+        let grant = |staffer: &str, expires| ExecuteMsg::GrantStaff {
+            staffer: staffer.to_string(),
+            role: StaffRole::Voter,
+            categories: Some(vec![TEXT_PROPOSAL.to_string()]),
+            expires,
+        };
+
+        // only the owner manages grants
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            grant("alice", None),
+        )
+        .unwrap_err();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(OWNER, &[]),
+            grant("alice", None),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(OWNER, &[]),
+            grant("bob", Some(env.block.time.plus_seconds(60))),
+        )
+        .unwrap();
+
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), vote(1)).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        let err =
+            execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), vote(2)).unwrap_err();
+        assert!(matches!(err, ContractError::CategoryNotGranted {}));
+        execute(deps.as_mut(), env.clone(), mock_info("carol", &[]), vote(1)).unwrap_err();
+
+        let signers: GetVoteSignersResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GetVoteSigners { proposal_id: 1 },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            signers.votes,
+            vec![SignedVote {
+                vote_option: 1,
+                signers: vec!["alice".to_string()],
+            }]
+        );
+
+        // expired grants can't vote and are not listed anymore
+        let mut later = env.clone();
+        later.block.time = env.block.time.plus_seconds(60);
+        let err =
+            execute(deps.as_mut(), later.clone(), mock_info("bob", &[]), vote(3)).unwrap_err();
+        assert!(matches!(err, ContractError::StaffGrantExpired {}));
+
+        let grants: GetStaffGrantsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                later.clone(),
+                QueryMsg::GetStaffGrants {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(grants.grants.len(), 1);
+        assert_eq!(grants.grants[0].staffer, "alice");
+
+        execute(
+            deps.as_mut(),
+            later.clone(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::RevokeStaff {
+                staffer: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        let err = execute(deps.as_mut(), later, mock_info("alice", &[]), vote(3)).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+    }
+
+    #[test]
+    fn ownership_transfer_is_two_step() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            InstantiateMsg::InstatiateDearLeaderAccount {
+                owner_addr: OWNER.to_string(),
+                assembly_addr: ASSEMBLY.to_string(),
+                accounts_factory_addr: Some("factory".to_string()),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::GrantStaff {
+                staffer: "staffer".to_string(),
+                role: StaffRole::Voter,
+                categories: None,
+                expires: None,
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::TransferOwnership {
+                new_owner: "successor".to_string(),
+            },
+        )
+        .unwrap();
+
+        // the owner stays in charge until the successor accepts
+        let config: GetConfigResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap())
+                .unwrap();
+        assert_eq!(config.owner, OWNER);
+        assert_eq!(config.pending_owner, Some("successor".to_string()));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("someone", &[]),
+            ExecuteMsg::AcceptOwnership {},
+        )
+        .unwrap_err();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("successor", &[]),
+            ExecuteMsg::AcceptOwnership {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "factory".to_string(),
+                msg: to_binary(&ExecuteMsgCommon::UpdateDearLeaderAccountOwner {
+                    previous_owner: OWNER.to_string(),
+                    new_owner: "successor".to_string(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        let config: GetConfigResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap())
+                .unwrap();
+        assert_eq!(config.owner, "successor");
+        assert_eq!(config.pending_owner, None);
+
+        // grants of the previous owner are gone, and so is its access
+        let grants: GetStaffGrantsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetStaffGrants {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(grants.grants.is_empty());
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::Retire {},
+        )
+        .unwrap_err();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("successor", &[]),
+            ExecuteMsg::Retire {},
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+    }
+}
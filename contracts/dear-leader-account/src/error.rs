@@ -9,6 +9,9 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Instantiate msg is not for a dear leader account")]
+    InvalidInstantiateMsg {},
+
     #[error("Wrong token sent")]
     WrongToken {},
 
@@ -26,6 +29,39 @@ pub enum ContractError {
 
     #[error("Vote option is invalid")]
     InvalidVote {},
+
+    #[error("This account is run by a council, propose the action instead")]
+    CouncilApprovalRequired {},
+
+    #[error("Council mode is already enabled")]
+    CouncilAlreadyEnabled {},
+
+    #[error("This account is not run by a council")]
+    NoCouncil {},
+
+    #[error("Threshold must be between 1 and the number of members")]
+    InvalidThreshold {},
+
+    #[error("Council proposal not found")]
+    CouncilProposalNotFound {},
+
+    #[error("Council members changed since this proposal was made")]
+    CouncilProposalStale {},
+
+    #[error("Member already approved this proposal")]
+    AlreadyApproved {},
+
+    #[error("Staff grant not found")]
+    StaffGrantNotFound {},
+
+    #[error("Staff grant expired")]
+    StaffGrantExpired {},
+
+    #[error("Staff grant doesn't cover this proposal category")]
+    CategoryNotGranted {},
+
+    #[error("No ownership transfer pending")]
+    NoPendingOwner {},
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
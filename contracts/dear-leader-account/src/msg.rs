@@ -1,13 +1,110 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Timestamp, Uint128};
+use util_types::{DearLeaderProfile, VoteRationale};
+
+use crate::state::{Council, CouncilProposal, SignedVote, StaffGrant};
+
+pub use util_types::InstantiateMsg;
 
 #[cw_serde]
-pub struct InstantiateMsg {
-    pub assembly_addr: String,
+pub enum ExecuteMsg {
+    Vote {
+        proposal_id: u64,
+        vote_option: u64,
+        rationale: Option<VoteRationale>,
+    },
+    AnnounceVote {
+        proposal_id: u64,
+        vote_option: u64,
+        rationale: Option<VoteRationale>,
+    },
+    SetProfile {
+        profile: DearLeaderProfile,
+    },
+    EnableCouncil {
+        members: Vec<String>,
+        threshold: u64,
+    },
+    ProposeCouncilAction {
+        action: CouncilAction,
+    },
+    ApproveCouncilAction {
+        proposal_id: u64,
+    },
+    GrantStaff {
+        staffer: String,
+        role: StaffRole,
+        categories: Option<Vec<String>>,
+        expires: Option<Timestamp>,
+    },
+    RevokeStaff {
+        staffer: String,
+    },
+    // the new owner takes over with AcceptOwnership
+    TransferOwnership {
+        new_owner: String,
+    },
+    AcceptOwnership {},
+    // leaves the assembly, delegators get their vote power back
+    Retire {},
+    // defers the votes on the categories to another dear leader, every category if None
+    FollowDearLeader {
+        dear_leader_addr: String,
+        categories: Option<Vec<String>>,
+    },
+    UnfollowDearLeader {
+        dear_leader_addr: String,
+    },
 }
 
+// what a staffer can do on behalf of the owner
 #[cw_serde]
-pub enum ExecuteMsg {
-    Vote { proposal_id: u64, vote_option: u64 },
+pub enum StaffRole {
+    // cast votes, categories are gov proposal content types like /cosmos.gov.v1beta1.TextProposal
+    Voter,
+}
+
+// actions that need the council approval when the account is run by a council
+#[cw_serde]
+pub enum CouncilAction {
+    Vote {
+        proposal_id: u64,
+        vote_option: u64,
+        rationale: Option<VoteRationale>,
+    },
+    AnnounceVote {
+        proposal_id: u64,
+        vote_option: u64,
+        rationale: Option<VoteRationale>,
+    },
+    SetProfile {
+        profile: DearLeaderProfile,
+    },
+    UpdateMembers {
+        to_add: Vec<String>,
+        to_remove: Vec<String>,
+        threshold: u64,
+    },
+    GrantStaff {
+        staffer: String,
+        role: StaffRole,
+        categories: Option<Vec<String>>,
+        expires: Option<Timestamp>,
+    },
+    RevokeStaff {
+        staffer: String,
+    },
+    TransferOwnership {
+        new_owner: String,
+    },
+    Retire {},
+    FollowDearLeader {
+        dear_leader_addr: String,
+        categories: Option<Vec<String>>,
+    },
+    UnfollowDearLeader {
+        dear_leader_addr: String,
+    },
 }
 
 #[cw_serde]
@@ -15,9 +112,97 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     #[returns(GetOwnerAddrResponse)]
     GetOwnerAddr {},
+
+    // returns the owner and the assembly the account votes through
+    #[returns(GetConfigResponse)]
+    GetConfig {},
+
+    // returns the user accounts delegated to this dear leader and their stake
+    #[returns(GetDelegatorsResponse)]
+    GetDelegators {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    // returns the stake of every user account delegated to this dear leader
+    #[returns(GetVotingPowerResponse)]
+    GetVotingPower {},
+
+    // returns the council members and threshold, if the account is run by a council
+    #[returns(GetCouncilResponse)]
+    GetCouncil {},
+
+    // returns the council actions of the current members waiting for approvals
+    #[returns(GetCouncilProposalsResponse)]
+    GetCouncilProposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    // returns the staff grants that did not expire
+    #[returns(GetStaffGrantsResponse)]
+    GetStaffGrants {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    // returns every vote of this dear leader on the proposal and who signed it, oldest first
+    #[returns(GetVoteSignersResponse)]
+    GetVoteSigners { proposal_id: u64 },
+
+    // returns the proposals this dear leader voted on and the option used
+    #[returns(util_types::GetDearLeaderVotesResponse)]
+    GetVotes {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
 pub struct GetOwnerAddrResponse {
     pub owner_addr: String,
 }
+
+#[cw_serde]
+pub struct GetConfigResponse {
+    pub owner: String,
+    pub pending_owner: Option<String>,
+    pub assembly_addr: String,
+    pub accounts_factory_addr: Option<String>,
+}
+
+#[cw_serde]
+pub struct GetDelegatorsResponse {
+    pub delegators: Vec<Delegator>,
+}
+
+#[cw_serde]
+pub struct Delegator {
+    pub user_account: String,
+    pub stake: Uint128,
+}
+
+#[cw_serde]
+pub struct GetVotingPowerResponse {
+    pub power: Uint128,
+}
+
+#[cw_serde]
+pub struct GetStaffGrantsResponse {
+    pub grants: Vec<StaffGrant>,
+}
+
+#[cw_serde]
+pub struct GetVoteSignersResponse {
+    pub votes: Vec<SignedVote>,
+}
+
+#[cw_serde]
+pub struct GetCouncilResponse {
+    pub council: Option<Council>,
+}
+
+#[cw_serde]
+pub struct GetCouncilProposalsResponse {
+    pub proposals: Vec<CouncilProposal>,
+}
@@ -1,12 +1,67 @@
 use cosmwasm_schema::cw_serde;
-use cw_storage_plus::Item;
+use cosmwasm_std::Timestamp;
+use cw_storage_plus::{Item, Map};
 
-// pub const ACCOUNTS_FACTORY_ADDR: Item<String> = Item::new("accounts_factory");
+use crate::msg::{CouncilAction, StaffRole};
+
+pub const ACCOUNTS_FACTORY_ADDR: Item<String> = Item::new("accounts_factory");
 pub const ASSEMBLY_ADDR: Item<String> = Item::new("assembly");
 pub const CONFIG: Item<Config> = Item::new("config");
 
+// owner the account is being transferred to, until it accepts.
+pub const PENDING_OWNER: Item<String> = Item::new("pending_owner");
+
+// members that run the account instead of the owner, once council mode is enabled.
+pub const COUNCIL: Item<Council> = Item::new("council");
+
+// council actions waiting for enough approvals.
+pub const COUNCIL_PROPOSALS: Map<u64, CouncilProposal> = Map::new("council_proposals");
+pub const NEXT_COUNCIL_PROPOSAL_ID: Item<u64> = Item::new("next_council_proposal_id");
+
+// assistants allowed to act on behalf of the owner, keyed by address.
+pub const STAFF_GRANTS: Map<String, StaffGrant> = Map::new("staff_grants");
+
+// every vote cast on each proposal and who signed it: the owner, a staffer or the approving
+// council members. Votes can be changed, the last one is the one the assembly counts.
+pub const VOTE_SIGNERS: Map<u64, Vec<SignedVote>> = Map::new("vote_signers");
+
 #[cw_serde]
 pub struct Config {
     /// The name of the contract.
     pub owner: String,
 }
+
+#[cw_serde]
+pub struct Council {
+    pub members: Vec<String>,
+    /// approvals needed to run an action
+    pub threshold: u64,
+    /// bumped on every membership change, proposals from an older epoch can't run anymore
+    pub epoch: u64,
+}
+
+#[cw_serde]
+pub struct CouncilProposal {
+    pub id: u64,
+    pub action: CouncilAction,
+    pub proposer: String,
+    pub approvals: Vec<String>,
+    /// council epoch the proposal was made in
+    pub epoch: u64,
+}
+
+#[cw_serde]
+pub struct SignedVote {
+    pub vote_option: u64,
+    pub signers: Vec<String>,
+}
+
+#[cw_serde]
+pub struct StaffGrant {
+    pub staffer: String,
+    pub role: StaffRole,
+    /// proposal content types the staffer can act on, all of them if not set
+    pub categories: Option<Vec<String>>,
+    /// the grant stops working at this time, if set
+    pub expires: Option<Timestamp>,
+}
@@ -7,22 +7,61 @@ use cw2::set_contract_version;
 
 use crate::error::ContractError;
 use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::state::{ASSEMBLY_ADDR, BOSS_ADDR};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:user_acount";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const UNDELEGATION_REPLY_ID: u64 = 0;
+const REWARDS_REPLY_ID: u64 = 1;
+
+// page size of the cranks that walk a map
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+// messages that would let the account vote without going through the assembly
+const VOTE_TYPE_URLS: [&str; 4] = [
+    "/cosmos.gov.v1beta1.MsgVote",
+    "/cosmos.gov.v1beta1.MsgVoteWeighted",
+    "/cosmos.gov.v1.MsgVote",
+    "/cosmos.gov.v1.MsgVoteWeighted",
+];
+const AUTHZ_GRANT_TYPE_URL: &str = "/cosmos.authz.v1beta1.MsgGrant";
+const GENERIC_AUTHORIZATION_TYPE_URL: &str = "/cosmos.authz.v1beta1.GenericAuthorization";
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
-    _info: MessageInfo,
-    _msg: InstantiateMsg,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    unimplemented!()
+
+    let (boss_addr, assembly_addr, vesting) = match msg {
+        InstantiateMsg::InstantiateUserAccountMsg {
+            boss_addr,
+            assembly_addr,
+            vesting,
+        } => (boss_addr, assembly_addr, vesting),
+        _ => return Err(ContractError::InvalidInstantiateMsg {}),
+    };
+
+    // save boss and assembly addrs
+    let boss_addr = deps.api.addr_validate(&boss_addr)?;
+    let assembly_addr = deps.api.addr_validate(&assembly_addr)?;
+    BOSS_ADDR.save(deps.storage, &boss_addr.to_string())?;
+    ASSEMBLY_ADDR.save(deps.storage, &assembly_addr.to_string())?;
+
+    let response = Response::new()
+        .add_attribute("action", "instantiate_user_account")
+        .add_attribute("boss", boss_addr.to_string());
+
+    match vesting {
+        Some(vesting) => execute::start_vesting(deps, env, info, vesting, response),
+        None => Ok(response),
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -31,6 +70,20 @@ pub fn execute(
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    // joint accounts only run these through ExecuteJointAction
+    if execute::requires_joint_approval(deps.as_ref(), &msg)? {
+        return Err(ContractError::JointApprovalRequired {});
+    }
+
+    dispatch(deps, env, info, msg)
+}
+
+fn dispatch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Delegate { validator_addr } => {
@@ -65,18 +118,113 @@ pub fn execute(
             proposal_id,
             vote_option,
         } => execute::vote(deps, env, info, proposal_id, vote_option),
-        ExecuteMsg::Withdraw { amount } => execute::withdraw(deps, info, amount),
+        ExecuteMsg::Withdraw {
+            token,
+            amount,
+            recipient,
+        } => execute::withdraw(deps, env, info, token, amount, recipient),
+        ExecuteMsg::Deposit {} => execute::deposit(deps, info),
+        ExecuteMsg::Receive(cw20_msg) => execute::receive(deps, info, cw20_msg),
+        ExecuteMsg::UpdateAllowedTokens { to_add, to_remove } => {
+            execute::update_allowed_tokens(deps, info, to_add, to_remove)
+        }
+        ExecuteMsg::Execute { msgs } => execute::execute_msgs(deps, env, info, msgs),
+        ExecuteMsg::SweepMatured { start_after, limit } => {
+            execute::sweep_matured(deps, env, start_after, limit)
+        }
+        ExecuteMsg::SetSweepDestination { destination } => {
+            execute::set_sweep_destination(deps, env, info, destination)
+        }
+        ExecuteMsg::SetRewardWithdrawAddr { addr } => {
+            execute::set_reward_withdraw_addr(deps, env, info, addr)
+        }
+        ExecuteMsg::SetRewardRoutes { routes } => {
+            execute::set_reward_routes(deps, env, info, routes)
+        }
+        ExecuteMsg::ApplyPayoutChanges {} => execute::apply_payout_changes(deps, env),
+        ExecuteMsg::CancelPayoutChanges {} => execute::cancel_payout_changes(deps, env, info),
+        ExecuteMsg::SetDelegationSchedule {
+            amount,
+            validators,
+            interval,
+            tip,
+        } => execute::set_delegation_schedule(deps, env, info, amount, validators, interval, tip),
+        ExecuteMsg::PauseDelegationSchedule {} => {
+            execute::set_delegation_schedule_paused(deps, env, info, true)
+        }
+        ExecuteMsg::ResumeDelegationSchedule {} => {
+            execute::set_delegation_schedule_paused(deps, env, info, false)
+        }
+        ExecuteMsg::CancelDelegationSchedule {} => execute::cancel_delegation_schedule(deps, info),
+        ExecuteMsg::FundTipPool {} => execute::fund_tip_pool(deps, info),
+        ExecuteMsg::ExecuteSchedule {} => execute::execute_schedule(deps, env, info),
+        ExecuteMsg::EnableJointOwnership {
+            owners,
+            threshold,
+            proposal_duration,
+        } => execute::enable_joint_ownership(deps, info, owners, threshold, proposal_duration),
+        ExecuteMsg::ProposeJointAction { action } => {
+            execute::propose_joint_action(deps, env, info, action)
+        }
+        ExecuteMsg::ApproveJointAction { proposal_id } => {
+            execute::approve_joint_action(deps, env, info, proposal_id)
+        }
+        ExecuteMsg::ExecuteJointAction { proposal_id } => {
+            execute::execute_joint_action(deps, env, info, proposal_id)
+        }
+        ExecuteMsg::SetWithdrawalPolicy {
+            caps,
+            period,
+            delay,
+            guardians,
+        } => execute::set_withdrawal_policy(deps, env, info, caps, period, delay, guardians),
+        ExecuteMsg::RemoveWithdrawalPolicy {} => execute::remove_withdrawal_policy(deps, env, info),
+        ExecuteMsg::CancelWithdrawalPolicyChange {} => {
+            execute::cancel_withdrawal_policy_change(deps, env, info)
+        }
+        ExecuteMsg::CancelWithdrawal { withdrawal_id } => {
+            execute::cancel_withdrawal(deps, env, info, withdrawal_id)
+        }
+        ExecuteMsg::ReleaseWithdrawal { withdrawal_id } => {
+            execute::release_withdrawal(deps, env, withdrawal_id)
+        }
+        ExecuteMsg::Clawback {} => execute::clawback(deps, env, info),
+        ExecuteMsg::SyncStake {} => execute::sync_stake(deps, env),
+        ExecuteMsg::OptOutOfLeaderVote { proposal_id } => {
+            execute::opt_out_of_leader_vote(deps, info, proposal_id)
+        }
+        ExecuteMsg::SetVoteFallback { fallback } => {
+            execute::set_vote_fallback(deps, info, fallback)
+        }
     }
 }
 
 pub mod execute {
     use cosmwasm_std::{
-        BankMsg, Coin, DistributionMsg, GovMsg, StakingMsg, SubMsg, Uint128, VoteOption, WasmMsg,
+        BankMsg, Coin, CosmosMsg, Decimal, Delegation, DistributionMsg, GovMsg, Order, StakingMsg,
+        Storage, SubMsg, Timestamp, Uint128, Validator, VoteOption, WasmMsg,
     };
+    use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+    use cw_storage_plus::Bound;
     use cw_utils::must_pay;
-    use util_types::ExecuteMsg as CommonExecuteMsg;
+    use util_types::{
+        proto::{bytes_field, query_unbonding_time},
+        ExecuteMsg as CommonExecuteMsg, GetProxyAllowListResponse, GetValidatorPolicyResponse,
+        QueryMsg as CommonQueryMsg, StakeAmount, VestingParams, VoteFallback,
+    };
 
-    use crate::state::{ASSEMBLY_ADDR, BOSS_ADDR};
+    use crate::helpers::{load_withdrawal_policy, query_token_balance};
+    use crate::msg::{JointAction, TokenInfo, WithdrawalCap};
+    use crate::state::{
+        CheckedTokenInfo, DelegationSchedule, JointOwnership, JointProposal, LedgerAction,
+        LedgerEntry, PayoutChange, PendingPayoutChange, PendingWithdrawal, PendingWithdrawalPolicy,
+        RewardRoute, UnbondingEntry, ValidatorTotals, Vesting, WithdrawalPeriod, WithdrawalPolicy,
+        ALLOWED_TOKENS, ASSEMBLY_ADDR, BOSS_ADDR, DELEGATION_SCHEDULE, JOINT_OWNERSHIP,
+        JOINT_PROPOSALS, LEDGER, NEXT_JOINT_PROPOSAL_ID, NEXT_LEDGER_ID, NEXT_UNBONDING_ID,
+        NEXT_WITHDRAWAL_ID, PENDING_PAYOUT_CHANGES, PENDING_WITHDRAWALS, PENDING_WITHDRAWAL_POLICY,
+        REWARD_ROUTES, REWARD_WITHDRAW_ADDR, SWEEP_DESTINATION, TIP_POOL, UNBONDINGS,
+        VALIDATOR_TOTALS, VESTING, WITHDRAWAL_PERIODS, WITHDRAWAL_POLICY,
+    };
 
     use super::*;
 
@@ -94,17 +242,26 @@ pub mod execute {
         let sent_token =
             must_pay(&info, native_denom.as_str()).map_err(|_| ContractError::WrongToken {})?;
 
-        // validate validator_addr
-        let valid_val_addr = deps
-            .api
-            .addr_validate(&validator_addr)
-            .map_err(|_| ContractError::InvalidAddr {})?;
+        // validate validator exists and complies with the assembly validator policy
+        let validator = validate_validator(deps.as_ref(), &validator_addr)?;
 
-        // create the staking message
-        let msg = StakingMsg::Delegate {
-            validator: valid_val_addr.to_string(),
-            amount: info.funds[0].clone(),
-        };
+        record_ledger_entry(
+            deps.storage,
+            &env,
+            LedgerAction::Delegate {
+                validator: validator.address.clone(),
+                amount: info.funds[0].clone(),
+            },
+        )?;
+
+        // create the staking message, the reply records rewards paid out by the delegation change
+        let msg = SubMsg::reply_on_success(
+            StakingMsg::Delegate {
+                validator: validator.address,
+                amount: info.funds[0].clone(),
+            },
+            REWARDS_REPLY_ID,
+        );
 
         // check if it is first delegation
         let is_first_delegation = deps
@@ -125,7 +282,7 @@ pub mod execute {
                 .add_attribute("boss", info.sender.to_string())
                 .add_attribute("amount", sent_token.to_string())
                 .add_attribute("to", validator_addr)
-                .add_message(msg)
+                .add_submessage(msg)
                 .add_message(register_msg))
         } else {
             Ok(Response::new()
@@ -133,7 +290,8 @@ pub mod execute {
                 .add_attribute("boss", info.sender.to_string())
                 .add_attribute("amount", sent_token.to_string())
                 .add_attribute("to", validator_addr)
-                .add_message(msg))
+                .add_submessage(msg)
+                .add_message(sync_stake_msg(deps.storage)?))
         }
     }
 
@@ -141,34 +299,59 @@ pub mod execute {
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
-        amount: Uint128,
+        amount: StakeAmount,
         validator_addr: String,
     ) -> Result<Response, ContractError> {
         // confirm boss is calling
         validate_boss(deps.as_ref(), &info)?;
-        // validate validator_addr
-        let valid_val_addr = deps
-            .api
-            .addr_validate(&validator_addr)
-            .map_err(|_| ContractError::InvalidAddr {})?;
-
         // confirm existing delegation and amount sent is within the bounds
         let delegation = deps
             .querier
-            .query_delegation(env.contract.address, valid_val_addr)?
+            .query_delegation(env.contract.address.clone(), &validator_addr)?
             .ok_or(ContractError::NoDelegation {})?;
 
         // validate amount requested to undelegate is within the bounds
-        if amount >= delegation.amount.amount {
-            return Err(ContractError::UndelegateAmountTooHigh {});
+        let amount = match amount {
+            StakeAmount::Exact(amount) if amount.is_zero() => {
+                return Err(ContractError::ZeroAmount {})
+            }
+            StakeAmount::Exact(amount) if amount > delegation.amount.amount => {
+                return Err(ContractError::UndelegateAmountTooHigh {})
+            }
+            StakeAmount::Exact(amount) => amount,
+            StakeAmount::Max {} => delegation.amount.amount,
+        };
+
+        // unvested funds must stay staked
+        if let Some(vesting) = VESTING.may_load(deps.storage)? {
+            let staked = staked_amount(deps.as_ref(), &env)?;
+            if amount > staked.saturating_sub(vesting.unvested(env.block.time)) {
+                return Err(ContractError::VestingLocked {});
+            }
         }
+
+        let amount = Coin {
+            denom: delegation.amount.denom,
+            amount,
+        };
+
+        // keep track of when the funds come back
+        let completion_time = env
+            .block
+            .time
+            .plus_seconds(query_unbonding_time(&deps.querier)?);
+        record_unbonding(
+            deps.storage,
+            &env,
+            &delegation.validator,
+            &amount,
+            completion_time,
+        )?;
+
         // create undelegate message
         let msg = StakingMsg::Undelegate {
             validator: delegation.validator,
-            amount: Coin {
-                denom: delegation.amount.denom,
-                amount: delegation.amount.amount,
-            },
+            amount: amount.clone(),
         };
 
         // add reply on sucess, so we can check if there is no delegation left, to unregister vote power in the assembly
@@ -179,6 +362,7 @@ pub mod execute {
             .add_attribute("boss", info.sender.to_string())
             .add_attribute("amount", amount.to_string())
             .add_attribute("from_validator", validator_addr)
+            .add_attribute("completion_time", completion_time.to_string())
             .add_submessage(sub_msg))
     }
 
@@ -190,55 +374,121 @@ pub mod execute {
         // confirm boss is calling
         validate_boss(deps.as_ref(), &info)?;
         // get all delegations
-        let delegations = deps.querier.query_all_delegations(env.contract.address)?;
+        let delegations = deps
+            .querier
+            .query_all_delegations(env.contract.address.clone())?;
 
         if delegations.is_empty() {
             return Err(ContractError::NoDelegation {});
         }
-        // create undelegate message for each delegation
-        let msgs = delegations
+
+        // unvested funds stay staked, only the rest is undelegated
+        let staked: Uint128 = delegations
             .iter()
-            .map(|delegation| StakingMsg::Undelegate {
-                validator: delegation.validator.clone(),
-                amount: Coin {
-                    denom: delegation.amount.denom.clone(),
-                    amount: delegation.amount.amount,
-                },
-            })
-            .collect::<Vec<StakingMsg>>();
+            .map(|delegation| delegation.amount.amount)
+            .sum();
+        let unvested = VESTING
+            .may_load(deps.storage)?
+            .map(|vesting| vesting.unvested(env.block.time))
+            .unwrap_or_default();
+        if staked <= unvested {
+            return Err(ContractError::VestingLocked {});
+        }
 
-        // create message to unregister vote power in the assembly
-        let msg = WasmMsg::Execute {
-            contract_addr: ASSEMBLY_ADDR.load(deps.storage)?,
-            msg: to_binary(&CommonExecuteMsg::UnregisterUserAccount {})?,
-            funds: vec![],
-        };
+        // keep track of when the funds come back
+        let completion_time = env
+            .block
+            .time
+            .plus_seconds(query_unbonding_time(&deps.querier)?);
 
-        Ok(Response::new()
+        // create undelegate message for each delegation
+        let msgs = undelegate_in_order(
+            deps.storage,
+            &env,
+            &delegations,
+            staked - unvested,
+            completion_time,
+        )?;
+
+        // the replies record the rewards paid out by the undelegations, and unregister the vote
+        // power or sync the stake kept staked once the last one ran
+        let response = Response::new()
             .add_attribute("action", "undelegate")
             .add_attribute("boss", info.sender.to_string())
-            .add_messages(msgs)
-            .add_message(msg))
-        // .add_submessage(submessage))
+            .add_attribute("completion_time", completion_time.to_string())
+            .add_submessages(
+                msgs.into_iter()
+                    .map(|msg| SubMsg::reply_on_success(msg, UNDELEGATION_REPLY_ID)),
+            );
+
+        if !unvested.is_zero() {
+            return Ok(response.add_attribute("kept_staked", unvested.to_string()));
+        }
+
+        Ok(response)
     }
 
     pub fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
         // confirm boss is calling
         validate_boss(deps.as_ref(), &info)?;
         // get all delegations
-        let delegations = deps.querier.query_all_delegations(env.contract.address)?;
-        // create claim rewards messages for each delegation
+        let delegations = deps
+            .querier
+            .query_all_delegations(env.contract.address.clone())?;
+        // create claim rewards messages for each delegation, the replies record the rewards paid
         let msgs = delegations
             .iter()
-            .map(|delegation| DistributionMsg::WithdrawDelegatorReward {
-                validator: delegation.validator.clone(),
+            .map(|delegation| {
+                SubMsg::reply_on_success(
+                    DistributionMsg::WithdrawDelegatorReward {
+                        validator: delegation.validator.clone(),
+                    },
+                    REWARDS_REPLY_ID,
+                )
             })
-            .collect::<Vec<DistributionMsg>>();
+            .collect::<Vec<SubMsg>>();
+
+        // forward the configured shares of the claimed rewards, which land here before the sends run
+        let routes = REWARD_ROUTES.may_load(deps.storage)?.unwrap_or_default();
+        let mut route_msgs = vec![];
+        if !routes.is_empty() {
+            let denom = deps.querier.query_bonded_denom()?;
+            let mut rewards = Uint128::zero();
+            for delegation in delegations.iter() {
+                if let Some(full_delegation) = deps
+                    .querier
+                    .query_delegation(env.contract.address.clone(), &delegation.validator)?
+                {
+                    rewards += full_delegation
+                        .accumulated_rewards
+                        .iter()
+                        .filter(|reward| reward.denom == denom)
+                        .map(|reward| reward.amount)
+                        .sum::<Uint128>();
+                }
+            }
+
+            route_msgs = routes
+                .into_iter()
+                .map(|route| (route.addr, rewards * route.share))
+                .filter(|(_, amount)| !amount.is_zero())
+                .map(|(addr, amount)| BankMsg::Send {
+                    to_address: addr,
+                    amount: vec![Coin {
+                        denom: denom.clone(),
+                        amount,
+                    }],
+                })
+                .collect::<Vec<BankMsg>>();
+        }
+
         // return response
         Ok(Response::new()
             .add_attribute("action", "claim_rewards")
             .add_attribute("boss", info.sender.to_string())
-            .add_messages(msgs))
+            .add_attribute("routed", route_msgs.len().to_string())
+            .add_submessages(msgs)
+            .add_messages(route_msgs))
     }
 
     pub fn redelegate(
@@ -247,49 +497,66 @@ pub mod execute {
         info: MessageInfo,
         from_validator_addr: String,
         to_validator_addr: String,
-        amount: Uint128,
+        amount: StakeAmount,
     ) -> Result<Response, ContractError> {
         // confirm boss is calling
         validate_boss(deps.as_ref(), &info)?;
-        // validate from_validator_addr
-        let valid_from_val_addr = deps
-            .api
-            .addr_validate(&from_validator_addr)
-            .map_err(|_| ContractError::InvalidAddr {})?;
+        if from_validator_addr == to_validator_addr {
+            return Err(ContractError::SameValidator {});
+        }
+        // validate to_validator_addr exists and complies with the assembly validator policy
+        let to_validator = validate_validator(deps.as_ref(), &to_validator_addr)?;
 
-        // validate to_validator_addr
-        let valid_to_val_addr = deps
-            .api
-            .addr_validate(&to_validator_addr)
-            .map_err(|_| ContractError::InvalidAddr {})?;
         // validate amount is delegated to from_validator_addr and is valid
-
         let delegation = deps
             .querier
-            .query_delegation(env.contract.address, valid_from_val_addr.clone())?
+            .query_delegation(env.contract.address.clone(), &from_validator_addr)?
             .ok_or(ContractError::NoDelegation {})?;
 
-        // validate amount requested to undelegate is within the bounds
-        if amount >= delegation.amount.amount {
-            return Err(ContractError::UndelegateAmountTooHigh {});
-        }
+        // validate amount requested to redelegate is within the bounds.
+        // Stake that was redelegated recently can't be redelegated again until it matures
+        let amount = match amount {
+            StakeAmount::Exact(amount) if amount.is_zero() => {
+                return Err(ContractError::ZeroAmount {})
+            }
+            StakeAmount::Exact(amount) if amount > delegation.can_redelegate.amount => {
+                return Err(ContractError::RedelegateAmountTooHigh {})
+            }
+            StakeAmount::Exact(amount) => amount,
+            StakeAmount::Max {} if delegation.can_redelegate.amount.is_zero() => {
+                return Err(ContractError::RedelegateAmountTooHigh {})
+            }
+            StakeAmount::Max {} => delegation.can_redelegate.amount,
+        };
 
-        // create restake message
-        let msg = StakingMsg::Redelegate {
-            src_validator: valid_from_val_addr.to_string(),
-            dst_validator: valid_to_val_addr.to_string(),
-            amount: Coin {
-                denom: delegation.amount.denom,
-                amount: delegation.amount.amount,
+        let amount = Coin {
+            denom: delegation.amount.denom,
+            amount,
+        };
+        record_ledger_entry(
+            deps.storage,
+            &env,
+            LedgerAction::Redelegate {
+                from_validator: delegation.validator.clone(),
+                to_validator: to_validator.address.clone(),
+                amount: amount.clone(),
             },
+        )?;
+
+        // create restake message, the reply records rewards paid out by the delegation change
+        let msg = StakingMsg::Redelegate {
+            src_validator: delegation.validator,
+            dst_validator: to_validator.address,
+            amount: amount.clone(),
         };
 
         Ok(Response::new()
             .add_attribute("action", "redelegate")
             .add_attribute("from_validator", from_validator_addr)
             .add_attribute("to_validator", to_validator_addr)
-            .add_attribute("amount", amount.to_string())
-            .add_message(msg))
+            .add_attribute("amount", amount.amount.to_string())
+            .add_submessage(SubMsg::reply_on_success(msg, REWARDS_REPLY_ID))
+            .add_message(sync_stake_msg(deps.storage)?))
     }
 
     pub fn transfer_vote_power(
@@ -363,10 +630,10 @@ pub mod execute {
         // confirm boss is calling
         validate_boss(deps.as_ref(), &info)?;
 
-        // create vote message to the Assembly
+        // create vote message to the Assembly, a direct vote overrides the dear leader one
         let msg = WasmMsg::Execute {
             contract_addr: ASSEMBLY_ADDR.load(deps.storage)?,
-            msg: to_binary(&CommonExecuteMsg::Vote {
+            msg: to_binary(&CommonExecuteMsg::UserAccountVote {
                 proposal_id,
                 vote_option: vote,
             })?,
@@ -378,91 +645,3766 @@ pub mod execute {
             .add_message(msg))
     }
 
+    pub fn opt_out_of_leader_vote(
+        deps: DepsMut,
+        info: MessageInfo,
+        proposal_id: u64,
+    ) -> Result<Response, ContractError> {
+        // confirm boss is calling
+        validate_boss(deps.as_ref(), &info)?;
+
+        // the dear leader vote on this proposal, announced or not, skips this account
+        let msg = WasmMsg::Execute {
+            contract_addr: ASSEMBLY_ADDR.load(deps.storage)?,
+            msg: to_binary(&CommonExecuteMsg::OptOutOfLeaderVote { proposal_id })?,
+            funds: vec![],
+        };
+
+        Ok(Response::new()
+            .add_attribute("action", "opt_out_of_leader_vote")
+            .add_attribute("proposal_id", proposal_id.to_string())
+            .add_message(msg))
+    }
+
+    pub fn set_vote_fallback(
+        deps: DepsMut,
+        info: MessageInfo,
+        fallback: VoteFallback,
+    ) -> Result<Response, ContractError> {
+        // confirm boss is calling
+        validate_boss(deps.as_ref(), &info)?;
+
+        // used by the assembly when the dear leader doesn't vote
+        let msg = WasmMsg::Execute {
+            contract_addr: ASSEMBLY_ADDR.load(deps.storage)?,
+            msg: to_binary(&CommonExecuteMsg::SetVoteFallback { fallback })?,
+            funds: vec![],
+        };
+
+        Ok(Response::new()
+            .add_attribute("action", "set_vote_fallback")
+            .add_message(msg))
+    }
+
     pub fn withdraw(
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
+        token: TokenInfo,
         amount: Uint128,
+        recipient: Option<String>,
     ) -> Result<Response, ContractError> {
         // confirm boss is calling
         validate_boss(deps.as_ref(), &info)?;
 
-        // create withdraw message
-        let msg = BankMsg::Send {
-            to_address: BOSS_ADDR.load(deps.storage)?,
-            amount: vec![Coin {
-                denom: deps.querier.query_bonded_denom()?,
-                amount,
-            }],
+        if amount.is_zero() {
+            return Err(ContractError::ZeroAmount {});
+        }
+
+        // the bonded denom can always be withdrawn, any other token must be allowed
+        let token = check_token(deps.as_ref(), token)?;
+        let tracked_token = ALLOWED_TOKENS.may_load(deps.storage, token.key())?;
+        if tracked_token.is_none() && token.key() != deps.querier.query_bonded_denom()? {
+            return Err(ContractError::TokenNotAllowed {});
+        }
+
+        // withdraw against the real balance, so tokens that arrived without a deposit
+        // (airdrops, IBC transfers) can be withdrawn too
+        let balance = spendable_balance(deps.as_ref(), &env, &token)?;
+        if amount > balance {
+            return Err(ContractError::InsufficientFunds {});
+        }
+        check_vesting_lock(deps.as_ref(), &env, &token, amount)?;
+
+        let recipient = match recipient {
+            Some(recipient) => deps
+                .api
+                .addr_validate(&recipient)
+                .map_err(|_| ContractError::InvalidAddr {})?
+                .to_string(),
+            None => BOSS_ADDR.load(deps.storage)?,
         };
 
+        // withdrawals above what is left of the cap for this period wait in the queue
+        if let Some(policy) = update_withdrawal_policy(deps.storage, &env)? {
+            let token = token.clone().with_amount(amount);
+            if !spend_withdrawal_cap(deps.storage, &env, &policy, &token)? {
+                let id = NEXT_WITHDRAWAL_ID
+                    .may_load(deps.storage)?
+                    .unwrap_or_default();
+                let withdrawal = PendingWithdrawal {
+                    id,
+                    token,
+                    recipient,
+                    release_time: env.block.time.plus_seconds(policy.delay),
+                };
+                PENDING_WITHDRAWALS.save(deps.storage, id, &withdrawal)?;
+                NEXT_WITHDRAWAL_ID.save(deps.storage, &(id + 1))?;
+
+                return Ok(Response::new()
+                    .add_attribute("action", "queue_withdrawal")
+                    .add_attribute("withdrawal_id", id.to_string())
+                    .add_attribute("amount", amount.to_string())
+                    .add_attribute("recipient", withdrawal.recipient)
+                    .add_attribute("release_time", withdrawal.release_time.to_string()));
+            }
+        }
+
+        let msg = withdraw_msg(deps.storage, &env, token.with_amount(amount), &recipient)?;
+
         Ok(Response::new()
             .add_attribute("action", "withdraw")
             .add_attribute("amount", amount.to_string())
+            .add_attribute("recipient", recipient)
             .add_message(msg))
     }
 
-    fn validate_boss(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
-        let boss = BOSS_ADDR.load(deps.storage)?;
-        if info.sender != boss {
-            return Err(ContractError::Unauthorized {});
+    pub fn set_withdrawal_policy(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        caps: Vec<WithdrawalCap>,
+        period: u64,
+        delay: u64,
+        guardians: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        // confirm boss is calling
+        validate_boss(deps.as_ref(), &info)?;
+
+        if period == 0 || delay == 0 {
+            return Err(ContractError::InvalidWithdrawalPolicy {});
         }
-        Ok(())
-    }
 
-    fn validate_assembly_call(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
-        let assembly_addr = ASSEMBLY_ADDR.load(deps.storage)?;
-        if info.sender != assembly_addr {
-            return Err(ContractError::Unauthorized {});
+        let mut checked_caps: Vec<CheckedTokenInfo> = vec![];
+        for cap in caps {
+            let token = check_token(deps.as_ref(), cap.token)?;
+            // the last cap of a token wins
+            checked_caps.retain(|checked_cap| checked_cap.key() != token.key());
+            checked_caps.push(token.with_amount(cap.amount));
         }
-        Ok(())
+
+        let guardians = guardians
+            .iter()
+            .map(|guardian| {
+                deps.api
+                    .addr_validate(guardian)
+                    .map(|guardian| guardian.to_string())
+                    .map_err(|_| ContractError::InvalidAddr {})
+            })
+            .collect::<Result<Vec<String>, ContractError>>()?;
+
+        let policy = WithdrawalPolicy {
+            caps: checked_caps,
+            period,
+            delay,
+            guardians,
+        };
+
+        change_withdrawal_policy(deps, env, Some(policy), "set_withdrawal_policy")
     }
-}
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(_deps: Deps, _env: Env, _msg: QueryMsg) -> StdResult<Binary> {
-    unimplemented!()
-}
+    pub fn remove_withdrawal_policy(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        // confirm boss is calling
+        validate_boss(deps.as_ref(), &info)?;
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
-    match msg.id {
-        UNDELEGATION_REPLY_ID => reply::unregister_vote_if_last_delegation(deps, env, msg),
-        _ => Err(ContractError::UnknownReplyIdCommon {}),
+        change_withdrawal_policy(deps, env, None, "remove_withdrawal_policy")
     }
-}
 
-pub mod reply {
-    use cosmwasm_std::WasmMsg;
-    use util_types::ExecuteMsg as CommonExecuteMsg;
+    pub fn cancel_withdrawal_policy_change(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let policy = update_withdrawal_policy(deps.storage, &env)?;
+        validate_boss_or_guardian(deps.as_ref(), &info, policy.as_ref())?;
 
-    use crate::state::ASSEMBLY_ADDR;
+        if PENDING_WITHDRAWAL_POLICY.may_load(deps.storage)?.is_none() {
+            return Err(ContractError::NoPendingPolicyChange {});
+        }
+        PENDING_WITHDRAWAL_POLICY.remove(deps.storage);
 
-    use super::*;
+        Ok(Response::new()
+            .add_attribute("action", "cancel_withdrawal_policy_change")
+            .add_attribute("canceled_by", info.sender.to_string()))
+    }
 
-    pub fn unregister_vote_if_last_delegation(
+    pub fn cancel_withdrawal(
         deps: DepsMut,
         env: Env,
-        _msg: Reply,
+        info: MessageInfo,
+        withdrawal_id: u64,
     ) -> Result<Response, ContractError> {
-        let delegations = deps.querier.query_all_delegations(env.contract.address)?;
+        let policy = update_withdrawal_policy(deps.storage, &env)?;
+        validate_boss_or_guardian(deps.as_ref(), &info, policy.as_ref())?;
 
-        // if there are no more delegations, send message to Assembly to unregister the user account
-        if delegations.is_empty() {
-            // create message to the Assembly
-            let msg = WasmMsg::Execute {
-                contract_addr: ASSEMBLY_ADDR.load(deps.storage)?,
-                msg: to_binary(&CommonExecuteMsg::UnregisterUserAccount {})?,
-                funds: vec![],
-            };
+        if !PENDING_WITHDRAWALS.has(deps.storage, withdrawal_id) {
+            return Err(ContractError::WithdrawalNotFound {});
+        }
+        PENDING_WITHDRAWALS.remove(deps.storage, withdrawal_id);
 
-            Ok(Response::new()
-                .add_attribute("action", "unregister_vote_on_reply")
-                .add_message(msg))
-        } else {
-            Ok(Response::default())
+        Ok(Response::new()
+            .add_attribute("action", "cancel_withdrawal")
+            .add_attribute("withdrawal_id", withdrawal_id.to_string())
+            .add_attribute("canceled_by", info.sender.to_string()))
+    }
+
+    // anyone can release a withdrawal once its delay passed, the recipient was set by the boss
+    pub fn release_withdrawal(
+        deps: DepsMut,
+        env: Env,
+        withdrawal_id: u64,
+    ) -> Result<Response, ContractError> {
+        let withdrawal = PENDING_WITHDRAWALS
+            .may_load(deps.storage, withdrawal_id)?
+            .ok_or(ContractError::WithdrawalNotFound {})?;
+        if withdrawal.release_time > env.block.time {
+            return Err(ContractError::WithdrawalLocked {});
+        }
+
+        let balance = spendable_balance(deps.as_ref(), &env, &withdrawal.token)?;
+        if withdrawal.token.amount() > balance {
+            return Err(ContractError::InsufficientFunds {});
+        }
+        check_vesting_lock(
+            deps.as_ref(),
+            &env,
+            &withdrawal.token,
+            withdrawal.token.amount(),
+        )?;
+        PENDING_WITHDRAWALS.remove(deps.storage, withdrawal_id);
+
+        let amount = withdrawal.token.amount();
+        let msg = withdraw_msg(deps.storage, &env, withdrawal.token, &withdrawal.recipient)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "release_withdrawal")
+            .add_attribute("withdrawal_id", withdrawal_id.to_string())
+            .add_attribute("amount", amount.to_string())
+            .add_attribute("recipient", withdrawal.recipient)
+            .add_message(msg))
+    }
+
+    // stakes the grant with the chosen validator, so the account starts with vote power
+    pub fn start_vesting(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        vesting: VestingParams,
+        response: Response,
+    ) -> Result<Response, ContractError> {
+        if !vesting.schedule.is_valid() {
+            return Err(ContractError::InvalidVesting {});
+        }
+
+        // the grantor sends exactly the vesting total
+        let denom = deps.querier.query_bonded_denom()?;
+        let sent_token =
+            must_pay(&info, denom.as_str()).map_err(|_| ContractError::InvalidVesting {})?;
+        if sent_token != vesting.schedule.total() {
+            return Err(ContractError::InvalidVesting {});
         }
+
+        let grantor = deps
+            .api
+            .addr_validate(&vesting.grantor)
+            .map_err(|_| ContractError::InvalidAddr {})?;
+        let validator = validate_validator(deps.as_ref(), &vesting.validator_addr)?;
+
+        VESTING.save(
+            deps.storage,
+            &Vesting {
+                grantor: grantor.to_string(),
+                schedule: vesting.schedule,
+                clawback: vesting.clawback,
+                clawed_back_at: None,
+                owed_to_grantor: Uint128::zero(),
+            },
+        )?;
+
+        let amount = Coin {
+            denom,
+            amount: sent_token,
+        };
+        record_ledger_entry(
+            deps.storage,
+            &env,
+            LedgerAction::Delegate {
+                validator: validator.address.clone(),
+                amount: amount.clone(),
+            },
+        )?;
+
+        let register_msg = WasmMsg::Execute {
+            contract_addr: ASSEMBLY_ADDR.load(deps.storage)?,
+            msg: to_binary(&CommonExecuteMsg::RegisterUserAccount {})?,
+            funds: vec![],
+        };
+
+        Ok(response
+            .add_attribute("grantor", grantor.to_string())
+            .add_attribute("vesting_total", sent_token.to_string())
+            .add_attribute("validator", validator.address.clone())
+            .add_message(StakingMsg::Delegate {
+                validator: validator.address,
+                amount,
+            })
+            .add_message(register_msg))
     }
-}
 
-#[cfg(test)]
-mod tests {}
+    pub fn clawback(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        let mut vesting = VESTING
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoVesting {})?;
+
+        // confirm grantor is calling
+        if info.sender != vesting.grantor {
+            return Err(ContractError::Unauthorized {});
+        }
+        if !vesting.clawback || vesting.clawed_back_at.is_some() {
+            return Err(ContractError::ClawbackDisabled {});
+        }
+
+        let unvested = vesting.unvested(env.block.time);
+        if unvested.is_zero() {
+            return Err(ContractError::NothingToClawBack {});
+        }
+
+        // liquid funds are paid right away, the rest is undelegated and paid when swept
+        let denom = deps.querier.query_bonded_denom()?;
+        let balance = deps
+            .querier
+            .query_balance(env.contract.address.clone(), denom.clone())?;
+        let tip_pool = TIP_POOL.may_load(deps.storage)?.unwrap_or_default();
+        let paid = balance.amount.saturating_sub(tip_pool).min(unvested);
+
+        let delegations = deps
+            .querier
+            .query_all_delegations(env.contract.address.clone())?;
+        let completion_time = env
+            .block
+            .time
+            .plus_seconds(query_unbonding_time(&deps.querier)?);
+        let msgs = undelegate_in_order(
+            deps.storage,
+            &env,
+            &delegations,
+            unvested - paid,
+            completion_time,
+        )?;
+
+        vesting.clawed_back_at = Some(env.block.time);
+        vesting.owed_to_grantor = msgs
+            .iter()
+            .map(|msg| match msg {
+                StakingMsg::Undelegate { amount, .. } => amount.amount,
+                _ => Uint128::zero(),
+            })
+            .sum();
+        VESTING.save(deps.storage, &vesting)?;
+
+        let mut response = Response::new()
+            .add_attribute("action", "clawback")
+            .add_attribute("grantor", vesting.grantor.clone())
+            .add_attribute("paid", paid.to_string())
+            .add_attribute("owed", vesting.owed_to_grantor.to_string());
+
+        if !paid.is_zero() {
+            response = response.add_message(BankMsg::Send {
+                to_address: vesting.grantor,
+                amount: vec![Coin {
+                    denom,
+                    amount: paid,
+                }],
+            });
+        }
+
+        // the reply unregisters the vote power if nothing is left staked
+        Ok(response.add_submessages(
+            msgs.into_iter()
+                .map(|msg| SubMsg::reply_on_success(msg, UNDELEGATION_REPLY_ID)),
+        ))
+    }
+
+    // slashing lowers the delegations without any message, so anyone can have the assembly
+    // recompute the stake from the chain. It emits the stake_sync event with the delta
+    pub fn sync_stake(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+        let stake = staked_amount(deps.as_ref(), &env)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "sync_stake")
+            .add_attribute("stake", stake.to_string())
+            .add_message(sync_stake_msg(deps.storage)?))
+    }
+
+    // sent after any staking msg, the assembly reads the delegations once they ran.
+    // Undelegations send it from their reply, unless it unregisters the account
+    pub fn sync_stake_msg(storage: &dyn Storage) -> StdResult<WasmMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: ASSEMBLY_ADDR.load(storage)?,
+            msg: to_binary(&CommonExecuteMsg::SyncAccountStake {})?,
+            funds: vec![],
+        })
+    }
+
+    pub fn deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+        if info.funds.is_empty() {
+            return Err(ContractError::WrongToken {});
+        }
+
+        // track every native token sent, all of them must be allowed. The bonded denom
+        // always is, as it can always be withdrawn
+        let bonded_denom = deps.querier.query_bonded_denom()?;
+        for coin in info.funds.iter() {
+            let token = match ALLOWED_TOKENS.may_load(deps.storage, coin.denom.clone())? {
+                Some(token) => token,
+                None if coin.denom == bonded_denom => CheckedTokenInfo::Native {
+                    denom: bonded_denom.clone(),
+                    amount: Uint128::zero(),
+                },
+                None => return Err(ContractError::TokenNotAllowed {}),
+            };
+            let deposited = token.amount() + coin.amount;
+            ALLOWED_TOKENS.save(
+                deps.storage,
+                coin.denom.clone(),
+                &token.with_amount(deposited),
+            )?;
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "deposit")
+            .add_attribute("from", info.sender.to_string())
+            .add_attribute(
+                "amount",
+                info.funds
+                    .iter()
+                    .map(|coin| coin.to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+            ))
+    }
+
+    pub fn receive(
+        deps: DepsMut,
+        info: MessageInfo,
+        cw20_msg: Cw20ReceiveMsg,
+    ) -> Result<Response, ContractError> {
+        // the cw20 contract is the caller, it must be allowed
+        let token = ALLOWED_TOKENS
+            .may_load(deps.storage, info.sender.to_string())?
+            .filter(|token| matches!(token, CheckedTokenInfo::Cw20 { .. }))
+            .ok_or(ContractError::TokenNotAllowed {})?;
+
+        let deposited = token.amount() + cw20_msg.amount;
+        ALLOWED_TOKENS.save(
+            deps.storage,
+            info.sender.to_string(),
+            &token.with_amount(deposited),
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "receive")
+            .add_attribute("token", info.sender.to_string())
+            .add_attribute("from", cw20_msg.sender)
+            .add_attribute("amount", cw20_msg.amount.to_string()))
+    }
+
+    pub fn update_allowed_tokens(
+        deps: DepsMut,
+        info: MessageInfo,
+        to_add: Vec<TokenInfo>,
+        to_remove: Vec<TokenInfo>,
+    ) -> Result<Response, ContractError> {
+        // confirm boss is calling
+        validate_boss(deps.as_ref(), &info)?;
+
+        for token in to_add.into_iter() {
+            let token = check_token(deps.as_ref(), token)?;
+            // keep tracking deposits of tokens that are already allowed
+            if !ALLOWED_TOKENS.has(deps.storage, token.key()) {
+                ALLOWED_TOKENS.save(deps.storage, token.key(), &token)?;
+            }
+        }
+
+        for token in to_remove.into_iter() {
+            let token = check_token(deps.as_ref(), token)?;
+            ALLOWED_TOKENS.remove(deps.storage, token.key());
+        }
+
+        Ok(Response::new().add_attribute("action", "update_allowed_tokens"))
+    }
+
+    pub fn sweep_matured(
+        deps: DepsMut,
+        env: Env,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Response, ContractError> {
+        // anyone can trigger the sweep, funds only go to the boss or the configured destination.
+        // Entries are checked a page at a time, last_id tells where the next page starts
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let page = UNBONDINGS
+            .range(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .collect::<StdResult<Vec<(u64, UnbondingEntry)>>>()?;
+        let last_id = page.last().map(|(id, _)| *id);
+        let matured = page
+            .into_iter()
+            .filter(|(_, entry)| entry.completion_time <= env.block.time)
+            .collect::<Vec<(u64, UnbondingEntry)>>();
+
+        if matured.is_empty() {
+            return Err(ContractError::NothingToSweep {});
+        }
+
+        let denom = deps.querier.query_bonded_denom()?;
+        let mut matured_amount = Uint128::zero();
+        for (id, entry) in matured.iter() {
+            if entry.amount.denom == denom {
+                matured_amount += entry.amount.amount;
+            }
+            UNBONDINGS.remove(deps.storage, *id);
+        }
+
+        // a slash while unbonding or a previous withdraw can leave less than expected,
+        // and the funds kept for the delegation schedule are never swept
+        let balance = deps
+            .querier
+            .query_balance(env.contract.address.clone(), denom.clone())?;
+        let reserved = reserved_for_schedule(deps.storage)?;
+        let mut amount = matured_amount.min(balance.amount.saturating_sub(reserved));
+
+        let destination = match SWEEP_DESTINATION.may_load(deps.storage)? {
+            Some(destination) => destination,
+            None => BOSS_ADDR.load(deps.storage)?,
+        };
+
+        let mut response = Response::new()
+            .add_attribute("action", "sweep_matured")
+            .add_attribute("entries", matured.len().to_string())
+            .add_attribute("last_id", last_id.unwrap_or_default().to_string());
+
+        // clawed back funds go to the grantor first, and locked funds stay in the account
+        if let Some(mut vesting) = VESTING.may_load(deps.storage)? {
+            let grantor_amount = amount.min(vesting.owed_to_grantor);
+            vesting.owed_to_grantor -= grantor_amount;
+            VESTING.save(deps.storage, &vesting)?;
+
+            let free = bonded_holdings(deps.as_ref(), &env)?
+                .saturating_sub(grantor_amount)
+                .saturating_sub(vesting.locked(env.block.time));
+            amount = (amount - grantor_amount).min(free);
+
+            if !grantor_amount.is_zero() {
+                response = response
+                    .add_attribute("to_grantor", grantor_amount.to_string())
+                    .add_message(BankMsg::Send {
+                        to_address: vesting.grantor,
+                        amount: vec![Coin {
+                            denom: denom.clone(),
+                            amount: grantor_amount,
+                        }],
+                    });
+            }
+        }
+
+        response = response
+            .add_attribute("amount", amount.to_string())
+            .add_attribute("to", destination.clone());
+
+        if !amount.is_zero() {
+            response = response.add_message(BankMsg::Send {
+                to_address: destination,
+                amount: vec![Coin { denom, amount }],
+            });
+        }
+
+        Ok(response)
+    }
+
+    pub fn set_sweep_destination(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        destination: Option<String>,
+    ) -> Result<Response, ContractError> {
+        // confirm boss is calling
+        validate_boss(deps.as_ref(), &info)?;
+
+        let destination = match destination {
+            Some(destination) => Some(
+                deps.api
+                    .addr_validate(&destination)
+                    .map_err(|_| ContractError::InvalidAddr {})?
+                    .to_string(),
+            ),
+            None => None,
+        };
+
+        change_payout(deps, env, PayoutChange::SweepDestination { destination })
+    }
+
+    pub fn set_reward_withdraw_addr(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        addr: Option<String>,
+    ) -> Result<Response, ContractError> {
+        // confirm boss is calling
+        validate_boss(deps.as_ref(), &info)?;
+
+        // None resets the withdraw address to the user account itself
+        let addr = match addr {
+            Some(addr) => Some(
+                deps.api
+                    .addr_validate(&addr)
+                    .map_err(|_| ContractError::InvalidAddr {})?
+                    .to_string(),
+            ),
+            None => None,
+        };
+        let change = PayoutChange::RewardWithdrawAddr { addr };
+        check_payout_change(deps.storage, &change)?;
+
+        change_payout(deps, env, change)
+    }
+
+    pub fn set_reward_routes(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        routes: Vec<RewardRoute>,
+    ) -> Result<Response, ContractError> {
+        // confirm boss is calling
+        validate_boss(deps.as_ref(), &info)?;
+
+        // whatever is not routed stays in the user account
+        let mut total_share = Decimal::zero();
+        let mut valid_routes = vec![];
+        for route in routes {
+            if route.share.is_zero() {
+                return Err(ContractError::InvalidRewardShares {});
+            }
+            total_share += route.share;
+            let valid_addr = deps
+                .api
+                .addr_validate(&route.addr)
+                .map_err(|_| ContractError::InvalidAddr {})?;
+            valid_routes.push(RewardRoute {
+                addr: valid_addr.to_string(),
+                share: route.share,
+            });
+        }
+        if total_share > Decimal::one() {
+            return Err(ContractError::InvalidRewardShares {});
+        }
+        let change = PayoutChange::RewardRoutes {
+            routes: valid_routes,
+        };
+        check_payout_change(deps.storage, &change)?;
+
+        change_payout(deps, env, change)
+    }
+
+    // anyone can apply the payout changes whose delay passed
+    pub fn apply_payout_changes(mut deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+        let due = PENDING_PAYOUT_CHANGES
+            .range(deps.storage, None, None, Order::Ascending)
+            .filter(|item| match item {
+                Ok((_, pending)) => pending.effective_time <= env.block.time,
+                Err(_) => true,
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let mut response = Response::new()
+            .add_attribute("action", "apply_payout_changes")
+            .add_attribute("n_of_changes", due.len().to_string());
+        for (key, pending) in due {
+            PENDING_PAYOUT_CHANGES.remove(deps.storage, key);
+            let applied = apply_payout_change(deps.branch(), &env, pending.change)?;
+            response = response
+                .add_attributes(applied.attributes)
+                .add_submessages(applied.messages);
+        }
+
+        Ok(response)
+    }
+
+    pub fn cancel_payout_changes(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let policy = update_withdrawal_policy(deps.storage, &env)?;
+        validate_boss_or_guardian(deps.as_ref(), &info, policy.as_ref())?;
+
+        if PENDING_PAYOUT_CHANGES.is_empty(deps.storage) {
+            return Err(ContractError::NoPendingPayoutChange {});
+        }
+        PENDING_PAYOUT_CHANGES.clear(deps.storage);
+
+        Ok(Response::new()
+            .add_attribute("action", "cancel_payout_changes")
+            .add_attribute("canceled_by", info.sender.to_string()))
+    }
+
+    pub fn set_delegation_schedule(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        amount: Uint128,
+        validators: Vec<String>,
+        interval: u64,
+        tip: Uint128,
+    ) -> Result<Response, ContractError> {
+        // confirm boss is calling
+        validate_boss(deps.as_ref(), &info)?;
+
+        if amount.is_zero() || interval == 0 || validators.is_empty() {
+            return Err(ContractError::InvalidSchedule {});
+        }
+
+        // validate every validator exists and complies with the assembly validator policy
+        let validators = validators
+            .iter()
+            .map(|validator_addr| {
+                validate_validator(deps.as_ref(), validator_addr).map(|val| val.address)
+            })
+            .collect::<Result<Vec<String>, ContractError>>()?;
+
+        // first execution is due once the interval elapses
+        let schedule = DelegationSchedule {
+            amount,
+            validators,
+            interval,
+            next_execution: env.block.time.plus_seconds(interval),
+            tip,
+            paused: false,
+        };
+        DELEGATION_SCHEDULE.save(deps.storage, &schedule)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_delegation_schedule")
+            .add_attribute("amount", amount.to_string())
+            .add_attribute("interval", interval.to_string())
+            .add_attribute("next_execution", schedule.next_execution.to_string()))
+    }
+
+    pub fn set_delegation_schedule_paused(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        paused: bool,
+    ) -> Result<Response, ContractError> {
+        // confirm boss is calling
+        validate_boss(deps.as_ref(), &info)?;
+
+        let mut schedule = DELEGATION_SCHEDULE
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoSchedule {})?;
+
+        // on resume, executions missed while paused are not caught up
+        if !paused && schedule.next_execution < env.block.time {
+            schedule.next_execution = env.block.time;
+        }
+        schedule.paused = paused;
+        DELEGATION_SCHEDULE.save(deps.storage, &schedule)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_delegation_schedule_paused")
+            .add_attribute("paused", paused.to_string())
+            .add_attribute("next_execution", schedule.next_execution.to_string()))
+    }
+
+    pub fn cancel_delegation_schedule(
+        deps: DepsMut,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        // confirm boss is calling
+        validate_boss(deps.as_ref(), &info)?;
+
+        if DELEGATION_SCHEDULE.may_load(deps.storage)?.is_none() {
+            return Err(ContractError::NoSchedule {});
+        }
+        DELEGATION_SCHEDULE.remove(deps.storage);
+
+        // the tip pool goes back to the liquid balance, so the boss can withdraw it
+        TIP_POOL.remove(deps.storage);
+
+        Ok(Response::new().add_attribute("action", "cancel_delegation_schedule"))
+    }
+
+    pub fn fund_tip_pool(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+        let native_denom = deps.querier.query_bonded_denom()?;
+        let sent_token =
+            must_pay(&info, native_denom.as_str()).map_err(|_| ContractError::WrongToken {})?;
+
+        let tip_pool = TIP_POOL.may_load(deps.storage)?.unwrap_or_default() + sent_token;
+        TIP_POOL.save(deps.storage, &tip_pool)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "fund_tip_pool")
+            .add_attribute("amount", sent_token.to_string())
+            .add_attribute("tip_pool", tip_pool.to_string()))
+    }
+
+    pub fn execute_schedule(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        // anyone can execute the schedule once it is due
+        let mut schedule = DELEGATION_SCHEDULE
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoSchedule {})?;
+        if schedule.paused {
+            return Err(ContractError::SchedulePaused {});
+        }
+        if env.block.time < schedule.next_execution {
+            return Err(ContractError::ScheduleNotDue {});
+        }
+
+        // only the liquid balance that is not reserved for tips can be delegated
+        let denom = deps.querier.query_bonded_denom()?;
+        let balance = deps
+            .querier
+            .query_balance(env.contract.address.clone(), denom.clone())?;
+        let tip_pool = TIP_POOL.may_load(deps.storage)?.unwrap_or_default();
+        if balance.amount.saturating_sub(tip_pool) < schedule.amount {
+            return Err(ContractError::InsufficientFunds {});
+        }
+
+        // the validator policy may have changed since the schedule was set
+        for validator_addr in schedule.validators.iter() {
+            validate_validator(deps.as_ref(), validator_addr)?;
+        }
+
+        // split the amount evenly, the first validator gets the remainder
+        let n_validators = Uint128::from(schedule.validators.len() as u128);
+        let share = schedule.amount / n_validators;
+        let remainder = schedule.amount - share * n_validators;
+        let msgs = schedule
+            .validators
+            .iter()
+            .enumerate()
+            .map(|(i, validator)| (validator, if i == 0 { share + remainder } else { share }))
+            .filter(|(_, amount)| !amount.is_zero())
+            .map(|(validator, amount)| StakingMsg::Delegate {
+                validator: validator.to_string(),
+                amount: Coin {
+                    denom: denom.clone(),
+                    amount,
+                },
+            })
+            .collect::<Vec<StakingMsg>>();
+        for msg in msgs.iter() {
+            if let StakingMsg::Delegate { validator, amount } = msg {
+                record_ledger_entry(
+                    deps.storage,
+                    &env,
+                    LedgerAction::Delegate {
+                        validator: validator.clone(),
+                        amount: amount.clone(),
+                    },
+                )?;
+            }
+        }
+
+        // if it is first delegation, register vote power in the assembly
+        let is_first_delegation = deps
+            .querier
+            .query_all_delegations(env.contract.address)?
+            .is_empty();
+
+        schedule.next_execution = env.block.time.plus_seconds(schedule.interval);
+        DELEGATION_SCHEDULE.save(deps.storage, &schedule)?;
+
+        let mut response = Response::new()
+            .add_attribute("action", "execute_schedule")
+            .add_attribute("amount", schedule.amount.to_string())
+            .add_attribute("next_execution", schedule.next_execution.to_string())
+            .add_submessages(
+                msgs.into_iter()
+                    .map(|msg| SubMsg::reply_on_success(msg, REWARDS_REPLY_ID)),
+            );
+
+        // registering syncs the stake too
+        if is_first_delegation {
+            response = response.add_message(WasmMsg::Execute {
+                contract_addr: ASSEMBLY_ADDR.load(deps.storage)?,
+                msg: to_binary(&CommonExecuteMsg::RegisterUserAccount {})?,
+                funds: vec![],
+            });
+        } else {
+            response = response.add_message(sync_stake_msg(deps.storage)?);
+        }
+
+        // reward the caller, as long as the tip pool lasts
+        let tip = schedule.tip.min(tip_pool);
+        if !tip.is_zero() {
+            TIP_POOL.save(deps.storage, &(tip_pool - tip))?;
+            response = response.add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![Coin { denom, amount: tip }],
+            });
+        }
+
+        Ok(response.add_attribute("tip", tip.to_string()))
+    }
+
+    // liquid funds kept for the delegation schedule: the tip pool and, while the schedule
+    // is active, the amount of its next execution
+    fn reserved_for_schedule(storage: &dyn Storage) -> StdResult<Uint128> {
+        let tip_pool = TIP_POOL.may_load(storage)?.unwrap_or_default();
+        let next_execution = match DELEGATION_SCHEDULE.may_load(storage)? {
+            Some(schedule) if !schedule.paused => schedule.amount,
+            _ => Uint128::zero(),
+        };
+        Ok(tip_pool + next_execution)
+    }
+
+    // undelegates amount from the delegations in order, recording the unbondings
+    fn undelegate_in_order(
+        storage: &mut dyn Storage,
+        env: &Env,
+        delegations: &[Delegation],
+        amount: Uint128,
+        completion_time: Timestamp,
+    ) -> StdResult<Vec<StakingMsg>> {
+        let mut left = amount;
+        let mut msgs = vec![];
+        for delegation in delegations.iter() {
+            if left.is_zero() {
+                break;
+            }
+            let amount = Coin {
+                denom: delegation.amount.denom.clone(),
+                amount: delegation.amount.amount.min(left),
+            };
+            if amount.amount.is_zero() {
+                continue;
+            }
+            left -= amount.amount;
+
+            record_unbonding(
+                storage,
+                env,
+                &delegation.validator,
+                &amount,
+                completion_time,
+            )?;
+            msgs.push(StakingMsg::Undelegate {
+                validator: delegation.validator.clone(),
+                amount,
+            });
+        }
+        Ok(msgs)
+    }
+
+    fn staked_amount(deps: Deps, env: &Env) -> StdResult<Uint128> {
+        Ok(deps
+            .querier
+            .query_all_delegations(env.contract.address.clone())?
+            .iter()
+            .map(|delegation| delegation.amount.amount)
+            .sum())
+    }
+
+    // bonded denom held by the account, liquid, staked or still unbonding
+    fn bonded_holdings(deps: Deps, env: &Env) -> StdResult<Uint128> {
+        let denom = deps.querier.query_bonded_denom()?;
+        let liquid = deps
+            .querier
+            .query_balance(env.contract.address.clone(), denom.clone())?
+            .amount;
+        let unbonding: Uint128 = UNBONDINGS
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(u64, UnbondingEntry)>>>()?
+            .into_iter()
+            .filter(|(_, entry)| {
+                entry.completion_time > env.block.time && entry.amount.denom == denom
+            })
+            .map(|(_, entry)| entry.amount.amount)
+            .sum();
+        Ok(liquid + staked_amount(deps, env)? + unbonding)
+    }
+
+    // taking amount of the token out of the account can't touch locked vesting funds
+    fn check_vesting_lock(
+        deps: Deps,
+        env: &Env,
+        token: &CheckedTokenInfo,
+        amount: Uint128,
+    ) -> Result<(), ContractError> {
+        let vesting = match VESTING.may_load(deps.storage)? {
+            Some(vesting) => vesting,
+            None => return Ok(()),
+        };
+        if amount.is_zero() || token.key() != deps.querier.query_bonded_denom()? {
+            return Ok(());
+        }
+
+        let free = bonded_holdings(deps, env)?.saturating_sub(vesting.locked(env.block.time));
+        if amount > free {
+            return Err(ContractError::VestingLocked {});
+        }
+        Ok(())
+    }
+
+    // adds an entry to the ledger and keeps the per validator totals in sync
+    pub fn record_ledger_entry(
+        storage: &mut dyn Storage,
+        env: &Env,
+        action: LedgerAction,
+    ) -> StdResult<()> {
+        let mut update_totals = |validator: &str, update: &dyn Fn(&mut ValidatorTotals)| {
+            let mut totals = VALIDATOR_TOTALS
+                .may_load(storage, validator.to_string())?
+                .unwrap_or_else(|| ValidatorTotals::new(validator));
+            update(&mut totals);
+            VALIDATOR_TOTALS.save(storage, validator.to_string(), &totals)
+        };
+
+        match &action {
+            LedgerAction::Delegate { validator, amount } => {
+                update_totals(validator, &|totals| totals.delegated += amount.amount)?
+            }
+            LedgerAction::Undelegate { validator, amount } => {
+                update_totals(validator, &|totals| totals.undelegated += amount.amount)?
+            }
+            LedgerAction::Redelegate {
+                from_validator,
+                to_validator,
+                amount,
+            } => {
+                update_totals(from_validator, &|totals| {
+                    totals.redelegated_out += amount.amount
+                })?;
+                update_totals(to_validator, &|totals| {
+                    totals.redelegated_in += amount.amount
+                })?;
+            }
+            LedgerAction::Rewards { validator, amount } => {
+                update_totals(validator, &|totals| totals.add_rewards(amount))?
+            }
+            LedgerAction::Withdraw { .. } => {}
+        }
+
+        let id = NEXT_LEDGER_ID.may_load(storage)?.unwrap_or_default();
+        LEDGER.save(
+            storage,
+            id,
+            &LedgerEntry {
+                id,
+                time: env.block.time,
+                height: env.block.height,
+                action,
+            },
+        )?;
+        NEXT_LEDGER_ID.save(storage, &(id + 1))
+    }
+
+    fn record_unbonding(
+        storage: &mut dyn Storage,
+        env: &Env,
+        validator: &str,
+        amount: &Coin,
+        completion_time: Timestamp,
+    ) -> StdResult<()> {
+        record_ledger_entry(
+            storage,
+            env,
+            LedgerAction::Undelegate {
+                validator: validator.to_string(),
+                amount: amount.clone(),
+            },
+        )?;
+
+        let id = NEXT_UNBONDING_ID.may_load(storage)?.unwrap_or_default();
+        UNBONDINGS.save(
+            storage,
+            id,
+            &UnbondingEntry {
+                id,
+                validator: validator.to_string(),
+                amount: amount.clone(),
+                completion_time,
+            },
+        )?;
+        NEXT_UNBONDING_ID.save(storage, &(id + 1))
+    }
+
+    fn validate_boss(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+        let boss = BOSS_ADDR.load(deps.storage)?;
+        if info.sender != boss {
+            return Err(ContractError::Unauthorized {});
+        }
+        Ok(())
+    }
+
+    pub fn execute_msgs(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msgs: Vec<CosmosMsg>,
+    ) -> Result<Response, ContractError> {
+        // confirm boss is calling
+        validate_boss(deps.as_ref(), &info)?;
+
+        // the allow list is managed by the factory that created this account
+        let factory_addr = deps
+            .querier
+            .query_wasm_contract_info(env.contract.address.clone())?
+            .creator;
+        let allow_list: GetProxyAllowListResponse = deps
+            .querier
+            .query_wasm_smart(factory_addr, &CommonQueryMsg::GetProxyAllowList {})?;
+
+        for msg in msgs.iter() {
+            check_proxy_msg(msg, &allow_list)?;
+        }
+
+        // stargate msgs can move or unbond anything without the contract seeing the amount,
+        // so they wait until nothing is locked by the vesting
+        let locked = VESTING
+            .may_load(deps.storage)?
+            .map(|vesting| vesting.locked(env.block.time))
+            .unwrap_or_default();
+        if !locked.is_zero()
+            && msgs
+                .iter()
+                .any(|msg| matches!(msg, CosmosMsg::Stargate { .. }))
+        {
+            return Err(ContractError::VestingLocked {});
+        }
+
+        // funds sent along with proxied executions can't touch locked vesting funds
+        let denom = deps.querier.query_bonded_denom()?;
+        let sent: Uint128 = msgs
+            .iter()
+            .filter_map(|msg| match msg {
+                CosmosMsg::Wasm(WasmMsg::Execute { funds, .. }) => Some(funds),
+                _ => None,
+            })
+            .flatten()
+            .filter(|coin| coin.denom == denom)
+            .map(|coin| coin.amount)
+            .sum();
+        let token = CheckedTokenInfo::Native {
+            denom,
+            amount: Uint128::zero(),
+        };
+        check_vesting_lock(deps.as_ref(), &env, &token, sent)?;
+
+        // they count against the withdrawal caps too, there is no queue to put them in. Stargate
+        // msgs can move funds the caps can't see, so they wait until the policy is removed
+        if let Some(policy) = update_withdrawal_policy(deps.storage, &env)? {
+            if msgs
+                .iter()
+                .any(|msg| matches!(msg, CosmosMsg::Stargate { .. }))
+            {
+                return Err(ContractError::WithdrawalPolicyActive {});
+            }
+            let funds = msgs.iter().flat_map(|msg| match msg {
+                CosmosMsg::Wasm(WasmMsg::Execute { funds, .. }) => funds.clone(),
+                _ => vec![],
+            });
+            for coin in funds {
+                let token = CheckedTokenInfo::Native {
+                    denom: coin.denom,
+                    amount: coin.amount,
+                };
+                if !spend_withdrawal_cap(deps.storage, &env, &policy, &token)? {
+                    return Err(ContractError::WithdrawalCapExceeded {});
+                }
+            }
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "execute")
+            .add_attribute("n_of_msgs", msgs.len().to_string())
+            .add_messages(msgs))
+    }
+
+    // only stargate messages with an allowed type url and executions on allowed contracts go
+    // through, and never anything that votes or lets someone else vote for the account
+    fn check_proxy_msg(
+        msg: &CosmosMsg,
+        allow_list: &GetProxyAllowListResponse,
+    ) -> Result<(), ContractError> {
+        match msg {
+            CosmosMsg::Gov(_) => Err(ContractError::VoteBypass {}),
+            CosmosMsg::Stargate { type_url, value } => {
+                if VOTE_TYPE_URLS.contains(&type_url.as_str()) {
+                    return Err(ContractError::VoteBypass {});
+                }
+                if type_url == AUTHZ_GRANT_TYPE_URL && grants_vote(value)? {
+                    return Err(ContractError::VoteBypass {});
+                }
+                if !allow_list.type_urls.contains(type_url) {
+                    return Err(ContractError::MsgNotAllowed {});
+                }
+                Ok(())
+            }
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. })
+                if allow_list.contracts.contains(contract_addr) =>
+            {
+                Ok(())
+            }
+            _ => Err(ContractError::MsgNotAllowed {}),
+        }
+    }
+
+    // MsgGrant { grant: Grant { authorization: Any { type_url, value: GenericAuthorization { msg } } } }
+    fn grants_vote(msg_grant: &[u8]) -> StdResult<bool> {
+        let grant = bytes_field(msg_grant, 3)?.unwrap_or_default();
+        let authorization = bytes_field(grant, 1)?.unwrap_or_default();
+        let authorization_type_url = bytes_field(authorization, 1)?.unwrap_or_default();
+        if authorization_type_url != GENERIC_AUTHORIZATION_TYPE_URL.as_bytes() {
+            return Ok(false);
+        }
+        let generic_authorization = bytes_field(authorization, 2)?.unwrap_or_default();
+        let authorized_msg = bytes_field(generic_authorization, 1)?.unwrap_or_default();
+        Ok(VOTE_TYPE_URLS
+            .iter()
+            .any(|type_url| type_url.as_bytes() == authorized_msg))
+    }
+
+    pub fn requires_joint_approval(deps: Deps, msg: &ExecuteMsg) -> StdResult<bool> {
+        Ok(needs_joint_approval(msg) && JOINT_OWNERSHIP.may_load(deps.storage)?.is_some())
+    }
+
+    // everything is guarded except adding funds, permissionless cranks, msgs only the
+    // assembly or the grantor can send, stopping funds from moving and the approval flow
+    fn needs_joint_approval(msg: &ExecuteMsg) -> bool {
+        !matches!(
+            msg,
+            ExecuteMsg::Deposit {}
+                | ExecuteMsg::Receive(_)
+                | ExecuteMsg::FundTipPool {}
+                | ExecuteMsg::Claim {}
+                | ExecuteMsg::SweepMatured { .. }
+                | ExecuteMsg::ExecuteSchedule {}
+                | ExecuteMsg::ReleaseWithdrawal { .. }
+                | ExecuteMsg::SyncStake {}
+                | ExecuteMsg::AssemblyVote { .. }
+                | ExecuteMsg::Clawback {}
+                | ExecuteMsg::PauseDelegationSchedule {}
+                | ExecuteMsg::CancelWithdrawal { .. }
+                | ExecuteMsg::CancelWithdrawalPolicyChange {}
+                | ExecuteMsg::ApplyPayoutChanges {}
+                | ExecuteMsg::CancelPayoutChanges {}
+                | ExecuteMsg::EnableJointOwnership { .. }
+                | ExecuteMsg::ProposeJointAction { .. }
+                | ExecuteMsg::ApproveJointAction { .. }
+                | ExecuteMsg::ExecuteJointAction { .. }
+        )
+    }
+
+    pub fn enable_joint_ownership(
+        deps: DepsMut,
+        info: MessageInfo,
+        owners: Vec<String>,
+        threshold: u64,
+        proposal_duration: u64,
+    ) -> Result<Response, ContractError> {
+        // confirm boss is calling
+        validate_boss(deps.as_ref(), &info)?;
+
+        // once enabled, the boss alone can't turn it off
+        if JOINT_OWNERSHIP.may_load(deps.storage)?.is_some() {
+            return Err(ContractError::JointOwnershipAlreadyEnabled {});
+        }
+
+        let mut valid_owners = vec![];
+        for owner in owners.iter() {
+            let valid_owner = deps
+                .api
+                .addr_validate(owner)
+                .map_err(|_| ContractError::InvalidAddr {})?
+                .to_string();
+            if !valid_owners.contains(&valid_owner) {
+                valid_owners.push(valid_owner);
+            }
+        }
+
+        if threshold == 0 || threshold > valid_owners.len() as u64 {
+            return Err(ContractError::InvalidThreshold {});
+        }
+
+        JOINT_OWNERSHIP.save(
+            deps.storage,
+            &JointOwnership {
+                owners: valid_owners.clone(),
+                threshold,
+                proposal_duration,
+            },
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "enable_joint_ownership")
+            .add_attribute("owners", valid_owners.join(","))
+            .add_attribute("threshold", threshold.to_string()))
+    }
+
+    pub fn propose_joint_action(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        action: JointAction,
+    ) -> Result<Response, ContractError> {
+        let joint_ownership = validate_joint_owner(deps.as_ref(), &info)?;
+        if let JointAction::Msg { msg } = &action {
+            if !needs_joint_approval(msg) {
+                return Err(ContractError::InvalidJointAction {});
+            }
+        }
+
+        // proposing counts as the proposer approval
+        let id = NEXT_JOINT_PROPOSAL_ID
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        let proposal = JointProposal {
+            id,
+            action,
+            proposer: info.sender.to_string(),
+            approvals: vec![info.sender.to_string()],
+            expires: env
+                .block
+                .time
+                .plus_seconds(joint_ownership.proposal_duration),
+        };
+        JOINT_PROPOSALS.save(deps.storage, id, &proposal)?;
+        NEXT_JOINT_PROPOSAL_ID.save(deps.storage, &(id + 1))?;
+
+        Ok(Response::new()
+            .add_attribute("action", "propose_joint_action")
+            .add_attribute("proposal_id", id.to_string())
+            .add_attribute("proposer", info.sender.to_string())
+            .add_attribute("expires", proposal.expires.to_string()))
+    }
+
+    pub fn approve_joint_action(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        proposal_id: u64,
+    ) -> Result<Response, ContractError> {
+        let joint_ownership = validate_joint_owner(deps.as_ref(), &info)?;
+
+        let mut proposal = load_open_joint_proposal(deps.as_ref(), &env, proposal_id)?;
+        if proposal.approvals.contains(&info.sender.to_string()) {
+            return Err(ContractError::AlreadyApproved {});
+        }
+        proposal.approvals.push(info.sender.to_string());
+        JOINT_PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "approve_joint_action")
+            .add_attribute("proposal_id", proposal_id.to_string())
+            .add_attribute("approved_by", info.sender.to_string())
+            .add_attribute(
+                "approvals",
+                format!("{}/{}", proposal.approvals.len(), joint_ownership.threshold),
+            ))
+    }
+
+    pub fn execute_joint_action(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        proposal_id: u64,
+    ) -> Result<Response, ContractError> {
+        let joint_ownership = validate_joint_owner(deps.as_ref(), &info)?;
+
+        let proposal = load_open_joint_proposal(deps.as_ref(), &env, proposal_id)?;
+        if (proposal.approvals.len() as u64) < joint_ownership.threshold {
+            return Err(ContractError::ThresholdNotReached {});
+        }
+        JOINT_PROPOSALS.remove(deps.storage, proposal_id);
+
+        // the owners approval stands in for the boss signature
+        let boss_info = MessageInfo {
+            sender: deps.api.addr_validate(&BOSS_ADDR.load(deps.storage)?)?,
+            funds: vec![],
+        };
+
+        let response = match proposal.action {
+            JointAction::Delegate {
+                validator_addr,
+                amount,
+            } => {
+                // delegate uses the attached funds, here they come from the liquid balance
+                let denom = deps.querier.query_bonded_denom()?;
+                let balance = deps
+                    .querier
+                    .query_balance(env.contract.address.clone(), denom.clone())?;
+                let tip_pool = TIP_POOL.may_load(deps.storage)?.unwrap_or_default();
+                if balance.amount.saturating_sub(tip_pool) < amount {
+                    return Err(ContractError::InsufficientFunds {});
+                }
+                let info = MessageInfo {
+                    funds: vec![Coin { denom, amount }],
+                    ..boss_info
+                };
+                delegate(deps, env, info, validator_addr)?
+            }
+            JointAction::Msg { msg } => super::dispatch(deps, env, boss_info, *msg)?,
+        };
+
+        Ok(response
+            .add_attribute("joint_proposal_id", proposal_id.to_string())
+            .add_attribute("executed_by", info.sender.to_string()))
+    }
+
+    fn validate_joint_owner(
+        deps: Deps,
+        info: &MessageInfo,
+    ) -> Result<JointOwnership, ContractError> {
+        let joint_ownership = JOINT_OWNERSHIP
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoJointOwnership {})?;
+        if !joint_ownership.owners.contains(&info.sender.to_string()) {
+            return Err(ContractError::Unauthorized {});
+        }
+        Ok(joint_ownership)
+    }
+
+    fn load_open_joint_proposal(
+        deps: Deps,
+        env: &Env,
+        proposal_id: u64,
+    ) -> Result<JointProposal, ContractError> {
+        let proposal = JOINT_PROPOSALS
+            .may_load(deps.storage, proposal_id)?
+            .ok_or(ContractError::JointProposalNotFound {})?;
+        if proposal.expires <= env.block.time {
+            return Err(ContractError::JointProposalExpired {});
+        }
+        Ok(proposal)
+    }
+
+    // without a policy changes apply right away, otherwise they wait for the current delay
+    fn change_withdrawal_policy(
+        deps: DepsMut,
+        env: Env,
+        policy: Option<WithdrawalPolicy>,
+        action: &str,
+    ) -> Result<Response, ContractError> {
+        let effective_time = match update_withdrawal_policy(deps.storage, &env)? {
+            Some(current_policy) => {
+                let effective_time = env.block.time.plus_seconds(current_policy.delay);
+                PENDING_WITHDRAWAL_POLICY.save(
+                    deps.storage,
+                    &PendingWithdrawalPolicy {
+                        policy,
+                        effective_time,
+                    },
+                )?;
+                effective_time
+            }
+            None => {
+                match policy {
+                    Some(policy) => WITHDRAWAL_POLICY.save(deps.storage, &policy)?,
+                    None => WITHDRAWAL_POLICY.remove(deps.storage),
+                }
+                PENDING_WITHDRAWAL_POLICY.remove(deps.storage);
+                env.block.time
+            }
+        };
+
+        Ok(Response::new()
+            .add_attribute("action", action)
+            .add_attribute("effective_time", effective_time.to_string()))
+    }
+
+    // applies a pending policy change whose delay passed and returns the policy in effect
+    fn update_withdrawal_policy(
+        storage: &mut dyn Storage,
+        env: &Env,
+    ) -> StdResult<Option<WithdrawalPolicy>> {
+        let (policy, pending) = load_withdrawal_policy(storage, env.block.time)?;
+        if pending.is_none() && PENDING_WITHDRAWAL_POLICY.may_load(storage)?.is_some() {
+            match &policy {
+                Some(policy) => WITHDRAWAL_POLICY.save(storage, policy)?,
+                None => WITHDRAWAL_POLICY.remove(storage),
+            }
+            PENDING_WITHDRAWAL_POLICY.remove(storage);
+        }
+        Ok(policy)
+    }
+
+    // with a withdrawal policy, changes to where funds are paid wait for its delay like the
+    // policy changes do, otherwise a stolen key could redirect the sweeps and the rewards
+    fn change_payout(
+        deps: DepsMut,
+        env: Env,
+        change: PayoutChange,
+    ) -> Result<Response, ContractError> {
+        match update_withdrawal_policy(deps.storage, &env)? {
+            Some(policy) => {
+                let effective_time = env.block.time.plus_seconds(policy.delay);
+                let key = change.key();
+                PENDING_PAYOUT_CHANGES.save(
+                    deps.storage,
+                    key.clone(),
+                    &PendingPayoutChange {
+                        change,
+                        effective_time,
+                    },
+                )?;
+                Ok(Response::new()
+                    .add_attribute("action", "queue_payout_change")
+                    .add_attribute("change", key)
+                    .add_attribute("effective_time", effective_time.to_string()))
+            }
+            None => apply_payout_change(deps, &env, change),
+        }
+    }
+
+    // routing only works on rewards that land in the user account, checked against the
+    // current config and the pending changes
+    fn check_payout_change(
+        storage: &dyn Storage,
+        change: &PayoutChange,
+    ) -> Result<(), ContractError> {
+        let pending = |key: &str| -> StdResult<Option<PayoutChange>> {
+            Ok(PENDING_PAYOUT_CHANGES
+                .may_load(storage, key.to_string())?
+                .map(|pending| pending.change))
+        };
+        let compatible = match change {
+            PayoutChange::RewardWithdrawAddr { addr: Some(_) } => {
+                let routes = REWARD_ROUTES.may_load(storage)?.unwrap_or_default();
+                let pending_routes = match pending("reward_routes")? {
+                    Some(PayoutChange::RewardRoutes { routes }) => routes,
+                    _ => vec![],
+                };
+                routes.is_empty() && pending_routes.is_empty()
+            }
+            PayoutChange::RewardRoutes { routes } if !routes.is_empty() => {
+                let pending_addr = matches!(
+                    pending("reward_withdraw_addr")?,
+                    Some(PayoutChange::RewardWithdrawAddr { addr: Some(_) })
+                );
+                REWARD_WITHDRAW_ADDR.may_load(storage)?.is_none() && !pending_addr
+            }
+            _ => true,
+        };
+        if !compatible {
+            return Err(ContractError::RewardsPaidElsewhere {});
+        }
+        Ok(())
+    }
+
+    fn apply_payout_change(
+        deps: DepsMut,
+        env: &Env,
+        change: PayoutChange,
+    ) -> Result<Response, ContractError> {
+        match change {
+            PayoutChange::SweepDestination { destination } => {
+                match &destination {
+                    Some(destination) => SWEEP_DESTINATION.save(deps.storage, destination)?,
+                    None => SWEEP_DESTINATION.remove(deps.storage),
+                }
+                Ok(Response::new()
+                    .add_attribute("action", "set_sweep_destination")
+                    .add_attribute(
+                        "destination",
+                        destination.unwrap_or_else(|| "boss".to_string()),
+                    ))
+            }
+            PayoutChange::RewardWithdrawAddr { addr } => {
+                let withdraw_addr = match addr {
+                    Some(addr) => {
+                        REWARD_WITHDRAW_ADDR.save(deps.storage, &addr)?;
+                        addr
+                    }
+                    None => {
+                        REWARD_WITHDRAW_ADDR.remove(deps.storage);
+                        env.contract.address.to_string()
+                    }
+                };
+                let msg = DistributionMsg::SetWithdrawAddress {
+                    address: withdraw_addr.clone(),
+                };
+                Ok(Response::new()
+                    .add_attribute("action", "set_reward_withdraw_addr")
+                    .add_attribute("withdraw_addr", withdraw_addr)
+                    .add_message(msg))
+            }
+            PayoutChange::RewardRoutes { routes } => {
+                let total_share = routes
+                    .iter()
+                    .fold(Decimal::zero(), |total, route| total + route.share);
+                REWARD_ROUTES.save(deps.storage, &routes)?;
+                Ok(Response::new()
+                    .add_attribute("action", "set_reward_routes")
+                    .add_attribute("routes", routes.len().to_string())
+                    .add_attribute("total_share", total_share.to_string()))
+            }
+        }
+    }
+
+    // adds the token amount to what was withdrawn this period, unless it goes above the cap
+    fn spend_withdrawal_cap(
+        storage: &mut dyn Storage,
+        env: &Env,
+        policy: &WithdrawalPolicy,
+        token: &CheckedTokenInfo,
+    ) -> StdResult<bool> {
+        let cap = policy
+            .caps
+            .iter()
+            .find(|cap| cap.key() == token.key())
+            .map(|cap| cap.amount())
+            .unwrap_or_default();
+        let period = WITHDRAWAL_PERIODS
+            .may_load(storage, token.key())?
+            .filter(|period| period.end > env.block.time)
+            .unwrap_or(WithdrawalPeriod {
+                end: env.block.time.plus_seconds(policy.period),
+                withdrawn: Uint128::zero(),
+            });
+
+        if period.withdrawn + token.amount() > cap {
+            return Ok(false);
+        }
+        WITHDRAWAL_PERIODS.save(
+            storage,
+            token.key(),
+            &WithdrawalPeriod {
+                withdrawn: period.withdrawn + token.amount(),
+                ..period
+            },
+        )?;
+        Ok(true)
+    }
+
+    // balance of the token the boss can withdraw, the tip pool is owed to the schedule crankers
+    fn spendable_balance(deps: Deps, env: &Env, token: &CheckedTokenInfo) -> StdResult<Uint128> {
+        let balance = query_token_balance(&deps.querier, &env.contract.address, token)?;
+        let bonded_denom = deps.querier.query_bonded_denom()?;
+        match token {
+            CheckedTokenInfo::Native { denom, .. } if *denom == bonded_denom => {
+                let tip_pool = TIP_POOL.may_load(deps.storage)?.unwrap_or_default();
+                Ok(balance.saturating_sub(tip_pool))
+            }
+            _ => Ok(balance),
+        }
+    }
+
+    // sends the token amount to the recipient and updates the deposited amount and the ledger
+    fn withdraw_msg(
+        storage: &mut dyn Storage,
+        env: &Env,
+        token: CheckedTokenInfo,
+        recipient: &str,
+    ) -> StdResult<CosmosMsg> {
+        let amount = token.amount();
+        if let Some(tracked_token) = ALLOWED_TOKENS.may_load(storage, token.key())? {
+            let deposited = tracked_token.amount().saturating_sub(amount);
+            ALLOWED_TOKENS.save(storage, token.key(), &tracked_token.with_amount(deposited))?;
+        }
+        record_ledger_entry(
+            storage,
+            env,
+            LedgerAction::Withdraw {
+                token: token.clone(),
+                recipient: recipient.to_string(),
+            },
+        )?;
+
+        let msg: CosmosMsg = match token {
+            CheckedTokenInfo::Native { denom, .. } => BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![Coin { denom, amount }],
+            }
+            .into(),
+            CheckedTokenInfo::Cw20 { contract_addr, .. } => WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.to_string(),
+                    amount,
+                })?,
+                funds: vec![],
+            }
+            .into(),
+        };
+        Ok(msg)
+    }
+
+    fn validate_boss_or_guardian(
+        deps: Deps,
+        info: &MessageInfo,
+        policy: Option<&WithdrawalPolicy>,
+    ) -> Result<(), ContractError> {
+        let is_guardian = policy
+            .map(|policy| policy.guardians.contains(&info.sender.to_string()))
+            .unwrap_or(false);
+        if is_guardian {
+            return Ok(());
+        }
+        validate_boss(deps, info)
+    }
+
+    fn check_token(deps: Deps, token: TokenInfo) -> Result<CheckedTokenInfo, ContractError> {
+        match token {
+            TokenInfo::Native { denom } => Ok(CheckedTokenInfo::Native {
+                denom,
+                amount: Uint128::zero(),
+            }),
+            TokenInfo::Cw20 { contract_addr } => Ok(CheckedTokenInfo::Cw20 {
+                contract_addr: deps
+                    .api
+                    .addr_validate(&contract_addr)
+                    .map_err(|_| ContractError::InvalidAddr {})?,
+                amount: Uint128::zero(),
+            }),
+        }
+    }
+
+    // validator addresses use the valoper prefix, so instead of addr_validate we make sure
+    // the validator exists and respects the commission cap and allow list held by the assembly
+    fn validate_validator(deps: Deps, validator_addr: &str) -> Result<Validator, ContractError> {
+        let validator = deps
+            .querier
+            .query_validator(validator_addr)?
+            .ok_or(ContractError::ValidatorNotFound {})?;
+
+        let policy: GetValidatorPolicyResponse = deps.querier.query_wasm_smart(
+            ASSEMBLY_ADDR.load(deps.storage)?,
+            &CommonQueryMsg::GetValidatorPolicy {
+                validator_addr: validator.address.clone(),
+            },
+        )?;
+
+        if !policy.is_allowed {
+            return Err(ContractError::ValidatorNotAllowed {});
+        }
+
+        if let Some(max_commission) = policy.max_commission {
+            if validator.commission > max_commission {
+                return Err(ContractError::ValidatorCommissionTooHigh {});
+            }
+        }
+
+        Ok(validator)
+    }
+
+    fn validate_assembly_call(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+        let assembly_addr = ASSEMBLY_ADDR.load(deps.storage)?;
+        if info.sender != assembly_addr {
+            return Err(ContractError::Unauthorized {});
+        }
+        Ok(())
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetUnbondings { start_after, limit } => {
+            query::get_unbondings(deps, start_after, limit)
+        }
+        QueryMsg::GetRewardConfig {} => query::get_reward_config(deps),
+        QueryMsg::GetDelegationSchedule {} => query::get_delegation_schedule(deps),
+        QueryMsg::GetTokenBalances { start_after, limit } => {
+            query::get_token_balances(deps, env, start_after, limit)
+        }
+        QueryMsg::GetJointOwnership {} => query::get_joint_ownership(deps),
+        QueryMsg::GetWithdrawalPolicy {} => query::get_withdrawal_policy(deps, env),
+        QueryMsg::GetLedger { start_after, limit } => query::get_ledger(deps, start_after, limit),
+        QueryMsg::GetValidatorTotals { start_after, limit } => {
+            query::get_validator_totals(deps, start_after, limit)
+        }
+        QueryMsg::GetVesting {} => query::get_vesting(deps, env),
+        QueryMsg::GetPendingWithdrawals { start_after, limit } => {
+            query::get_pending_withdrawals(deps, start_after, limit)
+        }
+        QueryMsg::GetJointProposals { start_after, limit } => {
+            query::get_joint_proposals(deps, start_after, limit)
+        }
+    }
+}
+
+pub mod query {
+    use cosmwasm_std::{Order, Uint128};
+    use cw_paginate::paginate_map_values;
+
+    use crate::{
+        helpers::{load_withdrawal_policy, query_token_balance},
+        msg::{
+            GetDelegationScheduleResponse, GetJointOwnershipResponse, GetJointProposalsResponse,
+            GetLedgerResponse, GetPendingWithdrawalsResponse, GetRewardConfigResponse,
+            GetTokenBalancesResponse, GetUnbondingsResponse, GetValidatorTotalsResponse,
+            GetVestingResponse, GetWithdrawalPolicyResponse, TokenBalance,
+        },
+        state::{
+            ALLOWED_TOKENS, DELEGATION_SCHEDULE, JOINT_OWNERSHIP, JOINT_PROPOSALS, LEDGER,
+            PENDING_PAYOUT_CHANGES, PENDING_WITHDRAWALS, REWARD_ROUTES, REWARD_WITHDRAW_ADDR,
+            TIP_POOL, UNBONDINGS, VALIDATOR_TOTALS, VESTING,
+        },
+    };
+
+    use super::*;
+
+    pub fn get_unbondings(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let unbondings =
+            paginate_map_values(deps, &UNBONDINGS, start_after, limit, Order::Ascending)?;
+
+        to_binary(&GetUnbondingsResponse { unbondings })
+    }
+
+    pub fn get_delegation_schedule(deps: Deps) -> StdResult<Binary> {
+        let schedule = DELEGATION_SCHEDULE.may_load(deps.storage)?;
+        let tip_pool = TIP_POOL.may_load(deps.storage)?.unwrap_or_default();
+
+        to_binary(&GetDelegationScheduleResponse { schedule, tip_pool })
+    }
+
+    pub fn get_token_balances(
+        deps: Deps,
+        env: Env,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let balances =
+            paginate_map_values(deps, &ALLOWED_TOKENS, start_after, limit, Order::Ascending)?
+                .into_iter()
+                .map(|token| {
+                    let balance =
+                        query_token_balance(&deps.querier, &env.contract.address, &token)?;
+                    Ok(TokenBalance { token, balance })
+                })
+                .collect::<StdResult<Vec<TokenBalance>>>()?;
+
+        to_binary(&GetTokenBalancesResponse { balances })
+    }
+
+    pub fn get_joint_ownership(deps: Deps) -> StdResult<Binary> {
+        let joint_ownership = JOINT_OWNERSHIP.may_load(deps.storage)?;
+
+        to_binary(&GetJointOwnershipResponse { joint_ownership })
+    }
+
+    pub fn get_joint_proposals(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let proposals =
+            paginate_map_values(deps, &JOINT_PROPOSALS, start_after, limit, Order::Ascending)?;
+
+        to_binary(&GetJointProposalsResponse { proposals })
+    }
+
+    pub fn get_ledger(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let entries = paginate_map_values(deps, &LEDGER, start_after, limit, Order::Ascending)?;
+
+        to_binary(&GetLedgerResponse { entries })
+    }
+
+    pub fn get_validator_totals(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let totals = paginate_map_values(
+            deps,
+            &VALIDATOR_TOTALS,
+            start_after,
+            limit,
+            Order::Ascending,
+        )?;
+
+        to_binary(&GetValidatorTotalsResponse { totals })
+    }
+
+    pub fn get_vesting(deps: Deps, env: Env) -> StdResult<Binary> {
+        let vesting = VESTING.may_load(deps.storage)?;
+        let (vested, unvested) = match &vesting {
+            Some(vesting) => (
+                vesting.vested(env.block.time),
+                vesting.unvested(env.block.time),
+            ),
+            None => (Uint128::zero(), Uint128::zero()),
+        };
+
+        to_binary(&GetVestingResponse {
+            vesting,
+            vested,
+            unvested,
+        })
+    }
+
+    pub fn get_withdrawal_policy(deps: Deps, env: Env) -> StdResult<Binary> {
+        let (policy, pending_change) = load_withdrawal_policy(deps.storage, env.block.time)?;
+
+        let pending_payout_changes = PENDING_PAYOUT_CHANGES
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, pending)| pending))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        to_binary(&GetWithdrawalPolicyResponse {
+            policy,
+            pending_change,
+            pending_payout_changes,
+        })
+    }
+
+    pub fn get_pending_withdrawals(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Binary> {
+        let withdrawals = paginate_map_values(
+            deps,
+            &PENDING_WITHDRAWALS,
+            start_after,
+            limit,
+            Order::Ascending,
+        )?;
+
+        to_binary(&GetPendingWithdrawalsResponse { withdrawals })
+    }
+
+    pub fn get_reward_config(deps: Deps) -> StdResult<Binary> {
+        let withdraw_addr = REWARD_WITHDRAW_ADDR.may_load(deps.storage)?;
+        let routes = REWARD_ROUTES.may_load(deps.storage)?.unwrap_or_default();
+
+        to_binary(&GetRewardConfigResponse {
+            withdraw_addr,
+            routes,
+        })
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        UNDELEGATION_REPLY_ID => reply::unregister_vote_if_last_delegation(deps, env, msg),
+        REWARDS_REPLY_ID => reply::record_rewards(deps, env, msg),
+        _ => Err(ContractError::UnknownReplyIdCommon {}),
+    }
+}
+
+pub mod reply {
+    use cosmwasm_std::{Storage, SubMsgResult, WasmMsg};
+    use util_types::ExecuteMsg as CommonExecuteMsg;
+
+    use crate::helpers::parse_coins;
+    use crate::state::{LedgerAction, ASSEMBLY_ADDR};
+
+    use super::execute::{record_ledger_entry, sync_stake_msg};
+
+    use super::*;
+
+    pub fn record_rewards(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+        let n_of_rewards = record_paid_rewards(deps.storage, &env, msg)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "record_rewards")
+            .add_attribute("n_of_rewards", n_of_rewards.to_string()))
+    }
+
+    // the distribution module pays pending rewards on claims and on any delegation change,
+    // the withdraw_rewards events of the reply tell how much
+    fn record_paid_rewards(storage: &mut dyn Storage, env: &Env, msg: Reply) -> StdResult<usize> {
+        let events = match msg.result {
+            SubMsgResult::Ok(response) => response.events,
+            SubMsgResult::Err(_) => vec![],
+        };
+
+        let mut n_of_rewards = 0;
+        for event in events.iter().filter(|event| event.ty == "withdraw_rewards") {
+            let attribute = |key: &str| {
+                event
+                    .attributes
+                    .iter()
+                    .find(|attribute| attribute.key == key)
+                    .map(|attribute| attribute.value.clone())
+            };
+            let (validator, amount) = match (attribute("validator"), attribute("amount")) {
+                (Some(validator), Some(amount)) => (validator, parse_coins(&amount)),
+                _ => continue,
+            };
+            if amount.is_empty() {
+                continue;
+            }
+
+            record_ledger_entry(storage, env, LedgerAction::Rewards { validator, amount })?;
+            n_of_rewards += 1;
+        }
+        Ok(n_of_rewards)
+    }
+
+    pub fn unregister_vote_if_last_delegation(
+        deps: DepsMut,
+        env: Env,
+        msg: Reply,
+    ) -> Result<Response, ContractError> {
+        // undelegating pays out the pending rewards too
+        record_paid_rewards(deps.storage, &env, msg)?;
+
+        let delegations = deps.querier.query_all_delegations(env.contract.address)?;
+
+        // if there is no bonded stake left, send message to Assembly to unregister the user account
+        if delegations
+            .iter()
+            .all(|delegation| delegation.amount.amount.is_zero())
+        {
+            // create message to the Assembly
+            let msg = WasmMsg::Execute {
+                contract_addr: ASSEMBLY_ADDR.load(deps.storage)?,
+                msg: to_binary(&CommonExecuteMsg::UnregisterUserAccount {})?,
+                funds: vec![],
+            };
+
+            Ok(Response::new()
+                .add_attribute("action", "unregister_vote_on_reply")
+                .add_message(msg))
+        } else {
+            Ok(Response::new()
+                .add_attribute("action", "sync_stake_on_reply")
+                .add_message(sync_stake_msg(deps.storage)?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::marker::PhantomData;
+
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{
+        coin, coins, from_binary, from_slice, BankMsg, ContractInfoResponse, ContractResult,
+        CosmosMsg, Decimal, DistributionMsg, Empty, Event, FullDelegation, GovMsg, OwnedDeps,
+        Querier, QuerierResult, QueryRequest, StakingMsg, SubMsgResponse, SubMsgResult,
+        SystemResult, Uint128, Validator, VoteOption, WasmMsg, WasmQuery,
+    };
+    use util_types::proto::{encode_bytes_field, encode_varint_field, STAKING_PARAMS_PATH};
+    use util_types::{
+        ExecuteMsg as CommonExecuteMsg, GetProxyAllowListResponse, GetValidatorPolicyResponse,
+        QueryMsg as CommonQueryMsg, StakeAmount, VestingParams, VestingSchedule,
+    };
+
+    use super::*;
+    use crate::msg::{
+        GetDelegationScheduleResponse, GetJointProposalsResponse, GetLedgerResponse,
+        GetPendingWithdrawalsResponse, GetTokenBalancesResponse, GetUnbondingsResponse,
+        GetValidatorTotalsResponse, GetVestingResponse, GetWithdrawalPolicyResponse, JointAction,
+        TokenInfo, WithdrawalCap,
+    };
+    use crate::state::LedgerAction;
+    use crate::state::{RewardRoute, ASSEMBLY_ADDR, BOSS_ADDR, REWARD_ROUTES, SWEEP_DESTINATION};
+
+    const BOSS: &str = "boss";
+    const ASSEMBLY: &str = "assembly";
+    const DENOM: &str = "ujuno";
+    const GOOD_VALIDATOR: &str = "junovaloper1good";
+    const GREEDY_VALIDATOR: &str = "junovaloper1greedy";
+    const OUTSIDER_VALIDATOR: &str = "junovaloper1outsider";
+    const STEADY_VALIDATOR: &str = "junovaloper1steady";
+    const UNBONDING_TIME: u64 = 1_814_400;
+    const CW20_TOKEN: &str = "cw20_token";
+    const FACTORY: &str = "factory";
+    const LIQUID_STAKING: &str = "liquid_staking";
+    const GOV_DEPOSIT_TYPE_URL: &str = "/cosmos.gov.v1beta1.MsgDeposit";
+    const BANK_SEND_TYPE_URL: &str = "/cosmos.bank.v1beta1.MsgSend";
+    const CW20_BALANCE: u128 = 500;
+
+    // MockQuerier doesn't support stargate queries, so the staking params are answered here
+    struct TestQuerier {
+        base: MockQuerier,
+    }
+
+    impl Querier for TestQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            match from_slice::<QueryRequest<Empty>>(bin_request).unwrap() {
+                QueryRequest::Stargate { path, .. } if path == STAKING_PARAMS_PATH => {
+                    let duration = encode_varint_field(1, UNBONDING_TIME);
+                    let params = encode_bytes_field(1, &duration);
+                    SystemResult::Ok(ContractResult::Ok(encode_bytes_field(1, &params).into()))
+                }
+                _ => self.base.raw_query(bin_request),
+            }
+        }
+    }
+
+    fn attr(res: &Response, key: &str) -> String {
+        res.attributes
+            .iter()
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.clone())
+            .unwrap_or_else(|| panic!("missing attribute {}", key))
+    }
+
+    fn validator(address: &str, commission: u64) -> Validator {
+        Validator {
+            address: address.to_string(),
+            commission: Decimal::percent(commission),
+            max_commission: Decimal::percent(100),
+            max_change_rate: Decimal::percent(1),
+        }
+    }
+
+    // user account with a boss and an assembly capping commission at 10% and allowing
+    // GOOD_VALIDATOR, STEADY_VALIDATOR and GREEDY_VALIDATOR
+    fn setup() -> OwnedDeps<MockStorage, MockApi, TestQuerier> {
+        let contract_addr = mock_env().contract.address;
+        let mut querier = MockQuerier::new(&[(contract_addr.as_str(), &coins(1000, DENOM))]);
+
+        querier.update_staking(
+            DENOM,
+            &[
+                validator(GOOD_VALIDATOR, 5),
+                validator(GREEDY_VALIDATOR, 20),
+                validator(OUTSIDER_VALIDATOR, 5),
+                validator(STEADY_VALIDATOR, 8),
+            ],
+            &[FullDelegation {
+                delegator: mock_env().contract.address,
+                validator: GOOD_VALIDATOR.to_string(),
+                amount: coin(100, DENOM),
+                can_redelegate: coin(60, DENOM),
+                accumulated_rewards: coins(50, DENOM),
+            }],
+        );
+        querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == ASSEMBLY => {
+                let validator_addr = match from_slice(msg).unwrap() {
+                    CommonQueryMsg::GetValidatorPolicy { validator_addr } => validator_addr,
+                    _ => unimplemented!(),
+                };
+                let response = GetValidatorPolicyResponse {
+                    max_commission: Some(Decimal::percent(10)),
+                    is_allowed: [GOOD_VALIDATOR, STEADY_VALIDATOR, GREEDY_VALIDATOR]
+                        .contains(&validator_addr.as_str()),
+                };
+                SystemResult::Ok(to_binary(&response).into())
+            }
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == FACTORY => {
+                let response = GetProxyAllowListResponse {
+                    type_urls: vec![
+                        GOV_DEPOSIT_TYPE_URL.to_string(),
+                        AUTHZ_GRANT_TYPE_URL.to_string(),
+                        BANK_SEND_TYPE_URL.to_string(),
+                    ],
+                    contracts: vec![LIQUID_STAKING.to_string()],
+                };
+                SystemResult::Ok(to_binary(&response).into())
+            }
+            WasmQuery::ContractInfo { .. } => {
+                let response = ContractInfoResponse::new(1, FACTORY);
+                SystemResult::Ok(to_binary(&response).into())
+            }
+            // every other contract is a cw20 where the account holds CW20_BALANCE
+            WasmQuery::Smart { .. } => {
+                let response = cw20::BalanceResponse {
+                    balance: Uint128::new(CW20_BALANCE),
+                };
+                SystemResult::Ok(to_binary(&response).into())
+            }
+            _ => unimplemented!(),
+        });
+
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: TestQuerier { base: querier },
+            custom_query_type: PhantomData,
+        };
+        BOSS_ADDR
+            .save(deps.as_mut().storage, &BOSS.to_string())
+            .unwrap();
+        ASSEMBLY_ADDR
+            .save(deps.as_mut().storage, &ASSEMBLY.to_string())
+            .unwrap();
+
+        deps
+    }
+
+    #[test]
+    fn delegate_checks_validator() {
+        let mut deps = setup();
+        let info = mock_info(BOSS, &coins(100, DENOM));
+
+        // unknown validator
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::Delegate {
+                validator_addr: "junovaloper1nowhere".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidatorNotFound {}));
+
+        // validator outside the allow list
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::Delegate {
+                validator_addr: OUTSIDER_VALIDATOR.to_string(),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidatorNotAllowed {}));
+
+        // allowed validator charging more than the cap
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::Delegate {
+                validator_addr: GREEDY_VALIDATOR.to_string(),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidatorCommissionTooHigh {}));
+
+        // redelegations are checked against the same policy
+        let redelegate_to = |deps: DepsMut, to_validator_addr: &str| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(BOSS, &[]),
+                ExecuteMsg::Redelagate {
+                    from_validator_addr: GOOD_VALIDATOR.to_string(),
+                    to_validator_addr: to_validator_addr.to_string(),
+                    amount: StakeAmount::Max {},
+                },
+            )
+            .unwrap_err()
+        };
+        assert!(matches!(
+            redelegate_to(deps.as_mut(), "junovaloper1nowhere"),
+            ContractError::ValidatorNotFound {}
+        ));
+        assert!(matches!(
+            redelegate_to(deps.as_mut(), OUTSIDER_VALIDATOR),
+            ContractError::ValidatorNotAllowed {}
+        ));
+        assert!(matches!(
+            redelegate_to(deps.as_mut(), GREEDY_VALIDATOR),
+            ContractError::ValidatorCommissionTooHigh {}
+        ));
+
+        // allowed validator
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::Delegate {
+                validator_addr: GOOD_VALIDATOR.to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASSEMBLY.to_string(),
+                msg: to_binary(&CommonExecuteMsg::SyncAccountStake {}).unwrap(),
+                funds: vec![],
+            })
+        );
+
+        // the first delegation also registers the account in the assembly
+        deps.querier
+            .base
+            .update_staking(DENOM, &[validator(GOOD_VALIDATOR, 5)], &[]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Delegate {
+                validator_addr: GOOD_VALIDATOR.to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASSEMBLY.to_string(),
+                msg: to_binary(&CommonExecuteMsg::RegisterUserAccount {}).unwrap(),
+                funds: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn undelegate_and_redelegate_requested_amounts() {
+        let mut deps = setup();
+        let info = mock_info(BOSS, &[]);
+
+        let undelegate = |deps: DepsMut, amount| {
+            execute(
+                deps,
+                mock_env(),
+                info.clone(),
+                ExecuteMsg::Undelegate {
+                    amount,
+                    validator_addr: GOOD_VALIDATOR.to_string(),
+                },
+            )
+        };
+
+        // partial undelegation uses the requested amount
+        let res = undelegate(deps.as_mut(), StakeAmount::Exact(Uint128::new(40))).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Staking(StakingMsg::Undelegate {
+                validator: GOOD_VALIDATOR.to_string(),
+                amount: coin(40, DENOM),
+            })
+        );
+
+        // the full delegation can be requested explicitly or through Max
+        let res = undelegate(deps.as_mut(), StakeAmount::Exact(Uint128::new(100))).unwrap();
+        let res_max = undelegate(deps.as_mut(), StakeAmount::Max {}).unwrap();
+        assert_eq!(res.messages, res_max.messages);
+
+        let err = undelegate(deps.as_mut(), StakeAmount::Exact(Uint128::new(101))).unwrap_err();
+        assert!(matches!(err, ContractError::UndelegateAmountTooHigh {}));
+
+        let err = undelegate(deps.as_mut(), StakeAmount::Exact(Uint128::zero())).unwrap_err();
+        assert!(matches!(err, ContractError::ZeroAmount {}));
+
+        // redelegation is bounded by what can be redelegated right now
+        let redelegate = |deps: DepsMut, amount| {
+            execute(
+                deps,
+                mock_env(),
+                info.clone(),
+                ExecuteMsg::Redelagate {
+                    from_validator_addr: GOOD_VALIDATOR.to_string(),
+                    to_validator_addr: STEADY_VALIDATOR.to_string(),
+                    amount,
+                },
+            )
+        };
+
+        let err = redelegate(deps.as_mut(), StakeAmount::Exact(Uint128::new(61))).unwrap_err();
+        assert!(matches!(err, ContractError::RedelegateAmountTooHigh {}));
+
+        let res = redelegate(deps.as_mut(), StakeAmount::Max {}).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Staking(StakingMsg::Redelegate {
+                src_validator: GOOD_VALIDATOR.to_string(),
+                dst_validator: STEADY_VALIDATOR.to_string(),
+                amount: coin(60, DENOM),
+            })
+        );
+
+        // redelegating to the source validator is rejected
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::Redelagate {
+                from_validator_addr: GOOD_VALIDATOR.to_string(),
+                to_validator_addr: GOOD_VALIDATOR.to_string(),
+                amount: StakeAmount::Max {},
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::SameValidator {}));
+    }
+
+    #[test]
+    fn unbondings_are_tracked_and_swept() {
+        let mut deps = setup();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::Undelegate {
+                amount: StakeAmount::Exact(Uint128::new(40)),
+                validator_addr: GOOD_VALIDATOR.to_string(),
+            },
+        )
+        .unwrap();
+
+        let res: GetUnbondingsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetUnbondings {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.unbondings.len(), 1);
+        assert_eq!(res.unbondings[0].amount, coin(40, DENOM));
+        assert_eq!(
+            res.unbondings[0].completion_time,
+            mock_env().block.time.plus_seconds(UNBONDING_TIME)
+        );
+
+        // nothing to sweep while the funds are still unbonding
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::SweepMatured {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NothingToSweep {}));
+
+        // once matured, anyone can sweep the funds to the boss
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(UNBONDING_TIME);
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("anyone", &[]),
+            ExecuteMsg::SweepMatured {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: BOSS.to_string(),
+                amount: coins(40, DENOM),
+            })
+        );
+
+        let res: GetUnbondingsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetUnbondings {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(res.unbondings.is_empty());
+
+        // two more unbondings, while most of the balance is kept for the schedule tips
+        for _ in 0..2 {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(BOSS, &[]),
+                ExecuteMsg::Undelegate {
+                    amount: StakeAmount::Exact(Uint128::new(30)),
+                    validator_addr: GOOD_VALIDATOR.to_string(),
+                },
+            )
+            .unwrap();
+        }
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &coins(980, DENOM)),
+            ExecuteMsg::FundTipPool {},
+        )
+        .unwrap();
+
+        // the sweep goes a page at a time and leaves the tip pool in the account
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(UNBONDING_TIME);
+        let sweep = |deps: DepsMut, start_after| {
+            execute(
+                deps,
+                env.clone(),
+                mock_info("anyone", &[]),
+                ExecuteMsg::SweepMatured {
+                    start_after,
+                    limit: Some(1),
+                },
+            )
+        };
+        let res = sweep(deps.as_mut(), None).unwrap();
+        assert_eq!(attr(&res, "entries"), "1");
+        assert_eq!(attr(&res, "amount"), "20");
+        let last_id = attr(&res, "last_id").parse().unwrap();
+
+        let res = sweep(deps.as_mut(), Some(last_id)).unwrap();
+        assert_eq!(attr(&res, "entries"), "1");
+        let last_id = attr(&res, "last_id").parse().unwrap();
+
+        let err = sweep(deps.as_mut(), Some(last_id)).unwrap_err();
+        assert!(matches!(err, ContractError::NothingToSweep {}));
+    }
+
+    #[test]
+    fn claim_routes_reward_shares() {
+        let mut deps = setup();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::SetRewardRoutes {
+                routes: vec![
+                    RewardRoute {
+                        addr: "dear_leader".to_string(),
+                        share: Decimal::percent(10),
+                    },
+                    RewardRoute {
+                        addr: "treasury".to_string(),
+                        share: Decimal::percent(20),
+                    },
+                ],
+            },
+        )
+        .unwrap();
+
+        // routing needs the rewards to land in the user account
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::SetRewardWithdrawAddr {
+                addr: Some("cold_wallet".to_string()),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::RewardsPaidElsewhere {}));
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap();
+        let msgs = res
+            .messages
+            .into_iter()
+            .map(|sub_msg| sub_msg.msg)
+            .collect::<Vec<CosmosMsg>>();
+        assert_eq!(
+            msgs,
+            vec![
+                CosmosMsg::Distribution(DistributionMsg::WithdrawDelegatorReward {
+                    validator: GOOD_VALIDATOR.to_string(),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "dear_leader".to_string(),
+                    amount: coins(5, DENOM),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "treasury".to_string(),
+                    amount: coins(10, DENOM),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn delegation_schedule_runs_when_due() {
+        let mut deps = setup();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &coins(10, DENOM)),
+            ExecuteMsg::FundTipPool {},
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::SetDelegationSchedule {
+                amount: Uint128::new(300),
+                validators: vec![GOOD_VALIDATOR.to_string()],
+                interval: 86_400,
+                tip: Uint128::new(4),
+            },
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("cranker", &[]),
+            ExecuteMsg::ExecuteSchedule {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ScheduleNotDue {}));
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(86_400);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("cranker", &[]),
+            ExecuteMsg::ExecuteSchedule {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Staking(StakingMsg::Delegate {
+                validator: GOOD_VALIDATOR.to_string(),
+                amount: coin(300, DENOM),
+            })
+        );
+        assert_eq!(
+            res.messages[2].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "cranker".to_string(),
+                amount: coins(4, DENOM),
+            })
+        );
+
+        let res: GetDelegationScheduleResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GetDelegationSchedule {},
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.schedule.unwrap().next_execution,
+            env.block.time.plus_seconds(86_400)
+        );
+        assert_eq!(res.tip_pool, Uint128::new(6));
+
+        // paused schedules can't be executed
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::PauseDelegationSchedule {},
+        )
+        .unwrap();
+        env.block.time = env.block.time.plus_seconds(86_400);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("cranker", &[]),
+            ExecuteMsg::ExecuteSchedule {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::SchedulePaused {}));
+
+        // the tips left in the pool can't be withdrawn by the boss
+        let withdraw = |amount: u128| ExecuteMsg::Withdraw {
+            token: TokenInfo::Native {
+                denom: DENOM.to_string(),
+            },
+            amount: Uint128::new(amount),
+            recipient: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(BOSS, &[]),
+            withdraw(995),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientFunds {}));
+
+        // on resume the missed execution is due right away, not caught up
+        env.block.time = env.block.time.plus_seconds(3 * 86_400);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::ResumeDelegationSchedule {},
+        )
+        .unwrap();
+        assert_eq!(attr(&res, "next_execution"), env.block.time.to_string());
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("cranker", &[]),
+            ExecuteMsg::ExecuteSchedule {},
+        )
+        .unwrap();
+        assert_eq!(attr(&res, "tip"), "4");
+
+        // canceling drops the schedule and gives the tip pool back to the boss
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::CancelDelegationSchedule {},
+        )
+        .unwrap();
+        let res: GetDelegationScheduleResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GetDelegationSchedule {},
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.schedule, None);
+        assert_eq!(res.tip_pool, Uint128::zero());
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("cranker", &[]),
+            ExecuteMsg::ExecuteSchedule {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NoSchedule {}));
+        execute(deps.as_mut(), env, mock_info(BOSS, &[]), withdraw(1000)).unwrap();
+    }
+
+    #[test]
+    fn allowed_cw20_deposit_and_withdraw() {
+        let mut deps = setup();
+
+        let receive_msg = ExecuteMsg::Receive(cw20::Cw20ReceiveMsg {
+            sender: "airdropper".to_string(),
+            amount: Uint128::new(200),
+            msg: Binary::default(),
+        });
+
+        // tokens that are not allowed are rejected
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(CW20_TOKEN, &[]),
+            receive_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::TokenNotAllowed {}));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::UpdateAllowedTokens {
+                to_add: vec![TokenInfo::Cw20 {
+                    contract_addr: CW20_TOKEN.to_string(),
+                }],
+                to_remove: vec![],
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(CW20_TOKEN, &[]),
+            receive_msg,
+        )
+        .unwrap();
+
+        let res: GetTokenBalancesResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetTokenBalances {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.balances[0].token.amount(), Uint128::new(200));
+        assert_eq!(res.balances[0].balance, Uint128::new(CW20_BALANCE));
+
+        // the whole balance can be withdrawn, not only what was deposited
+        let withdraw = |amount: u128| ExecuteMsg::Withdraw {
+            token: TokenInfo::Cw20 {
+                contract_addr: CW20_TOKEN.to_string(),
+            },
+            amount: Uint128::new(amount),
+            recipient: Some("cold_wallet".to_string()),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            withdraw(CW20_BALANCE + 1),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientFunds {}));
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            withdraw(CW20_BALANCE),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: CW20_TOKEN.to_string(),
+                msg: to_binary(&cw20::Cw20ExecuteMsg::Transfer {
+                    recipient: "cold_wallet".to_string(),
+                    amount: Uint128::new(CW20_BALANCE),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        // the bonded denom can be deposited without being in the allow list
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("friend", &coins(25, DENOM)),
+            ExecuteMsg::Deposit {},
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("friend", &coins(25, "uatom")),
+            ExecuteMsg::Deposit {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::TokenNotAllowed {}));
+    }
+
+    #[test]
+    fn execute_filters_proxied_msgs() {
+        let mut deps = setup();
+
+        let proxy = |deps: DepsMut, msg: CosmosMsg| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(BOSS, &[]),
+                ExecuteMsg::Execute { msgs: vec![msg] },
+            )
+        };
+
+        // allowed type urls and contracts go through
+        proxy(
+            deps.as_mut(),
+            CosmosMsg::Stargate {
+                type_url: GOV_DEPOSIT_TYPE_URL.to_string(),
+                value: Binary::default(),
+            },
+        )
+        .unwrap();
+        proxy(
+            deps.as_mut(),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: LIQUID_STAKING.to_string(),
+                msg: Binary::default(),
+                funds: vec![],
+            }),
+        )
+        .unwrap();
+
+        let err = proxy(
+            deps.as_mut(),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "random_contract".to_string(),
+                msg: Binary::default(),
+                funds: vec![],
+            }),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::MsgNotAllowed {}));
+
+        // votes never bypass the assembly
+        let err = proxy(
+            deps.as_mut(),
+            CosmosMsg::Gov(GovMsg::Vote {
+                proposal_id: 1,
+                vote: VoteOption::Yes,
+            }),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::VoteBypass {}));
+
+        // neither do grants letting someone else vote, even if grants are allowed
+        let generic_authorization = encode_bytes_field(1, VOTE_TYPE_URLS[0].as_bytes());
+        let mut authorization = encode_bytes_field(1, GENERIC_AUTHORIZATION_TYPE_URL.as_bytes());
+        authorization.extend(encode_bytes_field(2, &generic_authorization));
+        let grant = encode_bytes_field(1, &authorization);
+        let mut msg_grant = encode_bytes_field(1, b"granter");
+        msg_grant.extend(encode_bytes_field(2, b"grantee"));
+        msg_grant.extend(encode_bytes_field(3, &grant));
+        let err = proxy(
+            deps.as_mut(),
+            CosmosMsg::Stargate {
+                type_url: AUTHZ_GRANT_TYPE_URL.to_string(),
+                value: msg_grant.into(),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::VoteBypass {}));
+    }
+
+    #[test]
+    fn joint_actions_need_threshold_approvals() {
+        let mut deps = setup();
+
+        // only the boss can enable joint ownership, with a reachable threshold
+        let enable_msg = |threshold| ExecuteMsg::EnableJointOwnership {
+            owners: vec!["alice".to_string(), "bob".to_string(), "carol".to_string()],
+            threshold,
+            proposal_duration: 3600,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            enable_msg(2),
+        )
+        .unwrap_err();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            enable_msg(4),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidThreshold {}));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            enable_msg(2),
+        )
+        .unwrap();
+
+        // the boss alone can't move funds or change where they go anymore
+        let undelegate = JointAction::Msg {
+            msg: Box::new(ExecuteMsg::Undelegate {
+                amount: StakeAmount::Exact(Uint128::new(40)),
+                validator_addr: GOOD_VALIDATOR.to_string(),
+            }),
+        };
+        let guarded_msgs = vec![
+            ExecuteMsg::Undelegate {
+                amount: StakeAmount::Exact(Uint128::new(40)),
+                validator_addr: GOOD_VALIDATOR.to_string(),
+            },
+            ExecuteMsg::SetSweepDestination {
+                destination: Some("thief".to_string()),
+            },
+            ExecuteMsg::SetRewardWithdrawAddr {
+                addr: Some("thief".to_string()),
+            },
+            ExecuteMsg::SetRewardRoutes {
+                routes: vec![RewardRoute {
+                    addr: "thief".to_string(),
+                    share: Decimal::one(),
+                }],
+            },
+            ExecuteMsg::UpdateAllowedTokens {
+                to_add: vec![],
+                to_remove: vec![TokenInfo::Native {
+                    denom: DENOM.to_string(),
+                }],
+            },
+            ExecuteMsg::SetWithdrawalPolicy {
+                caps: vec![],
+                period: 1,
+                delay: 1,
+                guardians: vec![],
+            },
+            ExecuteMsg::RemoveWithdrawalPolicy {},
+            ExecuteMsg::SetDelegationSchedule {
+                amount: Uint128::new(100),
+                validators: vec![GOOD_VALIDATOR.to_string()],
+                interval: 3600,
+                tip: Uint128::zero(),
+            },
+            ExecuteMsg::CancelDelegationSchedule {},
+            ExecuteMsg::Vote {
+                proposal_id: 1,
+                vote_option: 1,
+            },
+        ];
+        for msg in guarded_msgs {
+            let err = execute(deps.as_mut(), mock_env(), mock_info(BOSS, &[]), msg).unwrap_err();
+            assert!(matches!(err, ContractError::JointApprovalRequired {}));
+        }
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::ProposeJointAction { action: undelegate },
+        )
+        .unwrap();
+
+        let execute_proposal = |deps: DepsMut, sender| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                ExecuteMsg::ExecuteJointAction { proposal_id: 0 },
+            )
+        };
+        let err = execute_proposal(deps.as_mut(), "alice").unwrap_err();
+        assert!(matches!(err, ContractError::ThresholdNotReached {}));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            ExecuteMsg::ApproveJointAction { proposal_id: 0 },
+        )
+        .unwrap();
+        let res = execute_proposal(deps.as_mut(), "bob").unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Staking(StakingMsg::Undelegate {
+                validator: GOOD_VALIDATOR.to_string(),
+                amount: coin(40, DENOM),
+            })
+        );
+
+        // executed proposals are gone
+        let proposals: GetJointProposalsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetJointProposals {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(proposals.proposals.is_empty());
+    }
+
+    // alice and bob must both approve
+    fn joint_setup() -> OwnedDeps<MockStorage, MockApi, TestQuerier> {
+        let mut deps = setup();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::EnableJointOwnership {
+                owners: vec!["alice".to_string(), "bob".to_string()],
+                threshold: 2,
+                proposal_duration: 3600,
+            },
+        )
+        .unwrap();
+        deps
+    }
+
+    #[test]
+    fn joint_set_sweep_destination() {
+        // it runs once both owners approve, msgs that don't need approval can't be proposed
+        let mut deps = joint_setup();
+        let propose = |deps: DepsMut, msg| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info("alice", &[]),
+                ExecuteMsg::ProposeJointAction {
+                    action: JointAction::Msg { msg: Box::new(msg) },
+                },
+            )
+        };
+        let err = propose(deps.as_mut(), ExecuteMsg::Deposit {}).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidJointAction {}));
+        propose(
+            deps.as_mut(),
+            ExecuteMsg::SetSweepDestination {
+                destination: Some("treasury".to_string()),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            ExecuteMsg::ApproveJointAction { proposal_id: 0 },
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            ExecuteMsg::ExecuteJointAction { proposal_id: 0 },
+        )
+        .unwrap();
+        assert_eq!(attr(&res, "action"), "set_sweep_destination");
+    }
+
+    #[test]
+    fn withdrawals_above_the_cap_are_delayed() {
+        let mut deps = setup();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::SetWithdrawalPolicy {
+                caps: vec![WithdrawalCap {
+                    token: TokenInfo::Native {
+                        denom: DENOM.to_string(),
+                    },
+                    amount: Uint128::new(100),
+                }],
+                period: 86_400,
+                delay: 3600,
+                guardians: vec!["guardian".to_string()],
+            },
+        )
+        .unwrap();
+
+        let withdraw = |deps: DepsMut, amount| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(BOSS, &[]),
+                ExecuteMsg::Withdraw {
+                    token: TokenInfo::Native {
+                        denom: DENOM.to_string(),
+                    },
+                    amount: Uint128::new(amount),
+                    recipient: None,
+                },
+            )
+            .unwrap()
+        };
+
+        // within the cap funds go out right away, above it they wait in the queue
+        let res = withdraw(deps.as_mut(), 60);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: BOSS.to_string(),
+                amount: coins(60, DENOM),
+            })
+        );
+        let res = withdraw(deps.as_mut(), 60);
+        assert!(res.messages.is_empty());
+        withdraw(deps.as_mut(), 200);
+
+        let release = |deps: DepsMut, env, withdrawal_id| {
+            execute(
+                deps,
+                env,
+                mock_info("anyone", &[]),
+                ExecuteMsg::ReleaseWithdrawal { withdrawal_id },
+            )
+        };
+        let err = release(deps.as_mut(), mock_env(), 0).unwrap_err();
+        assert!(matches!(err, ContractError::WithdrawalLocked {}));
+
+        // guardians can cancel queued withdrawals, strangers can't
+        let cancel = |deps: DepsMut, sender| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                ExecuteMsg::CancelWithdrawal { withdrawal_id: 1 },
+            )
+        };
+        cancel(deps.as_mut(), "anyone").unwrap_err();
+        cancel(deps.as_mut(), "guardian").unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(3600);
+        let res = release(deps.as_mut(), env, 0).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: BOSS.to_string(),
+                amount: coins(60, DENOM),
+            })
+        );
+        let pending: GetPendingWithdrawalsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetPendingWithdrawals {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(pending.withdrawals.is_empty());
+
+        // removing the policy waits for the delay, so a guardian can stop it
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::RemoveWithdrawalPolicy {},
+        )
+        .unwrap();
+        let policy: GetWithdrawalPolicyResponse = from_binary(
+            &query(deps.as_ref(), mock_env(), QueryMsg::GetWithdrawalPolicy {}).unwrap(),
+        )
+        .unwrap();
+        assert!(policy.policy.is_some());
+        assert_eq!(policy.pending_change.unwrap().policy, None);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("guardian", &[]),
+            ExecuteMsg::CancelWithdrawalPolicyChange {},
+        )
+        .unwrap();
+        let res = withdraw(deps.as_mut(), 50);
+        assert!(res.messages.is_empty());
+    }
+
+    // 100 DENOM per day, anything above waits an hour
+    fn capped_setup() -> OwnedDeps<MockStorage, MockApi, TestQuerier> {
+        let mut deps = setup();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::SetWithdrawalPolicy {
+                caps: vec![WithdrawalCap {
+                    token: TokenInfo::Native {
+                        denom: DENOM.to_string(),
+                    },
+                    amount: Uint128::new(100),
+                }],
+                period: 86_400,
+                delay: 3600,
+                guardians: vec!["guardian".to_string()],
+            },
+        )
+        .unwrap();
+        deps
+    }
+
+    #[test]
+    fn payout_changes_wait_for_the_policy_delay() {
+        let mut deps = capped_setup();
+        let boss_execute =
+            |deps: DepsMut, msg| execute(deps, mock_env(), mock_info(BOSS, &[]), msg);
+        let apply = |deps: DepsMut, env| {
+            execute(
+                deps,
+                env,
+                mock_info("anyone", &[]),
+                ExecuteMsg::ApplyPayoutChanges {},
+            )
+            .unwrap()
+        };
+
+        // the sweep destination and the reward address don't move right away
+        let res = boss_execute(
+            deps.as_mut(),
+            ExecuteMsg::SetSweepDestination {
+                destination: Some("thief".to_string()),
+            },
+        )
+        .unwrap();
+        assert_eq!(attr(&res, "action"), "queue_payout_change");
+        let res = boss_execute(
+            deps.as_mut(),
+            ExecuteMsg::SetRewardWithdrawAddr {
+                addr: Some("thief".to_string()),
+            },
+        )
+        .unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(SWEEP_DESTINATION.may_load(&deps.storage).unwrap(), None);
+
+        // routes conflict with the pending reward address
+        let routes = vec![RewardRoute {
+            addr: "thief".to_string(),
+            share: Decimal::one(),
+        }];
+        let err = boss_execute(
+            deps.as_mut(),
+            ExecuteMsg::SetRewardRoutes {
+                routes: routes.clone(),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::RewardsPaidElsewhere {}));
+
+        let res = apply(deps.as_mut(), mock_env());
+        assert_eq!(attr(&res, "n_of_changes"), "0");
+
+        // a guardian can stop them while they wait
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::CancelPayoutChanges {},
+        )
+        .unwrap_err();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("guardian", &[]),
+            ExecuteMsg::CancelPayoutChanges {},
+        )
+        .unwrap();
+        let policy: GetWithdrawalPolicyResponse = from_binary(
+            &query(deps.as_ref(), mock_env(), QueryMsg::GetWithdrawalPolicy {}).unwrap(),
+        )
+        .unwrap();
+        assert!(policy.pending_payout_changes.is_empty());
+
+        // otherwise anyone applies them once the delay passed
+        boss_execute(
+            deps.as_mut(),
+            ExecuteMsg::SetSweepDestination {
+                destination: Some("treasury".to_string()),
+            },
+        )
+        .unwrap();
+        boss_execute(deps.as_mut(), ExecuteMsg::SetRewardRoutes { routes }).unwrap();
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(3600);
+        let res = apply(deps.as_mut(), env);
+        assert_eq!(attr(&res, "n_of_changes"), "2");
+        assert_eq!(
+            SWEEP_DESTINATION.load(&deps.storage).unwrap(),
+            "treasury".to_string()
+        );
+        assert_eq!(REWARD_ROUTES.load(&deps.storage).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn proxied_funds_count_against_the_cap() {
+        let mut deps = capped_setup();
+        let proxy = |deps: DepsMut, amount| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(BOSS, &[]),
+                ExecuteMsg::Execute {
+                    msgs: vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                        contract_addr: LIQUID_STAKING.to_string(),
+                        msg: Binary::default(),
+                        funds: coins(amount, DENOM),
+                    })],
+                },
+            )
+        };
+
+        proxy(deps.as_mut(), 60).unwrap();
+        let err = proxy(deps.as_mut(), 60).unwrap_err();
+        assert!(matches!(err, ContractError::WithdrawalCapExceeded {}));
+
+        // withdrawals share what is left of the cap
+        let withdraw = |deps: DepsMut, amount| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(BOSS, &[]),
+                ExecuteMsg::Withdraw {
+                    token: TokenInfo::Native {
+                        denom: DENOM.to_string(),
+                    },
+                    amount: Uint128::new(amount),
+                    recipient: None,
+                },
+            )
+            .unwrap()
+        };
+        let res = withdraw(deps.as_mut(), 40);
+        assert_eq!(attr(&res, "action"), "withdraw");
+        let res = withdraw(deps.as_mut(), 1);
+        assert_eq!(attr(&res, "action"), "queue_withdrawal");
+
+        // allowed stargate msgs could send anything past the cap
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::Execute {
+                msgs: vec![CosmosMsg::Stargate {
+                    type_url: BANK_SEND_TYPE_URL.to_string(),
+                    value: Binary::default(),
+                }],
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::WithdrawalPolicyActive {}));
+    }
+
+    #[test]
+    fn vesting_keeps_unvested_funds_staked() {
+        let mut deps = setup();
+        let start = mock_env().block.time;
+        let at = |seconds| {
+            let mut env = mock_env();
+            env.block.time = start.plus_seconds(seconds);
+            env
+        };
+
+        // the grantor funds the account through the factory, the grant is staked right away
+        let res = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(FACTORY, &coins(1000, DENOM)),
+            InstantiateMsg::InstantiateUserAccountMsg {
+                boss_addr: BOSS.to_string(),
+                assembly_addr: ASSEMBLY.to_string(),
+                vesting: Some(VestingParams {
+                    grantor: "grantor".to_string(),
+                    schedule: VestingSchedule::CliffLinear {
+                        total: Uint128::new(1000),
+                        start,
+                        cliff: start.plus_seconds(100),
+                        end: start.plus_seconds(1000),
+                    },
+                    validator_addr: GOOD_VALIDATOR.to_string(),
+                    clawback: true,
+                }),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Staking(StakingMsg::Delegate {
+                validator: GOOD_VALIDATOR.to_string(),
+                amount: coin(1000, DENOM),
+            })
+        );
+
+        // holdings are 1000 liquid and 100 staked in the mock, 1000 is still unvested
+        let withdraw = |deps: DepsMut, amount| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(BOSS, &[]),
+                ExecuteMsg::Withdraw {
+                    token: TokenInfo::Native {
+                        denom: DENOM.to_string(),
+                    },
+                    amount: Uint128::new(amount),
+                    recipient: None,
+                },
+            )
+        };
+        let err = withdraw(deps.as_mut(), 200).unwrap_err();
+        assert!(matches!(err, ContractError::VestingLocked {}));
+        withdraw(deps.as_mut(), 100).unwrap();
+
+        let undelegate_all = |deps: DepsMut, env| {
+            execute(
+                deps,
+                env,
+                mock_info(BOSS, &[]),
+                ExecuteMsg::UndelegateAll {},
+            )
+        };
+        let err = undelegate_all(deps.as_mut(), at(500)).unwrap_err();
+        assert!(matches!(err, ContractError::VestingLocked {}));
+
+        // the assembly gets the stake kept staked once the undelegations ran
+        let res = undelegate_all(deps.as_mut(), at(950)).unwrap();
+        assert_eq!(attr(&res, "kept_staked"), "50");
+        assert_eq!(res.messages[0].id, UNDELEGATION_REPLY_ID);
+        let res = reply(
+            deps.as_mut(),
+            at(950),
+            Reply {
+                id: UNDELEGATION_REPLY_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASSEMBLY.to_string(),
+                msg: to_binary(&CommonExecuteMsg::SyncAccountStake {}).unwrap(),
+                funds: vec![],
+            })
+        );
+        undelegate_all(deps.as_mut(), at(1000)).unwrap();
+
+        // proxied stargate msgs can't be checked, they wait for the vesting to end
+        let stargate = |deps: DepsMut, env| {
+            execute(
+                deps,
+                env,
+                mock_info(BOSS, &[]),
+                ExecuteMsg::Execute {
+                    msgs: vec![CosmosMsg::Stargate {
+                        type_url: GOV_DEPOSIT_TYPE_URL.to_string(),
+                        value: Binary::default(),
+                    }],
+                },
+            )
+        };
+        let err = stargate(deps.as_mut(), at(500)).unwrap_err();
+        assert!(matches!(err, ContractError::VestingLocked {}));
+        stargate(deps.as_mut(), at(1000)).unwrap();
+
+        let vesting: GetVestingResponse =
+            from_binary(&query(deps.as_ref(), at(500), QueryMsg::GetVesting {}).unwrap()).unwrap();
+        assert_eq!(vesting.vested, Uint128::new(500));
+        assert_eq!(vesting.unvested, Uint128::new(500));
+
+        // only the grantor can claw back, and only once
+        let clawback = |deps: DepsMut, sender| {
+            execute(
+                deps,
+                at(500),
+                mock_info(sender, &[]),
+                ExecuteMsg::Clawback {},
+            )
+        };
+        clawback(deps.as_mut(), BOSS).unwrap_err();
+        let res = clawback(deps.as_mut(), "grantor").unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "grantor".to_string(),
+                amount: coins(500, DENOM),
+            })
+        );
+        let err = clawback(deps.as_mut(), "grantor").unwrap_err();
+        assert!(matches!(err, ContractError::ClawbackDisabled {}));
+
+        // vesting stopped at the clawback
+        let vesting: GetVestingResponse =
+            from_binary(&query(deps.as_ref(), at(1000), QueryMsg::GetVesting {}).unwrap()).unwrap();
+        assert_eq!(vesting.vested, Uint128::new(500));
+        assert_eq!(vesting.unvested, Uint128::zero());
+    }
+
+    #[test]
+    fn ledger_records_staking_rewards_and_withdrawals() {
+        let mut deps = setup();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &coins(200, DENOM)),
+            ExecuteMsg::Delegate {
+                validator_addr: GOOD_VALIDATOR.to_string(),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::Undelegate {
+                amount: StakeAmount::Exact(Uint128::new(40)),
+                validator_addr: GOOD_VALIDATOR.to_string(),
+            },
+        )
+        .unwrap();
+
+        // claimed amounts come from the distribution events of the replies
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap();
+        assert_eq!(res.messages[0].id, REWARDS_REPLY_ID);
+        reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: REWARDS_REPLY_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![Event::new("withdraw_rewards")
+                        .add_attribute("amount", "50ujuno")
+                        .add_attribute("validator", GOOD_VALIDATOR)],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::Withdraw {
+                token: TokenInfo::Native {
+                    denom: DENOM.to_string(),
+                },
+                amount: Uint128::new(10),
+                recipient: None,
+            },
+        )
+        .unwrap();
+
+        let ledger: GetLedgerResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetLedger {
+                    start_after: Some(1),
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(ledger.entries.len(), 2);
+        assert_eq!(
+            ledger.entries[0].action,
+            LedgerAction::Rewards {
+                validator: GOOD_VALIDATOR.to_string(),
+                amount: coins(50, DENOM),
+            }
+        );
+
+        let totals: GetValidatorTotalsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetValidatorTotals {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(totals.totals.len(), 1);
+        assert_eq!(totals.totals[0].delegated, Uint128::new(200));
+        assert_eq!(totals.totals[0].undelegated, Uint128::new(40));
+        assert_eq!(totals.totals[0].rewards, coins(50, DENOM));
+
+        // undelegating everything pays out the pending rewards as well
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::UndelegateAll {},
+        )
+        .unwrap();
+        assert_eq!(res.messages[0].id, UNDELEGATION_REPLY_ID);
+        reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: UNDELEGATION_REPLY_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![Event::new("withdraw_rewards")
+                        .add_attribute("amount", "7ujuno")
+                        .add_attribute("validator", GOOD_VALIDATOR)],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap();
+        let totals: GetValidatorTotalsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetValidatorTotals {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(totals.totals[0].rewards, coins(57, DENOM));
+    }
+
+    #[test]
+    fn stake_changes_are_synced_to_the_assembly() {
+        let mut deps = setup();
+        let sync_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ASSEMBLY.to_string(),
+            msg: to_binary(&CommonExecuteMsg::SyncAccountStake {}).unwrap(),
+            funds: vec![],
+        });
+
+        // anyone can sync, e.g. after a slash
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::SyncStake {},
+        )
+        .unwrap();
+        assert_eq!(attr(&res, "stake"), "100");
+        assert_eq!(res.messages[0].msg, sync_msg);
+
+        // staking actions sync after their staking msgs ran
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOSS, &[]),
+            ExecuteMsg::Redelagate {
+                from_validator_addr: GOOD_VALIDATOR.to_string(),
+                to_validator_addr: STEADY_VALIDATOR.to_string(),
+                amount: StakeAmount::Exact(Uint128::new(10)),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.messages.last().unwrap().msg, sync_msg);
+
+        // undelegations sync from the reply, unless nothing is left staked
+        let undelegation_reply = |deps: DepsMut| {
+            reply(
+                deps,
+                mock_env(),
+                Reply {
+                    id: UNDELEGATION_REPLY_ID,
+                    result: SubMsgResult::Ok(SubMsgResponse {
+                        events: vec![],
+                        data: None,
+                    }),
+                },
+            )
+            .unwrap()
+        };
+        let res = undelegation_reply(deps.as_mut());
+        assert_eq!(attr(&res, "action"), "sync_stake_on_reply");
+        assert_eq!(res.messages[0].msg, sync_msg);
+
+        deps.querier
+            .base
+            .update_staking(DENOM, &[validator(GOOD_VALIDATOR, 5)], &[]);
+        let res = undelegation_reply(deps.as_mut());
+        assert_eq!(attr(&res, "action"), "unregister_vote_on_reply");
+    }
+}
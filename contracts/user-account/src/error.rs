@@ -24,9 +24,123 @@ pub enum ContractError {
     #[error("Requested redelegation amount is too high")]
     RedelegateAmountTooHigh {},
 
+    #[error("Amount must be greater than zero")]
+    ZeroAmount {},
+
+    #[error("Source and destination validators are the same")]
+    SameValidator {},
+
+    #[error("There are no matured unbondings to sweep")]
+    NothingToSweep {},
+
+    #[error("Reward route shares must be above zero and add up to at most 100%")]
+    InvalidRewardShares {},
+
+    #[error("Rewards can only be routed when they are paid to the user account")]
+    RewardsPaidElsewhere {},
+
+    #[error("Schedule needs an amount, an interval and at least one validator")]
+    InvalidSchedule {},
+
+    #[error("No delegation schedule set")]
+    NoSchedule {},
+
+    #[error("Delegation schedule is paused")]
+    SchedulePaused {},
+
+    #[error("Delegation schedule is not due yet")]
+    ScheduleNotDue {},
+
+    #[error("Not enough liquid funds")]
+    InsufficientFunds {},
+
+    #[error("Token is not in the allowed tokens list")]
+    TokenNotAllowed {},
+
+    #[error("Message is not in the proxy allow list")]
+    MsgNotAllowed {},
+
+    #[error("Votes must go through the assembly")]
+    VoteBypass {},
+
+    #[error("This action needs the joint owners approval")]
+    JointApprovalRequired {},
+
+    #[error("Account already has joint ownership")]
+    JointOwnershipAlreadyEnabled {},
+
+    #[error("Account does not have joint ownership")]
+    NoJointOwnership {},
+
+    #[error("Threshold must be between 1 and the number of owners")]
+    InvalidThreshold {},
+
+    #[error("Joint actions only run messages that need the owners approval")]
+    InvalidJointAction {},
+
+    #[error("Joint proposal not found")]
+    JointProposalNotFound {},
+
+    #[error("Joint proposal expired")]
+    JointProposalExpired {},
+
+    #[error("Owner already approved this proposal")]
+    AlreadyApproved {},
+
+    #[error("Joint proposal has not reached the threshold")]
+    ThresholdNotReached {},
+
+    #[error("Withdrawal policy needs a period and a delay")]
+    InvalidWithdrawalPolicy {},
+
+    #[error("No withdrawal policy change pending")]
+    NoPendingPolicyChange {},
+
+    #[error("No payout change pending")]
+    NoPendingPayoutChange {},
+
+    #[error("Funds sent by the proxied messages are above the withdrawal cap")]
+    WithdrawalCapExceeded {},
+
+    #[error("Stargate messages can't be proxied while a withdrawal policy is active")]
+    WithdrawalPolicyActive {},
+
+    #[error("Pending withdrawal not found")]
+    WithdrawalNotFound {},
+
+    #[error("Pending withdrawal is still locked")]
+    WithdrawalLocked {},
+
+    #[error("Vesting needs a valid schedule and exactly its total in the bonded denom")]
+    InvalidVesting {},
+
+    #[error("Unvested funds must stay staked in the account")]
+    VestingLocked {},
+
+    #[error("Account has no vesting schedule")]
+    NoVesting {},
+
+    #[error("Vesting schedule can't be clawed back")]
+    ClawbackDisabled {},
+
+    #[error("There are no unvested funds to claw back")]
+    NothingToClawBack {},
+
+    #[error("Instantiate msg is not for a user account")]
+    InvalidInstantiateMsg {},
+
     #[error("Vote option is invalid")]
     InvalidVote {},
 
+    #[error("Validator not found")]
+    ValidatorNotFound {},
+
+    #[error("Validator is not in the allow list")]
+    ValidatorNotAllowed {},
+
+    #[error("Validator commission is above the allowed maximum")]
+    ValidatorCommissionTooHigh {},
+
     #[error("Account already exists")]
     UnknownReplyIdCommon {},
     // Add any other custom errors you like here.
@@ -1,7 +1,65 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Coin, QuerierWrapper, StdResult, Storage, Timestamp, Uint128};
+use cw20::{BalanceResponse, Cw20QueryMsg};
+
+use crate::state::{
+    CheckedTokenInfo, PendingWithdrawalPolicy, WithdrawalPolicy, PENDING_WITHDRAWAL_POLICY,
+    WITHDRAWAL_POLICY,
+};
 
 /// CwTemplateContract is a wrapper around Addr that provides a lot of helpers
 /// for working with this.
 #[cw_serde]
 pub struct CwTemplateContract(pub Addr);
+
+/// Returns the current balance the account holds of the given token.
+pub fn query_token_balance(
+    querier: &QuerierWrapper,
+    account_addr: &Addr,
+    token: &CheckedTokenInfo,
+) -> StdResult<Uint128> {
+    match token {
+        CheckedTokenInfo::Native { denom, .. } => {
+            Ok(querier.query_balance(account_addr, denom)?.amount)
+        }
+        CheckedTokenInfo::Cw20 { contract_addr, .. } => {
+            let response: BalanceResponse = querier.query_wasm_smart(
+                contract_addr,
+                &Cw20QueryMsg::Balance {
+                    address: account_addr.to_string(),
+                },
+            )?;
+            Ok(response.balance)
+        }
+    }
+}
+
+/// Returns the withdrawal policy in effect at `now`, counting a pending change whose delay
+/// passed, and the change still waiting, if any.
+pub fn load_withdrawal_policy(
+    storage: &dyn Storage,
+    now: Timestamp,
+) -> StdResult<(Option<WithdrawalPolicy>, Option<PendingWithdrawalPolicy>)> {
+    let policy = WITHDRAWAL_POLICY.may_load(storage)?;
+    match PENDING_WITHDRAWAL_POLICY.may_load(storage)? {
+        Some(pending) if pending.effective_time <= now => Ok((pending.policy, None)),
+        pending => Ok((policy, pending)),
+    }
+}
+
+/// Parses a coins string from sdk events, like "50ujuno,3uatom". Anything unparsable is skipped.
+pub fn parse_coins(coins: &str) -> Vec<Coin> {
+    coins
+        .split(',')
+        .filter_map(|coin| {
+            let coin = coin.trim();
+            let denom_start = coin.find(|c: char| !c.is_ascii_digit())?;
+            let (amount, denom) = coin.split_at(denom_start);
+            Some(Coin {
+                denom: denom.to_string(),
+                amount: amount.parse::<u128>().ok()?.into(),
+            })
+        })
+        .filter(|coin| !coin.amount.is_zero())
+        .collect()
+}
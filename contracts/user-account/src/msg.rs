@@ -1,10 +1,15 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{CosmosMsg, Uint128};
+use cw20::Cw20ReceiveMsg;
+use util_types::{StakeAmount, VoteFallback};
 
-#[cw_serde]
-pub struct InstantiateMsg {
-    uninmp: String,
-}
+use crate::state::{
+    CheckedTokenInfo, DelegationSchedule, JointOwnership, JointProposal, LedgerEntry,
+    PendingPayoutChange, PendingWithdrawal, PendingWithdrawalPolicy, RewardRoute, UnbondingEntry,
+    ValidatorTotals, Vesting, WithdrawalPolicy,
+};
+
+pub use util_types::InstantiateMsg;
 
 #[cw_serde]
 pub enum ExecuteMsg {
@@ -12,14 +17,14 @@ pub enum ExecuteMsg {
         validator_addr: String,
     },
     Undelegate {
-        amount: Uint128,
+        amount: StakeAmount,
         validator_addr: String,
     },
     Claim {},
     Redelagate {
         from_validator_addr: String,
         to_validator_addr: String,
-        amount: Uint128,
+        amount: StakeAmount,
     },
     UndelegateAll {},
     TransferVotePower {
@@ -34,10 +39,238 @@ pub enum ExecuteMsg {
         vote_option: u64,
     },
     Withdraw {
+        token: TokenInfo,
+        amount: Uint128,
+        recipient: Option<String>,
+    },
+    Deposit {},
+    Receive(Cw20ReceiveMsg),
+    UpdateAllowedTokens {
+        to_add: Vec<TokenInfo>,
+        to_remove: Vec<TokenInfo>,
+    },
+    Execute {
+        msgs: Vec<CosmosMsg>,
+    },
+    SweepMatured {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    SetSweepDestination {
+        destination: Option<String>,
+    },
+    SetRewardWithdrawAddr {
+        addr: Option<String>,
+    },
+    SetRewardRoutes {
+        routes: Vec<RewardRoute>,
+    },
+    ApplyPayoutChanges {},
+    CancelPayoutChanges {},
+    SetDelegationSchedule {
+        amount: Uint128,
+        validators: Vec<String>,
+        interval: u64,
+        tip: Uint128,
+    },
+    PauseDelegationSchedule {},
+    ResumeDelegationSchedule {},
+    CancelDelegationSchedule {},
+    FundTipPool {},
+    ExecuteSchedule {},
+    EnableJointOwnership {
+        owners: Vec<String>,
+        threshold: u64,
+        proposal_duration: u64,
+    },
+    ProposeJointAction {
+        action: JointAction,
+    },
+    ApproveJointAction {
+        proposal_id: u64,
+    },
+    ExecuteJointAction {
+        proposal_id: u64,
+    },
+    SetWithdrawalPolicy {
+        caps: Vec<WithdrawalCap>,
+        period: u64,
+        delay: u64,
+        guardians: Vec<String>,
+    },
+    RemoveWithdrawalPolicy {},
+    CancelWithdrawalPolicyChange {},
+    CancelWithdrawal {
+        withdrawal_id: u64,
+    },
+    ReleaseWithdrawal {
+        withdrawal_id: u64,
+    },
+    Clawback {},
+    SyncStake {},
+    OptOutOfLeaderVote {
+        proposal_id: u64,
+    },
+    SetVoteFallback {
+        fallback: VoteFallback,
+    },
+}
+
+#[cw_serde]
+pub struct WithdrawalCap {
+    pub token: TokenInfo,
+    pub amount: Uint128,
+}
+
+// actions that need the owners approval when the account has joint ownership
+#[cw_serde]
+pub enum JointAction {
+    // delegates from the account liquid balance
+    Delegate {
+        validator_addr: String,
         amount: Uint128,
     },
+    // runs any other msg that needs the approval, as if the boss sent it
+    Msg {
+        msg: Box<ExecuteMsg>,
+    },
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
-pub enum QueryMsg {}
+pub enum QueryMsg {
+    // returns the unbondings that were not swept yet
+    #[returns(GetUnbondingsResponse)]
+    GetUnbondings {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    // returns where rewards are paid to and how claims are split
+    #[returns(GetRewardConfigResponse)]
+    GetRewardConfig {},
+
+    // returns the delegation schedule, including when it is due next, and the tip pool
+    #[returns(GetDelegationScheduleResponse)]
+    GetDelegationSchedule {},
+
+    // returns the allowed tokens with the deposited amount and the current balance
+    #[returns(GetTokenBalancesResponse)]
+    GetTokenBalances {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    // returns the joint owners and threshold, if the account has joint ownership
+    #[returns(GetJointOwnershipResponse)]
+    GetJointOwnership {},
+
+    // returns the joint actions waiting for approvals or execution
+    #[returns(GetJointProposalsResponse)]
+    GetJointProposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    // returns the withdrawal policy in effect and the change waiting for its delay, if any
+    #[returns(GetWithdrawalPolicyResponse)]
+    GetWithdrawalPolicy {},
+
+    // returns the ledger of staking actions, rewards and withdrawals, oldest first
+    #[returns(GetLedgerResponse)]
+    GetLedger {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    // returns the ledger totals for each validator the account ever used
+    #[returns(GetValidatorTotalsResponse)]
+    GetValidatorTotals {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    // returns the vesting schedule with the vested and unvested amounts, if the account has one
+    #[returns(GetVestingResponse)]
+    GetVesting {},
+
+    // returns the withdrawals waiting in the queue
+    #[returns(GetPendingWithdrawalsResponse)]
+    GetPendingWithdrawals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct GetWithdrawalPolicyResponse {
+    pub policy: Option<WithdrawalPolicy>,
+    pub pending_change: Option<PendingWithdrawalPolicy>,
+    pub pending_payout_changes: Vec<PendingPayoutChange>,
+}
+
+#[cw_serde]
+pub struct GetLedgerResponse {
+    pub entries: Vec<LedgerEntry>,
+}
+
+#[cw_serde]
+pub struct GetValidatorTotalsResponse {
+    pub totals: Vec<ValidatorTotals>,
+}
+
+#[cw_serde]
+pub struct GetVestingResponse {
+    pub vesting: Option<Vesting>,
+    pub vested: Uint128,
+    pub unvested: Uint128,
+}
+
+#[cw_serde]
+pub struct GetPendingWithdrawalsResponse {
+    pub withdrawals: Vec<PendingWithdrawal>,
+}
+
+#[cw_serde]
+pub struct GetJointOwnershipResponse {
+    pub joint_ownership: Option<JointOwnership>,
+}
+
+#[cw_serde]
+pub struct GetJointProposalsResponse {
+    pub proposals: Vec<JointProposal>,
+}
+
+#[cw_serde]
+pub enum TokenInfo {
+    Native { denom: String },
+    Cw20 { contract_addr: String },
+}
+
+#[cw_serde]
+pub struct TokenBalance {
+    pub token: CheckedTokenInfo,
+    pub balance: Uint128,
+}
+
+#[cw_serde]
+pub struct GetTokenBalancesResponse {
+    pub balances: Vec<TokenBalance>,
+}
+
+#[cw_serde]
+pub struct GetUnbondingsResponse {
+    pub unbondings: Vec<UnbondingEntry>,
+}
+
+#[cw_serde]
+pub struct GetDelegationScheduleResponse {
+    pub schedule: Option<DelegationSchedule>,
+    pub tip_pool: Uint128,
+}
+
+#[cw_serde]
+pub struct GetRewardConfigResponse {
+    pub withdraw_addr: Option<String>,
+    pub routes: Vec<RewardRoute>,
+}
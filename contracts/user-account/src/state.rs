@@ -1,12 +1,73 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Coin, Decimal, Timestamp, Uint128};
+
 use cw_storage_plus::{Item, Map};
+use util_types::VestingSchedule;
+
+use crate::msg::JointAction;
 
 pub const ASSEMBLY_ADDR: Item<String> = Item::new("assembly");
 // pub const DEAR_LEADER_ADDR: Item<String> = Item::new("dear_leader");
 pub const BOSS_ADDR: Item<String> = Item::new("boss");
 
-pub const ALLOWED_TOKENS: Map<String, CheckedTokenInfo> = Map::new("proposal_vote_history");
+// where matured unbondings are swept to. If not set, they go to the boss.
+pub const SWEEP_DESTINATION: Item<String> = Item::new("sweep_destination");
+
+// unbondings created by Undelegate/UndelegateAll that were not swept yet.
+pub const UNBONDINGS: Map<u64, UnbondingEntry> = Map::new("unbondings");
+pub const NEXT_UNBONDING_ID: Item<u64> = Item::new("next_unbonding_id");
+
+// address rewards are paid to. If not set, rewards land in the user account.
+pub const REWARD_WITHDRAW_ADDR: Item<String> = Item::new("reward_withdraw_addr");
+
+// shares of each claim forwarded to other addresses, e.g. the dear leader or a protocol treasury.
+pub const REWARD_ROUTES: Item<Vec<RewardRoute>> = Item::new("reward_routes");
+
+// recurring delegation set by the boss, executed by anyone once it is due.
+pub const DELEGATION_SCHEDULE: Item<DelegationSchedule> = Item::new("delegation_schedule");
+
+// funds reserved to tip whoever executes the schedule, not available for delegation.
+pub const TIP_POOL: Item<Uint128> = Item::new("tip_pool");
+
+// when set, any msg that can move funds or change the account needs M-of-N owner approvals.
+pub const JOINT_OWNERSHIP: Item<JointOwnership> = Item::new("joint_ownership");
+
+// joint actions proposed by the owners and not executed yet.
+pub const JOINT_PROPOSALS: Map<u64, JointProposal> = Map::new("joint_proposals");
+pub const NEXT_JOINT_PROPOSAL_ID: Item<u64> = Item::new("next_joint_proposal_id");
+
+// optional caps on withdrawals, anything above the cap waits in the withdrawal queue.
+pub const WITHDRAWAL_POLICY: Item<WithdrawalPolicy> = Item::new("withdrawal_policy");
+
+// policy changes wait for the current delay, so a stolen key can't just lift the caps.
+pub const PENDING_WITHDRAWAL_POLICY: Item<PendingWithdrawalPolicy> =
+    Item::new("pending_withdrawal_policy");
+
+// amount withdrawn in the current period, keyed by denom or cw20 contract address.
+pub const WITHDRAWAL_PERIODS: Map<String, WithdrawalPeriod> = Map::new("withdrawal_periods");
+
+// sweep destination and reward address changes, waiting for the withdrawal policy delay,
+// keyed by the kind of change so a new one replaces the pending one.
+pub const PENDING_PAYOUT_CHANGES: Map<String, PendingPayoutChange> =
+    Map::new("pending_payout_changes");
+
+// withdrawals above the cap, released once their delay passes unless canceled.
+pub const PENDING_WITHDRAWALS: Map<u64, PendingWithdrawal> = Map::new("pending_withdrawals");
+pub const NEXT_WITHDRAWAL_ID: Item<u64> = Item::new("next_withdrawal_id");
+
+// append-only record of staking actions, rewards and withdrawals, for reporting.
+pub const LEDGER: Map<u64, LedgerEntry> = Map::new("ledger");
+pub const NEXT_LEDGER_ID: Item<u64> = Item::new("next_ledger_id");
+
+// ledger totals per validator, keyed by validator address.
+pub const VALIDATOR_TOTALS: Map<String, ValidatorTotals> = Map::new("validator_totals");
+
+// set at creation when a grantor funds the account with vesting tokens.
+pub const VESTING: Item<Vesting> = Item::new("vesting");
+
+// tokens the boss accepts in the account, keyed by denom or cw20 contract address,
+// with the amount deposited through Deposit/Receive and not withdrawn yet.
+pub const ALLOWED_TOKENS: Map<String, CheckedTokenInfo> = Map::new("allowed_tokens");
 
 #[cw_serde]
 pub enum CheckedTokenInfo {
@@ -19,3 +80,238 @@ pub enum CheckedTokenInfo {
         amount: Uint128,
     },
 }
+
+impl CheckedTokenInfo {
+    pub fn key(&self) -> String {
+        match self {
+            CheckedTokenInfo::Native { denom, .. } => denom.to_string(),
+            CheckedTokenInfo::Cw20 { contract_addr, .. } => contract_addr.to_string(),
+        }
+    }
+
+    pub fn amount(&self) -> Uint128 {
+        match self {
+            CheckedTokenInfo::Native { amount, .. } | CheckedTokenInfo::Cw20 { amount, .. } => {
+                *amount
+            }
+        }
+    }
+
+    pub fn with_amount(self, new_amount: Uint128) -> Self {
+        match self {
+            CheckedTokenInfo::Native { denom, .. } => CheckedTokenInfo::Native {
+                denom,
+                amount: new_amount,
+            },
+            CheckedTokenInfo::Cw20 { contract_addr, .. } => CheckedTokenInfo::Cw20 {
+                contract_addr,
+                amount: new_amount,
+            },
+        }
+    }
+}
+
+#[cw_serde]
+pub struct LedgerEntry {
+    pub id: u64,
+    pub time: Timestamp,
+    pub height: u64,
+    pub action: LedgerAction,
+}
+
+#[cw_serde]
+pub enum LedgerAction {
+    Delegate {
+        validator: String,
+        amount: Coin,
+    },
+    Undelegate {
+        validator: String,
+        amount: Coin,
+    },
+    Redelegate {
+        from_validator: String,
+        to_validator: String,
+        amount: Coin,
+    },
+    /// rewards paid by the distribution module, on claims and on delegation changes
+    Rewards {
+        validator: String,
+        amount: Vec<Coin>,
+    },
+    Withdraw {
+        /// token and amount withdrawn
+        token: CheckedTokenInfo,
+        recipient: String,
+    },
+}
+
+#[cw_serde]
+pub struct ValidatorTotals {
+    pub validator: String,
+    pub delegated: Uint128,
+    pub undelegated: Uint128,
+    pub redelegated_in: Uint128,
+    pub redelegated_out: Uint128,
+    pub rewards: Vec<Coin>,
+}
+
+impl ValidatorTotals {
+    pub fn new(validator: &str) -> Self {
+        ValidatorTotals {
+            validator: validator.to_string(),
+            delegated: Uint128::zero(),
+            undelegated: Uint128::zero(),
+            redelegated_in: Uint128::zero(),
+            redelegated_out: Uint128::zero(),
+            rewards: vec![],
+        }
+    }
+
+    pub fn add_rewards(&mut self, rewards: &[Coin]) {
+        for reward in rewards.iter() {
+            match self
+                .rewards
+                .iter_mut()
+                .find(|coin| coin.denom == reward.denom)
+            {
+                Some(coin) => coin.amount += reward.amount,
+                None => self.rewards.push(reward.clone()),
+            }
+        }
+    }
+}
+
+#[cw_serde]
+pub struct Vesting {
+    pub grantor: String,
+    pub schedule: VestingSchedule,
+    pub clawback: bool,
+    /// vesting stops at this time once the grantor claws back the unvested part
+    pub clawed_back_at: Option<Timestamp>,
+    /// clawed back funds still unbonding, paid to the grantor when swept
+    pub owed_to_grantor: Uint128,
+}
+
+impl Vesting {
+    pub fn vested(&self, time: Timestamp) -> Uint128 {
+        match self.clawed_back_at {
+            Some(clawed_back_at) => self.schedule.vested(clawed_back_at.min(time)),
+            None => self.schedule.vested(time),
+        }
+    }
+
+    /// amount that must stay staked
+    pub fn unvested(&self, time: Timestamp) -> Uint128 {
+        match self.clawed_back_at {
+            Some(_) => Uint128::zero(),
+            None => self.schedule.total() - self.vested(time),
+        }
+    }
+
+    /// amount of the bonded denom the boss can't take out of the account
+    pub fn locked(&self, time: Timestamp) -> Uint128 {
+        self.unvested(time) + self.owed_to_grantor
+    }
+}
+
+#[cw_serde]
+pub struct RewardRoute {
+    pub addr: String,
+    pub share: Decimal,
+}
+
+#[cw_serde]
+pub struct DelegationSchedule {
+    /// amount delegated on each execution, split evenly between the validators
+    pub amount: Uint128,
+    pub validators: Vec<String>,
+    /// seconds between executions
+    pub interval: u64,
+    pub next_execution: Timestamp,
+    /// paid from the tip pool to whoever executes the schedule
+    pub tip: Uint128,
+    pub paused: bool,
+}
+
+#[cw_serde]
+pub struct JointOwnership {
+    pub owners: Vec<String>,
+    pub threshold: u64,
+    /// seconds a proposal stays open for approvals and execution
+    pub proposal_duration: u64,
+}
+
+#[cw_serde]
+pub struct JointProposal {
+    pub id: u64,
+    pub action: JointAction,
+    pub proposer: String,
+    pub approvals: Vec<String>,
+    pub expires: Timestamp,
+}
+
+#[cw_serde]
+pub struct WithdrawalPolicy {
+    /// max amount of each token withdrawn per period, tokens without a cap always wait
+    pub caps: Vec<CheckedTokenInfo>,
+    /// seconds in a period
+    pub period: u64,
+    /// seconds withdrawals above the cap wait before they can be released
+    pub delay: u64,
+    /// addresses that can cancel pending withdrawals and policy changes, besides the boss
+    pub guardians: Vec<String>,
+}
+
+#[cw_serde]
+pub struct PendingWithdrawalPolicy {
+    /// None removes the policy
+    pub policy: Option<WithdrawalPolicy>,
+    pub effective_time: Timestamp,
+}
+
+#[cw_serde]
+pub enum PayoutChange {
+    SweepDestination { destination: Option<String> },
+    RewardWithdrawAddr { addr: Option<String> },
+    RewardRoutes { routes: Vec<RewardRoute> },
+}
+
+impl PayoutChange {
+    pub fn key(&self) -> String {
+        match self {
+            PayoutChange::SweepDestination { .. } => "sweep_destination".to_string(),
+            PayoutChange::RewardWithdrawAddr { .. } => "reward_withdraw_addr".to_string(),
+            PayoutChange::RewardRoutes { .. } => "reward_routes".to_string(),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct PendingPayoutChange {
+    pub change: PayoutChange,
+    pub effective_time: Timestamp,
+}
+
+#[cw_serde]
+pub struct WithdrawalPeriod {
+    pub end: Timestamp,
+    pub withdrawn: Uint128,
+}
+
+#[cw_serde]
+pub struct PendingWithdrawal {
+    pub id: u64,
+    /// token and amount to withdraw
+    pub token: CheckedTokenInfo,
+    pub recipient: String,
+    pub release_time: Timestamp,
+}
+
+#[cw_serde]
+pub struct UnbondingEntry {
+    pub id: u64,
+    pub validator: String,
+    pub amount: Coin,
+    pub completion_time: Timestamp,
+}
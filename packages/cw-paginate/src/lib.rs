@@ -364,6 +364,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(unknown_lints, clippy::unnecessary_fallible_conversions)]
     fn snapshot_pagination_keys() {
         let mut deps = mock_dependencies();
         let env = mock_env();
@@ -1,5 +1,9 @@
+pub mod proto;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod testing;
+
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Decimal, Timestamp, Uint128};
 use thiserror::Error;
 
 #[cw_serde]
@@ -8,14 +12,14 @@ pub enum ExecuteMsg {
         validator_addr: String,
     },
     Undelegate {
-        amount: Uint128,
+        amount: StakeAmount,
         validator_addr: String,
     },
     Claim {},
     Redelagate {
         from_validator_addr: String,
         to_validator_addr: String,
-        amount: Uint128,
+        amount: StakeAmount,
     },
     UndelegateAll {},
     TransferVotePower {
@@ -32,6 +36,7 @@ pub enum ExecuteMsg {
     DearLeaderVote {
         proposal_id: u64,
         vote_option: u64,
+        rationale: Option<VoteRationale>,
     },
     RegisterDearLeader {
         new_dear_leader_addr: String,
@@ -42,12 +47,315 @@ pub enum ExecuteMsg {
         proposal_id: u64,
         vote_option: u64,
     },
+    SyncAccountStake {},
+    SetDearLeaderProfile {
+        profile: DearLeaderProfile,
+    },
+    AnnounceDearLeaderVote {
+        proposal_id: u64,
+        vote_option: u64,
+        rationale: Option<VoteRationale>,
+    },
+    OptOutOfLeaderVote {
+        proposal_id: u64,
+    },
+    RetireDearLeader {},
+    SetVoteFallback {
+        fallback: VoteFallback,
+    },
+    FollowDearLeader {
+        dear_leader_addr: String,
+        categories: Option<Vec<String>>,
+    },
+    UnfollowDearLeader {
+        dear_leader_addr: String,
+    },
+    UpdateDearLeaderAccountOwner {
+        previous_owner: String,
+        new_owner: String,
+    },
+}
+
+// amount to take out of a delegation, either an exact amount or everything available
+#[cw_serde]
+pub enum StakeAmount {
+    Exact(Uint128),
+    Max {},
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    GetValidatorPolicy {
+        validator_addr: String,
+    },
+    GetProxyAllowList {},
+    GetDearLeaderDelegatores {
+        dear_leader_addr: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    GetAccountStake {
+        user_account_addr: String,
+    },
+    GetDearLeaderPower {
+        dear_leader_addr: String,
+    },
+    GetDearLeaderVotes {
+        dear_leader_addr: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct GetValidatorPolicyResponse {
+    pub max_commission: Option<Decimal>,
+    pub is_allowed: bool,
+}
+
+#[cw_serde]
+pub struct GetProxyAllowListResponse {
+    pub type_urls: Vec<String>,
+    pub contracts: Vec<String>,
+}
+
+#[cw_serde]
+pub struct GetDearLeaderDelegatoresResponse {
+    pub delegatores_list: Option<Vec<String>>,
+}
+
+#[cw_serde]
+pub struct GetAccountStakeResponse {
+    pub stake: Uint128,
+}
+
+#[cw_serde]
+pub struct GetDearLeaderPowerResponse {
+    pub power: Uint128,
+}
+
+#[cw_serde]
+pub struct GetLeaderVoteResponse {
+    pub vote: Option<DearLeaderVote>,
+}
+
+#[cw_serde]
+pub struct GetDearLeaderVotesResponse {
+    pub votes: Vec<DearLeaderVote>,
+}
+
+// vote a dear leader cast on behalf of its delegators
+#[cw_serde]
+pub struct DearLeaderVote {
+    pub proposal_id: u64,
+    pub vote_option: u64,
+    pub rationale: Option<VoteRationale>,
+}
+
+// explanation a dear leader publishes with a vote, either inline or as a link to a document
+#[cw_serde]
+pub enum VoteRationale {
+    Text { text: String },
+    // the hash lets delegators check the document was not changed after the vote
+    Uri { uri: String, hash: String },
+}
+
+impl VoteRationale {
+    pub fn is_valid(&self) -> bool {
+        match self {
+            VoteRationale::Text { text } => {
+                !text.trim().is_empty() && text.len() <= MAX_TEXT_LENGTH
+            }
+            VoteRationale::Uri { uri, hash } => {
+                !uri.is_empty()
+                    && uri.len() <= MAX_URL_LENGTH
+                    && !hash.is_empty()
+                    && hash.len() <= MAX_NAME_LENGTH * 2
+            }
+        }
+    }
+}
+
+// what a user account votes when its dear leader doesn't vote on a proposal
+#[cw_serde]
+pub enum VoteFallback {
+    Abstain {},
+    // votes like another dear leader, if that one voted
+    FollowLeader { dear_leader_addr: String },
+    Nothing {},
 }
 
 #[cw_serde]
 pub enum InstantiateMsg {
-    InstantiateUserAccountMsg {},
-    InstatiateDearLeaderAccount {},
+    InstantiateUserAccountMsg {
+        boss_addr: String,
+        assembly_addr: String,
+        vesting: Option<VestingParams>,
+    },
+    InstatiateDearLeaderAccount {
+        owner_addr: String,
+        assembly_addr: String,
+        /// factory keeping the owner index, told about ownership transfers
+        accounts_factory_addr: Option<String>,
+    },
+}
+
+// tokens a grantor locks in a user account, staked at creation and released to the boss over time
+#[cw_serde]
+pub struct VestingParams {
+    pub grantor: String,
+    pub schedule: VestingSchedule,
+    pub validator_addr: String,
+    // lets the grantor take back the unvested part
+    pub clawback: bool,
+}
+
+#[cw_serde]
+pub enum VestingSchedule {
+    // nothing vests before the cliff, then the total vests linearly from start to end
+    CliffLinear {
+        total: Uint128,
+        start: Timestamp,
+        cliff: Timestamp,
+        end: Timestamp,
+    },
+    // each step vests its amount once its time is reached
+    Steps {
+        steps: Vec<VestingStep>,
+    },
+}
+
+#[cw_serde]
+pub struct VestingStep {
+    pub time: Timestamp,
+    pub amount: Uint128,
+}
+
+impl VestingSchedule {
+    pub fn is_valid(&self) -> bool {
+        match self {
+            VestingSchedule::CliffLinear {
+                total,
+                start,
+                cliff,
+                end,
+            } => !total.is_zero() && start <= cliff && cliff <= end && start < end,
+            VestingSchedule::Steps { steps } => {
+                !steps.is_empty() && steps.iter().all(|step| !step.amount.is_zero())
+            }
+        }
+    }
+
+    pub fn total(&self) -> Uint128 {
+        match self {
+            VestingSchedule::CliffLinear { total, .. } => *total,
+            VestingSchedule::Steps { steps } => steps.iter().map(|step| step.amount).sum(),
+        }
+    }
+
+    pub fn vested(&self, time: Timestamp) -> Uint128 {
+        match self {
+            VestingSchedule::CliffLinear {
+                total,
+                start,
+                cliff,
+                end,
+            } => {
+                if time < *cliff {
+                    Uint128::zero()
+                } else if time >= *end {
+                    *total
+                } else {
+                    total.multiply_ratio(
+                        time.seconds() - start.seconds(),
+                        end.seconds() - start.seconds(),
+                    )
+                }
+            }
+            VestingSchedule::Steps { steps } => steps
+                .iter()
+                .filter(|step| step.time <= time)
+                .map(|step| step.amount)
+                .sum(),
+        }
+    }
+}
+
+// public information delegators see when choosing a dear leader
+#[cw_serde]
+pub struct DearLeaderProfile {
+    pub name: String,
+    pub description: Option<String>,
+    pub website: Option<String>,
+    pub socials: Vec<SocialHandle>,
+    pub logo_url: Option<String>,
+    pub voting_philosophy: Option<String>,
+}
+
+#[cw_serde]
+pub struct SocialHandle {
+    pub platform: String,
+    pub handle: String,
+}
+
+const MAX_NAME_LENGTH: usize = 64;
+const MAX_URL_LENGTH: usize = 256;
+const MAX_TEXT_LENGTH: usize = 2048;
+const MAX_SOCIALS: usize = 5;
+
+impl DearLeaderProfile {
+    pub fn is_valid(&self) -> bool {
+        let fits = |text: &Option<String>, max: usize| {
+            text.as_ref().map_or(true, |text| text.len() <= max)
+        };
+
+        !self.name.trim().is_empty()
+            && self.name.len() <= MAX_NAME_LENGTH
+            && fits(&self.description, MAX_TEXT_LENGTH)
+            && fits(&self.website, MAX_URL_LENGTH)
+            && fits(&self.logo_url, MAX_URL_LENGTH)
+            && fits(&self.voting_philosophy, MAX_TEXT_LENGTH)
+            && self.socials.len() <= MAX_SOCIALS
+            && self.socials.iter().all(|social| {
+                !social.platform.is_empty()
+                    && !social.handle.is_empty()
+                    && social.platform.len() <= MAX_NAME_LENGTH
+                    && social.handle.len() <= MAX_NAME_LENGTH
+            })
+    }
+}
+
+// status of a governance proposal, in the order of the x/gov enum
+#[cw_serde]
+pub enum ProposalStatus {
+    Unspecified,
+    DepositPeriod,
+    VotingPeriod,
+    Passed,
+    Rejected,
+    Failed,
+}
+
+impl ProposalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProposalStatus::Unspecified => "unspecified",
+            ProposalStatus::DepositPeriod => "deposit_period",
+            ProposalStatus::VotingPeriod => "voting_period",
+            ProposalStatus::Passed => "passed",
+            ProposalStatus::Rejected => "rejected",
+            ProposalStatus::Failed => "failed",
+        }
+    }
+}
+
+// signed difference between two stake figures, as shown in sync events
+pub fn stake_delta(previous: Uint128, current: Uint128) -> String {
+    if current >= previous {
+        (current - previous).to_string()
+    } else {
+        format!("-{}", previous - current)
+    }
 }
 
 #[derive(Error, Debug)]
@@ -0,0 +1,276 @@
+use cosmwasm_std::{
+    to_vec, Binary, ContractResult, Empty, QuerierWrapper, QueryRequest, StdError, StdResult,
+    SystemResult, Timestamp,
+};
+
+use crate::ProposalStatus;
+
+// Stargate queries answer with protobuf encoded data, so we only decode the few fields we need
+// instead of pulling the whole cosmos-sdk proto definitions into the contracts.
+
+const WIRE_VARINT: u64 = 0;
+const WIRE_FIXED64: u64 = 1;
+const WIRE_LEN: u64 = 2;
+const WIRE_FIXED32: u64 = 5;
+
+pub const STAKING_PARAMS_PATH: &str = "/cosmos.staking.v1beta1.Query/Params";
+pub const GOV_PROPOSAL_PATH: &str = "/cosmos.gov.v1beta1.Query/Proposal";
+
+/// Sends a stargate query and returns the raw protobuf response.
+pub fn query_stargate(querier: &QuerierWrapper, path: &str, data: Binary) -> StdResult<Binary> {
+    let request: QueryRequest<Empty> = QueryRequest::Stargate {
+        path: path.to_string(),
+        data,
+    };
+
+    match querier.raw_query(&to_vec(&request)?) {
+        SystemResult::Err(system_err) => Err(StdError::generic_err(format!(
+            "Querier system error: {}",
+            system_err
+        ))),
+        SystemResult::Ok(ContractResult::Err(contract_err)) => Err(StdError::generic_err(format!(
+            "Querier contract error: {}",
+            contract_err
+        ))),
+        SystemResult::Ok(ContractResult::Ok(value)) => Ok(value),
+    }
+}
+
+/// Returns the unbonding time of the staking module, in seconds.
+pub fn query_unbonding_time(querier: &QuerierWrapper) -> StdResult<u64> {
+    let response = query_stargate(querier, STAKING_PARAMS_PATH, Binary::default())?;
+    decode_unbonding_time(&response)
+}
+
+// a missing duration would make unbondings look matured right away, so it is an error
+fn decode_unbonding_time(response: &[u8]) -> StdResult<u64> {
+    // QueryParamsResponse { params: Params { unbonding_time: Duration { seconds } } }
+    let params = required(bytes_field(response, 1)?, "params")?;
+    let unbonding_time = required(bytes_field(params, 1)?, "unbonding_time")?;
+    required(varint_field(unbonding_time, 1)?, "unbonding_time.seconds")
+}
+
+/// The fields of a governance proposal the contracts use.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GovProposal {
+    pub proposal_id: u64,
+    /// type url of the content, e.g. `/cosmos.gov.v1beta1.TextProposal`
+    pub content_type: String,
+    pub title: String,
+    pub status: ProposalStatus,
+    pub voting_end_time: Timestamp,
+}
+
+/// Queries a governance proposal, fails if it doesn't exist.
+pub fn query_gov_proposal(querier: &QuerierWrapper, proposal_id: u64) -> StdResult<GovProposal> {
+    let request = encode_varint_field(1, proposal_id);
+    let response = query_stargate(querier, GOV_PROPOSAL_PATH, request.into())?;
+    decode_gov_proposal(proposal_id, &response)
+}
+
+// a missing voting end would let votes and fallbacks run at any time, so it is an error.
+// Scalars proto3 leaves out for their zero value, like nanos or the status, default
+fn decode_gov_proposal(proposal_id: u64, response: &[u8]) -> StdResult<GovProposal> {
+    // QueryProposalResponse { proposal: Proposal { content: Any { type_url, value }, status,
+    // voting_end_time: Timestamp { seconds, nanos } } }
+    let proposal = required(bytes_field(response, 1)?, "proposal")?;
+    let content = required(bytes_field(proposal, 2)?, "proposal.content")?;
+    let type_url = required(bytes_field(content, 1)?, "content.type_url")?;
+    // every content type has the title as its first field
+    let value = bytes_field(content, 2)?.unwrap_or_default();
+    let title = bytes_field(value, 1)?.unwrap_or_default();
+    let status = varint_field(proposal, 3)?.unwrap_or_default();
+    let voting_end_time = required(bytes_field(proposal, 9)?, "proposal.voting_end_time")?;
+    let seconds = required(varint_field(voting_end_time, 1)?, "voting_end_time.seconds")?;
+    let nanos = varint_field(voting_end_time, 2)?.unwrap_or_default();
+
+    Ok(GovProposal {
+        proposal_id,
+        content_type: String::from_utf8(type_url.to_vec()).map_err(StdError::invalid_utf8)?,
+        title: String::from_utf8(title.to_vec()).map_err(StdError::invalid_utf8)?,
+        status: match status {
+            1 => ProposalStatus::DepositPeriod,
+            2 => ProposalStatus::VotingPeriod,
+            3 => ProposalStatus::Passed,
+            4 => ProposalStatus::Rejected,
+            5 => ProposalStatus::Failed,
+            _ => ProposalStatus::Unspecified,
+        },
+        voting_end_time: Timestamp::from_seconds(seconds).plus_nanos(nanos),
+    })
+}
+
+/// Returns the last occurrence of a length delimited field (bytes, string or message).
+pub fn bytes_field(buf: &[u8], field_number: u64) -> StdResult<Option<&[u8]>> {
+    let mut found = None;
+    let mut pos = 0;
+    while pos < buf.len() {
+        let (number, wire_type) = read_key(buf, &mut pos)?;
+        match wire_type {
+            WIRE_LEN => {
+                let value = read_len(buf, &mut pos)?;
+                if number == field_number {
+                    found = Some(value);
+                }
+            }
+            _ => skip(buf, &mut pos, wire_type)?,
+        }
+    }
+    Ok(found)
+}
+
+/// Returns the last occurrence of a varint field (integers, bools and enums).
+pub fn varint_field(buf: &[u8], field_number: u64) -> StdResult<Option<u64>> {
+    let mut found = None;
+    let mut pos = 0;
+    while pos < buf.len() {
+        let (number, wire_type) = read_key(buf, &mut pos)?;
+        match wire_type {
+            WIRE_VARINT => {
+                let value = read_varint(buf, &mut pos)?;
+                if number == field_number {
+                    found = Some(value);
+                }
+            }
+            _ => skip(buf, &mut pos, wire_type)?,
+        }
+    }
+    Ok(found)
+}
+
+/// Fails if a field the caller can't do without is missing from the response.
+pub fn required<T>(field: Option<T>, name: &str) -> StdResult<T> {
+    field.ok_or_else(|| StdError::parse_err("protobuf", format!("missing field {}", name)))
+}
+
+/// Encodes a varint field, used to build stargate query requests.
+pub fn encode_varint_field(field_number: u64, value: u64) -> Vec<u8> {
+    let mut buf = vec![];
+    write_varint(&mut buf, field_number << 3 | WIRE_VARINT);
+    write_varint(&mut buf, value);
+    buf
+}
+
+/// Encodes a length delimited field, used to build stargate query requests and test responses.
+pub fn encode_bytes_field(field_number: u64, value: &[u8]) -> Vec<u8> {
+    let mut buf = vec![];
+    write_varint(&mut buf, field_number << 3 | WIRE_LEN);
+    write_varint(&mut buf, value.len() as u64);
+    buf.extend_from_slice(value);
+    buf
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn read_varint(buf: &[u8], pos: &mut usize) -> StdResult<u64> {
+    let mut value: u64 = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = *buf
+            .get(*pos)
+            .ok_or_else(|| StdError::parse_err("protobuf", "unexpected end of varint"))?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte < 0x80 {
+            return Ok(value);
+        }
+    }
+    Err(StdError::parse_err("protobuf", "varint too long"))
+}
+
+fn read_key(buf: &[u8], pos: &mut usize) -> StdResult<(u64, u64)> {
+    let key = read_varint(buf, pos)?;
+    Ok((key >> 3, key & 0x7))
+}
+
+fn read_len<'a>(buf: &'a [u8], pos: &mut usize) -> StdResult<&'a [u8]> {
+    let len = read_varint(buf, pos)? as usize;
+    let end = pos
+        .checked_add(len)
+        .filter(|end| *end <= buf.len())
+        .ok_or_else(|| StdError::parse_err("protobuf", "length out of bounds"))?;
+    let value = &buf[*pos..end];
+    *pos = end;
+    Ok(value)
+}
+
+fn skip(buf: &[u8], pos: &mut usize, wire_type: u64) -> StdResult<()> {
+    let size = match wire_type {
+        WIRE_VARINT => return read_varint(buf, pos).map(|_| ()),
+        WIRE_LEN => return read_len(buf, pos).map(|_| ()),
+        WIRE_FIXED64 => 8,
+        WIRE_FIXED32 => 4,
+        _ => return Err(StdError::parse_err("protobuf", "unsupported wire type")),
+    };
+    if *pos + size > buf.len() {
+        return Err(StdError::parse_err("protobuf", "length out of bounds"));
+    }
+    *pos += size;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::encode_gov_proposal;
+
+    #[test]
+    fn decode_nested_fields() {
+        // Params { unbonding_time: Duration { seconds: 1814400 }, max_validators: 150 }
+        let duration = encode_varint_field(1, 1_814_400);
+        let mut params = encode_bytes_field(1, &duration);
+        params.extend(encode_varint_field(2, 150));
+        let response = encode_bytes_field(1, &params);
+
+        let params = bytes_field(&response, 1).unwrap().unwrap();
+        assert_eq!(varint_field(params, 2).unwrap(), Some(150));
+        let duration = bytes_field(params, 1).unwrap().unwrap();
+        assert_eq!(varint_field(duration, 1).unwrap(), Some(1_814_400));
+        assert_eq!(varint_field(duration, 2).unwrap(), None);
+
+        // truncated messages are rejected
+        bytes_field(&response[..response.len() - 1], 1).unwrap_err();
+    }
+
+    #[test]
+    fn missing_unbonding_time_is_an_error() {
+        let duration = encode_varint_field(1, 1_814_400);
+        let params = encode_bytes_field(1, &duration);
+        assert_eq!(
+            decode_unbonding_time(&encode_bytes_field(1, &params)).unwrap(),
+            1_814_400
+        );
+
+        // params without unbonding_time, and a response without params
+        let params = encode_varint_field(2, 150);
+        decode_unbonding_time(&encode_bytes_field(1, &params)).unwrap_err();
+        decode_unbonding_time(&[]).unwrap_err();
+    }
+
+    #[test]
+    fn missing_voting_end_is_an_error() {
+        let proposal = GovProposal {
+            proposal_id: 1,
+            content_type: "/cosmos.gov.v1beta1.TextProposal".to_string(),
+            title: "Proposal 1".to_string(),
+            status: ProposalStatus::VotingPeriod,
+            voting_end_time: Timestamp::from_seconds(1_000),
+        };
+        assert_eq!(
+            decode_gov_proposal(1, &encode_gov_proposal(&proposal)).unwrap(),
+            proposal
+        );
+
+        // a proposal without voting_end_time, and a response without proposal
+        let content = encode_bytes_field(1, proposal.content_type.as_bytes());
+        let mut without_end = encode_bytes_field(2, &content);
+        without_end.extend(encode_varint_field(3, 2));
+        decode_gov_proposal(1, &encode_bytes_field(1, &without_end)).unwrap_err();
+        decode_gov_proposal(1, &[]).unwrap_err();
+    }
+}
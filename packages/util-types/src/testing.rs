@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    from_slice, ContractResult, Empty, OwnedDeps, Querier, QuerierResult, QueryRequest,
+    SystemResult,
+};
+
+use crate::proto::{
+    encode_bytes_field, encode_varint_field, varint_field, GovProposal, GOV_PROPOSAL_PATH,
+};
+
+/// Encodes a QueryProposalResponse, the inverse of `query_gov_proposal`.
+pub fn encode_gov_proposal(proposal: &GovProposal) -> Vec<u8> {
+    let value = encode_bytes_field(1, proposal.title.as_bytes());
+    let mut content = encode_bytes_field(1, proposal.content_type.as_bytes());
+    content.extend(encode_bytes_field(2, &value));
+    let mut voting_end_time = encode_varint_field(1, proposal.voting_end_time.seconds());
+    voting_end_time.extend(encode_varint_field(
+        2,
+        proposal.voting_end_time.subsec_nanos(),
+    ));
+
+    let mut encoded = encode_varint_field(1, proposal.proposal_id);
+    encoded.extend(encode_bytes_field(2, &content));
+    encoded.extend(encode_varint_field(3, proposal.status.clone() as u64));
+    encoded.extend(encode_bytes_field(9, &voting_end_time));
+    encode_bytes_field(1, &encoded)
+}
+
+/// MockQuerier doesn't support stargate queries, this one answers the gov proposal query
+/// with the proposals set by the test and sends everything else to `base`.
+pub struct MockGovQuerier {
+    pub base: MockQuerier,
+    proposals: HashMap<u64, GovProposal>,
+}
+
+impl MockGovQuerier {
+    pub fn new() -> Self {
+        MockGovQuerier {
+            base: MockQuerier::new(&[]),
+            proposals: HashMap::new(),
+        }
+    }
+
+    /// Adds the proposal, or replaces it to change its status.
+    pub fn set_proposal(&mut self, proposal: GovProposal) {
+        self.proposals.insert(proposal.proposal_id, proposal);
+    }
+}
+
+impl Default for MockGovQuerier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Querier for MockGovQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        match from_slice::<QueryRequest<Empty>>(bin_request) {
+            Ok(QueryRequest::Stargate { path, data }) if path == GOV_PROPOSAL_PATH => {
+                let proposal = varint_field(&data, 1)
+                    .ok()
+                    .flatten()
+                    .and_then(|proposal_id| self.proposals.get(&proposal_id));
+                match proposal {
+                    Some(proposal) => {
+                        SystemResult::Ok(ContractResult::Ok(encode_gov_proposal(proposal).into()))
+                    }
+                    None => SystemResult::Ok(ContractResult::Err("proposal not found".to_string())),
+                }
+            }
+            _ => self.base.raw_query(bin_request),
+        }
+    }
+}
+
+/// Like `mock_dependencies`, with a querier that knows about gov proposals.
+pub fn mock_dependencies_with_gov() -> OwnedDeps<MockStorage, MockApi, MockGovQuerier> {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: MockGovQuerier::new(),
+        custom_query_type: PhantomData,
+    }
+}